@@ -11,12 +11,15 @@
 //!
 //! Note: This endpoint requires a production API key
 
-use repliers_beta::{config::Config, RepliersClient};
+mod common;
+
+use common::ExampleConfig;
+use repliers_beta::RepliersClient;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration from config.toml
-    let config = Config::load_or_exit();
+    let config = ExampleConfig::load_or_exit();
 
     let client = RepliersClient::from_env()?;
 
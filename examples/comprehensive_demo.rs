@@ -19,8 +19,11 @@
 //!   This example reads parameters from config.toml under the [demo] section
 //!   Copy config.toml.example to config.toml and adjust values as needed
 
+mod common;
+
+use common::ExampleConfig;
 use repliers_beta::{
-    config::Config, DeletedListingsQuery, ListingSearchRequest, RepliersClient,
+    DeletedListingsQuery, Listing, ListingDetail, ListingSearchRequest, RepliersClient,
     SimilarListingsRequest,
 };
 use serde::Serialize;
@@ -53,7 +56,7 @@ struct SearchResults {
     total_count: u32,
     num_pages: u32,
     page_size: u32,
-    listings: Vec<serde_json::Value>,
+    listings: Vec<Listing>,
 }
 
 #[derive(Serialize)]
@@ -69,7 +72,7 @@ struct SearchQuery {
 #[derive(Serialize)]
 struct ListingDetails {
     mls_number: String,
-    data: serde_json::Value,
+    data: ListingDetail,
 }
 
 #[derive(Serialize)]
@@ -78,7 +81,7 @@ struct SimilarResults {
     search_radius_km: f64,
     price_range: f64,
     count: u32,
-    listings: Vec<serde_json::Value>,
+    listings: Vec<Listing>,
 }
 
 #[derive(Serialize)]
@@ -128,7 +131,7 @@ fn format_price(price: f64) -> String {
     let len = chars.len();
 
     for (i, ch) in chars.iter().enumerate() {
-        if i > 0 && (len - i) % 3 == 0 {
+        if i > 0 && (len - i).is_multiple_of(3) {
             result.push(',');
         }
         result.push(*ch);
@@ -143,7 +146,7 @@ fn format_number(num: f64) -> String {
     let len = chars.len();
 
     for (i, ch) in chars.iter().enumerate() {
-        if i > 0 && (len - i) % 3 == 0 {
+        if i > 0 && (len - i).is_multiple_of(3) {
             result.push(',');
         }
         result.push(*ch);
@@ -151,20 +154,15 @@ fn format_number(num: f64) -> String {
     result
 }
 
-fn extract_listing_info(listing: &serde_json::Value) -> (String, String, String) {
-    let mls = listing["mlsNumber"]
-        .as_str()
-        .unwrap_or("N/A")
-        .to_string();
-    let address = listing["address"]["streetName"]
-        .as_str()
-        .or(listing["address"]["full"].as_str())
-        .unwrap_or("Address not available");
-    let price = listing["listPrice"]
-        .as_f64()
+fn extract_listing_info(listing: &Listing) -> (String, String, String) {
+    let address = listing
+        .street_address()
+        .unwrap_or_else(|| "Address not available".to_string());
+    let price = listing
+        .list_price
         .map(format_price)
         .unwrap_or_else(|| "N/A".to_string());
-    (mls, address.to_string(), price)
+    (listing.mls_number.clone(), address, price)
 }
 
 #[tokio::main]
@@ -172,7 +170,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let start_time = Instant::now();
 
     // Load configuration
-    let config = Config::load_or_exit();
+    let config = ExampleConfig::load_or_exit();
     let cfg = &config.demo;
 
     // Initialize API client
@@ -253,29 +251,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n✓ Listing details retrieved successfully!");
 
     // Extract key information
-    if let Some(address) = listing_details["address"].as_object() {
-        if let Some(street) = address.get("streetName").and_then(|v| v.as_str()) {
-            print_stat("  Address:", street);
-        }
-        if let Some(city) = address.get("city").and_then(|v| v.as_str()) {
-            print_stat("  City:", city);
-        }
+    if let Some(street) = listing_details.street_address() {
+        print_stat("  Address:", &street);
+    }
+    if let Some(city) = listing_details.city() {
+        print_stat("  City:", city);
     }
-    if let Some(price) = listing_details["listPrice"].as_f64() {
+    if let Some(price) = listing_details.list_price {
         print_stat("  List Price:", &format_price(price));
     }
-    if let Some(beds) = listing_details["bedrooms"].as_u64() {
+    if let Some(beds) = listing_details.bedrooms() {
         print_stat("  Bedrooms:", &beds.to_string());
     }
-    if let Some(baths) = listing_details["bathrooms"].as_u64() {
+    if let Some(baths) = listing_details.bathrooms() {
         print_stat("  Bathrooms:", &baths.to_string());
     }
-    if let Some(sqft) = listing_details["squareFeet"].as_f64() {
-        print_stat("  Square Feet:", &format_number(sqft));
-    }
-    if let Some(prop_type) = listing_details["propertyType"].as_str() {
-        print_stat("  Property Type:", prop_type);
+    if let Some(details) = &listing_details.details {
+        if let Some(sqft) = &details.sqft {
+            // Some boards report a range (e.g. "1500-2000") rather than a number
+            let sqft = sqft
+                .parse::<f64>()
+                .map(format_number)
+                .unwrap_or_else(|_| sqft.clone());
+            print_stat("  Square Feet:", &sqft);
+        }
+        if let Some(prop_type) = &details.property_type {
+            print_stat("  Property Type:", prop_type);
+        }
     }
+    print_stat(
+        "  Comparables:",
+        &listing_details.comparables().len().to_string(),
+    );
+    print_stat(
+        "  History Entries:",
+        &listing_details.history().len().to_string(),
+    );
 
     // ========================================================================
     // STEP 3: Find Similar Listings
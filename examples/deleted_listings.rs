@@ -9,12 +9,15 @@
 //!   This example reads parameters from config.toml
 //!   Copy config.toml.example to config.toml and adjust values as needed
 
-use repliers_beta::{config::Config, DeletedListingsQuery, RepliersClient};
+mod common;
+
+use common::ExampleConfig;
+use repliers_beta::{DeletedListingsQuery, RepliersClient};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration from config.toml
-    let config = Config::load_or_exit();
+    let config = ExampleConfig::load_or_exit();
 
     let client = RepliersClient::from_env()?;
 
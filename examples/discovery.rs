@@ -19,7 +19,9 @@
 //!   This example reads analysis parameters from config.toml
 //!   Copy config.toml.example to config.toml and adjust values as needed
 
-use repliers_beta::{ListingSearchRequest, RepliersClient};
+mod common;
+
+use repliers_beta::{Listing, ListingSearchRequest, RepliersClient};
 use serde::Serialize;
 use std::collections::HashMap;
 
@@ -31,7 +33,7 @@ struct DiscoveryReport {
     property_types: HashMap<String, u32>,
     status_distribution: HashMap<String, u32>,
     price_statistics: PriceStats,
-    example_listings: Vec<serde_json::Value>,
+    example_listings: Vec<Listing>,
     sample_queries: Vec<QueryExample>,
 }
 
@@ -87,7 +89,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 2. Discover top cities
     eprintln!("🏙️  Analyzing top cities...");
     // Load configuration from config.toml
-    let config = repliers_beta::config::Config::load_or_exit();
+    let config = common::ExampleConfig::load_or_exit();
     let test_cities = &config.discovery.test_cities;
 
     let mut city_stats = Vec::new();
@@ -103,7 +105,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 // Calculate average price from first page
                 let avg_price = if !response.listings.is_empty() {
                     let prices: Vec<f64> = response.listings.iter()
-                        .filter_map(|l| l.get("listPrice")?.as_f64())
+                        .filter_map(|l| l.list_price)
                         .collect();
                     if !prices.is_empty() {
                         Some(prices.iter().sum::<f64>() / prices.len() as f64)
@@ -125,7 +127,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Sort by count and take top 5
-    city_stats.sort_by(|a, b| b.count.cmp(&a.count));
+    city_stats.sort_by_key(|c| std::cmp::Reverse(c.count));
     let top_cities: Vec<CityStats> = city_stats.into_iter().take(5).collect();
     eprintln!("   Top city: {} with {} listings\n", top_cities[0].city, top_cities[0].count);
 
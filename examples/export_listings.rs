@@ -1,13 +1,19 @@
-//! Example: Export listings to JSON file
+//! Example: Export listings to JSON (and optionally CSV, GeoJSON and NDJSON) files
 //!
 //! Demonstrates how to fetch property listings and export them to a local JSON file
-//! for offline analysis.
+//! for offline analysis. Set `csv_file` in the `[export]` section to also write a CSV
+//! file with the columns given by `csv_columns`, `geojson_file` to write a GeoJSON
+//! `FeatureCollection` for mapping tools, and `ndjson_file` to write one listing per
+//! line (gzip-compressed when the name ends in `.gz`). Results are fetched page by page
+//! through an auto-paginating stream, up to `max_results` listings. When the output
+//! file already exists, the new results are compared with it and price, status and
+//! other changes are reported.
 //!
 //! Usage:
 //!   cargo run --example export_listings
 //!
 //! Expected output:
-//!   Fetching 20 active properties in Toronto...
+//!   Fetching up to 20 active properties in Toronto...
 //!   Successfully retrieved X listings
 //!   Exported listings to: toronto_listings.json
 //!
@@ -15,23 +21,33 @@
 //!   This example reads parameters from config.toml
 //!   Copy config.toml.example to config.toml and adjust values as needed
 
-use repliers_beta::{config::Config, ListingSearchRequest, RepliersClient};
+mod common;
+
+use common::ExampleConfig;
+use futures::TryStreamExt;
+use repliers_beta::diff::{DiffOptions, ListingChange, Snapshot};
+use repliers_beta::export::csv::{ColumnSpec, CsvExporter};
+use repliers_beta::export::geojson::{GeoJsonExporter, GeoJsonOptions};
+use repliers_beta::export::ndjson::NdjsonWriter;
+use repliers_beta::{Listing, ListingSearchRequest, RepliersClient, SearchStreamOptions};
 use std::fs::File;
 use std::io::Write;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration from config.toml
-    let config = Config::load_or_exit();
+    let config = ExampleConfig::load_or_exit();
 
     // Load API key from environment (.env file or REPLIERS_API_KEY env var)
     let client = RepliersClient::from_env()?;
 
     let cfg = &config.export;
 
+    let max_results = cfg.max_results.unwrap_or(cfg.results_per_page as usize);
+
     println!(
-        "Fetching {} active properties in {}...",
-        cfg.results_per_page, cfg.city
+        "Fetching up to {} active properties in {}...",
+        max_results, cfg.city
     );
 
     // Build search request for active listings
@@ -43,36 +59,118 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         ..Default::default()
     };
 
-    // Execute search
-    match client.search_listings(request).await {
-        Ok(results) => {
-            println!("Successfully retrieved {} listings", results.listings.len());
-            println!(
-                "Total available: {} listings across {} pages",
-                results.count, results.num_pages
-            );
-
-            // Convert to pretty JSON
-            let json_output = serde_json::to_string_pretty(&results.listings)?;
-
-            // Write to file
-            let mut file = File::create(&cfg.output_file)?;
-            file.write_all(json_output.as_bytes())?;
-
-            println!("\nExported listings to: {}", cfg.output_file);
-            println!("File size: {} bytes", json_output.len());
-
-            // Show preview of first listing
-            if !results.listings.is_empty() {
-                println!("\nPreview of first listing:");
-                println!("{}", serde_json::to_string_pretty(&results.listings[0])?);
-            }
-        }
+    let options = SearchStreamOptions {
+        max_results: Some(max_results),
+        ..Default::default()
+    };
+
+    // Execute search, walking as many pages as needed
+    let listings: Vec<Listing> = match client
+        .search_listings_stream_with(request, options)
+        .try_collect()
+        .await
+    {
+        Ok(listings) => listings,
         Err(e) => {
             eprintln!("Error fetching listings: {}", e);
             return Err(e.into());
         }
+    };
+
+    println!("Successfully retrieved {} listings", listings.len());
+
+    // Compare with the previous export before overwriting it
+    if let Ok(previous) = Snapshot::from_json_file(&cfg.output_file) {
+        let current = Snapshot::new(listings.clone());
+        print_changes(&previous.diff(&current, &DiffOptions::default()));
+    }
+
+    // Convert to pretty JSON
+    let json_output = serde_json::to_string_pretty(&listings)?;
+
+    // Write to file
+    let mut file = File::create(&cfg.output_file)?;
+    file.write_all(json_output.as_bytes())?;
+
+    println!("\nExported listings to: {}", cfg.output_file);
+    println!("File size: {} bytes", json_output.len());
+
+    if let Some(csv_file) = &cfg.csv_file {
+        let spec = match &cfg.csv_columns {
+            Some(columns) => ColumnSpec::parse(columns)?,
+            None => ColumnSpec::default(),
+        };
+        let mut exporter = CsvExporter::create(csv_file, spec)?;
+        let rows = exporter.write_all(&listings)?;
+        exporter.finish()?;
+        println!("Exported {} CSV rows to: {}", rows, csv_file);
+    }
+
+    if let Some(geojson_file) = &cfg.geojson_file {
+        let mut exporter = GeoJsonExporter::create(geojson_file, GeoJsonOptions::default())?;
+        let features = exporter.write_all(&listings)?;
+        let skipped = exporter.report().skipped.clone();
+        exporter.finish()?;
+        println!(
+            "Exported {} GeoJSON features to: {}",
+            features, geojson_file
+        );
+        if !skipped.is_empty() {
+            println!("  Skipped without coordinates: {}", skipped.join(", "));
+        }
+    }
+
+    if let Some(ndjson_file) = &cfg.ndjson_file {
+        let mut writer = NdjsonWriter::create(ndjson_file)?;
+        let records = writer.write_all(&listings)?;
+        writer.finish()?;
+        println!("Exported {} NDJSON records to: {}", records, ndjson_file);
+    }
+
+    // Show preview of first listing
+    if let Some(first) = listings.first() {
+        println!("\nPreview of first listing:");
+        println!("{}", serde_json::to_string_pretty(first)?);
     }
 
     Ok(())
 }
+
+/// Prints a summary of the changes since the previous export
+fn print_changes(changes: &[ListingChange]) {
+    println!("\nChanges since previous export: {}", changes.len());
+    for change in changes {
+        match change {
+            ListingChange::New { listing } => println!("  + {} (new)", listing.mls_number),
+            ListingChange::Removed { listing } => {
+                println!("  - {} (no longer returned)", listing.mls_number)
+            }
+            ListingChange::PriceChanged {
+                mls_number,
+                old,
+                new,
+                pct,
+            } => match pct {
+                Some(pct) => println!(
+                    "  $ {}: ${:.0} -> ${:.0} ({:+.1}%)",
+                    mls_number, old, new, pct
+                ),
+                None => println!("  $ {}: ${:.0} -> ${:.0}", mls_number, old, new),
+            },
+            ListingChange::StatusChanged {
+                mls_number,
+                old_last_status,
+                new_last_status,
+                ..
+            } => println!(
+                "  * {}: {} -> {}",
+                mls_number,
+                old_last_status.as_deref().unwrap_or("?"),
+                new_last_status.as_deref().unwrap_or("?")
+            ),
+            ListingChange::FieldChanged {
+                mls_number, field, ..
+            } => println!("  ~ {}: {} changed", mls_number, field),
+        }
+    }
+}
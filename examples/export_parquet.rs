@@ -0,0 +1,95 @@
+//! Example: Stream listings into an Apache Parquet file
+//!
+//! Demonstrates how to export a large search to Parquet for DuckDB, Polars or pandas.
+//! Pages are fetched through an auto-paginating stream and written to the file as they
+//! arrive, one row group at a time, so memory use stays flat however many listings are
+//! exported. Set `parquet_columns` in the `[export]` section to choose the columns.
+//!
+//! Usage:
+//!   cargo run --example export_parquet --features parquet
+//!
+//! Expected output:
+//!   Streaming up to 20 active properties in Toronto...
+//!   Exported X listings to: toronto_listings.parquet
+//!
+//! Then, for example:
+//!   duckdb -c "SELECT city, avg(list_price) FROM 'toronto_listings.parquet' GROUP BY city"
+//!
+//! Configuration:
+//!   This example reads parameters from config.toml
+//!   Copy config.toml.example to config.toml and adjust values as needed
+
+mod common;
+
+use common::ExampleConfig;
+use repliers_beta::export::parquet::{ParquetExporter, ParquetOptions, ParquetSchema};
+use repliers_beta::{ListingSearchRequest, RepliersClient, SearchStreamOptions};
+use std::fs::File;
+use std::io::BufWriter;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Load configuration from config.toml
+    let config = ExampleConfig::load_or_exit();
+
+    // Load API key from environment (.env file or REPLIERS_API_KEY env var)
+    let client = RepliersClient::from_env()?;
+
+    let cfg = &config.export;
+
+    let max_results = cfg.max_results.unwrap_or(cfg.results_per_page as usize);
+    let output_file = cfg
+        .parquet_file
+        .clone()
+        .unwrap_or_else(|| "listings.parquet".to_string());
+    let schema = match &cfg.parquet_columns {
+        Some(columns) => ParquetSchema::parse(columns)?,
+        None => ParquetSchema::listings(),
+    };
+
+    println!(
+        "Streaming up to {} active properties in {}...",
+        max_results, cfg.city
+    );
+
+    let request = ListingSearchRequest {
+        city: Some(cfg.city.clone()),
+        status: Some(cfg.status.clone()),
+        page: Some(1),
+        results_per_page: Some(cfg.results_per_page),
+        ..Default::default()
+    };
+
+    let options = SearchStreamOptions {
+        max_results: Some(max_results),
+        ..Default::default()
+    };
+
+    // One row group per 10,000 listings keeps the buffered rows small
+    let file = BufWriter::new(File::create(&output_file)?);
+    let mut exporter = ParquetExporter::with_options(
+        file,
+        schema,
+        ParquetOptions {
+            row_group_size: 10_000,
+            ..Default::default()
+        },
+    )?;
+
+    let result = exporter
+        .write_stream(client.search_listings_stream_with(request, options))
+        .await;
+    let rows = exporter.rows();
+
+    // Finish even after an error so the listings fetched so far are readable
+    exporter.finish()?;
+    if let Err(e) = result {
+        eprintln!("Error fetching listings: {}", e);
+        eprintln!("Kept the {} listings fetched before the error", rows);
+        return Err(e.into());
+    }
+
+    println!("Exported {} listings to: {}", rows, output_file);
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! Example: Run client code against the in-process fake API
+//!
+//! Demonstrates seeding `FakeRepliers` with synthetic listings, searching it with a normal
+//! client, and injecting faults to watch the retry policy recover.
+//!
+//! Usage:
+//!   cargo run --example fake_server --features testing
+//!
+//! No API key or network access is needed.
+
+use repliers_beta::testing::{synthetic_listings, FakeRepliers, Fault};
+use repliers_beta::{ListingSearchRequest, RetryPolicy};
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let fake = FakeRepliers::start().await?;
+    fake.insert_listings(synthetic_listings(500, 42));
+    println!("Fake Repliers API listening on {}", fake.url());
+
+    let client = fake
+        .client_builder()
+        .retry_policy(
+            RetryPolicy::default()
+                .retry_search(true)
+                .base_delay(Duration::from_millis(50)),
+        )
+        .build()?;
+
+    let request = ListingSearchRequest::builder()
+        .city("Toronto")
+        .min_bedrooms(3)
+        .max_price(1_500_000.0)
+        .build();
+    let mut first_page = request.clone();
+    first_page.sort_by = Some("listPriceAsc".to_string());
+    first_page.results_per_page = Some(5);
+
+    let response = client.search_listings(first_page).await?;
+    println!(
+        "\n{} matches over {} pages, cheapest first:",
+        response.count, response.num_pages
+    );
+    for listing in &response.listings {
+        println!(
+            "  {} {:>10} {}bd {}",
+            listing.mls_number,
+            listing.list_price.unwrap_or_default(),
+            listing.bedrooms().unwrap_or_default(),
+            listing.street_address().unwrap_or_default()
+        );
+    }
+
+    // One rate-limited and one failed attempt before the search goes through
+    fake.inject(Fault::RateLimited { retry_after: None });
+    fake.inject(Fault::Status(503));
+    let before = fake.request_count();
+    let response = client.search_listings(request).await?;
+    println!(
+        "\nSearch succeeded after {} attempts ({} matches)",
+        fake.request_count() - before,
+        response.count
+    );
+
+    if let Some(listing) = response.listings.first() {
+        fake.inject(Fault::MalformedJson);
+        match client.get_listing(&listing.mls_number, None).await {
+            Ok(_) => println!("Unexpected success"),
+            Err(e) => println!("Malformed response surfaced as: {}", e),
+        }
+    }
+
+    Ok(())
+}
@@ -9,12 +9,15 @@
 //!   This example reads the MLS number from config.toml
 //!   Copy config.toml.example to config.toml and adjust values as needed
 
-use repliers_beta::{config::Config, RepliersClient};
+mod common;
+
+use common::ExampleConfig;
+use repliers_beta::RepliersClient;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration from config.toml
-    let config = Config::load_or_exit();
+    let config = ExampleConfig::load_or_exit();
 
     let client = RepliersClient::from_env()?;
 
@@ -27,7 +30,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     match client.get_listing(mls_number, board_id).await {
         Ok(listing) => {
             println!("Listing details retrieved successfully!");
-            println!("{:#}", listing);
+            println!("{}", serde_json::to_string_pretty(&listing.listing)?);
+
+            println!("\nComparables: {}", listing.comparables().len());
+            for comparable in listing.comparables() {
+                println!(
+                    "  - MLS #{}: {:?} ({})",
+                    comparable.mls_number,
+                    comparable.list_price,
+                    comparable.street_address().unwrap_or_default()
+                );
+            }
+
+            println!("\nHistory entries: {}", listing.history().len());
+            for entry in listing.history() {
+                println!(
+                    "  - MLS #{}: listed {:?} at {:?}, status {:?}",
+                    entry.mls_number.as_deref().unwrap_or("N/A"),
+                    entry.list_date,
+                    entry.list_price,
+                    entry.last_status
+                );
+            }
+        }
+        Err(e) if e.is_not_found() => {
+            eprintln!("Listing {} was not found: {}", mls_number, e);
+            eprintln!("\nUpdate config.toml with a valid MLS number from your accessible MLS board");
+            return Err(e.into());
         }
         Err(e) => {
             eprintln!("Error fetching listing: {}", e);
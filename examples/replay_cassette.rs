@@ -0,0 +1,71 @@
+//! Example: Record and replay API traffic
+//!
+//! Demonstrates running client code against a cassette of recorded responses, so it can
+//! be exercised without network access or an API key.
+//!
+//! Usage:
+//!   cargo run --example replay_cassette            # replay examples/cassettes/toronto_search.json
+//!   cargo run --example replay_cassette -- --record # record a fresh cassette (needs an API key)
+//!
+//! Recording writes to toronto_search.recorded.json in the current directory when the
+//! client is dropped. The API key is scrubbed from everything written to the cassette.
+
+use repliers_beta::transport::ReplayTransport;
+use repliers_beta::{ListingSearchRequest, RepliersClient};
+
+const CASSETTE: &str = "examples/cassettes/toronto_search.json";
+const RECORDED: &str = "toronto_search.recorded.json";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let record = std::env::args().any(|arg| arg == "--record");
+
+    let client = if record {
+        dotenvy::dotenv().ok();
+        let api_key = std::env::var("REPLIERS_API_KEY")?;
+        println!("Recording live responses to {}\n", RECORDED);
+        RepliersClient::builder()
+            .api_key(api_key)
+            .record(RECORDED)
+            .build()?
+    } else {
+        println!("Replaying responses from {}\n", CASSETTE);
+        RepliersClient::builder()
+            .api_key("replay")
+            .transport(ReplayTransport::from_file(CASSETTE)?)
+            .build()?
+    };
+
+    let request = ListingSearchRequest::builder()
+        .city("Toronto")
+        .add_status("Active")
+        .page(1)
+        .results_per_page(2)
+        .build();
+
+    let response = client.search_listings(request).await?;
+    println!("Found {} listings", response.count);
+    for listing in &response.listings {
+        println!(
+            "  {} - {} - {:?}",
+            listing.mls_number,
+            listing.street_address().unwrap_or_default(),
+            listing.list_price
+        );
+    }
+
+    if let Some(first) = response.listings.first() {
+        let detail = client.get_listing(&first.mls_number, None).await?;
+        println!(
+            "\n{}: {}",
+            detail.mls_number,
+            detail
+                .details
+                .as_ref()
+                .and_then(|d| d.description.as_deref())
+                .unwrap_or("(no description)")
+        );
+    }
+
+    Ok(())
+}
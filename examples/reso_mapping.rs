@@ -0,0 +1,72 @@
+//! Example: Convert a listing to a RESO Data Dictionary Property record
+//!
+//! Demonstrates mapping a Repliers listing onto RESO field names and lookup values, and
+//! reading the report of fields that had no RESO equivalent. The RESO record is then
+//! converted back to show which values survive the round trip.
+//!
+//! Usage:
+//!   cargo run --example reso_mapping
+//!
+//! Configuration:
+//!   This example reads the MLS number from config.toml
+//!   Copy config.toml.example to config.toml and adjust values as needed
+
+mod common;
+
+use common::ExampleConfig;
+use repliers_beta::reso::{self, MappingReport};
+use repliers_beta::RepliersClient;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Load configuration from config.toml
+    let config = ExampleConfig::load_or_exit();
+
+    let client = RepliersClient::from_env()?;
+
+    let mls_number = &config.listing.mls_number;
+    let board_id = config.listing.board_id.as_deref();
+
+    println!("Fetching listing {}...", mls_number);
+    let listing = match client.get_listing(mls_number, board_id).await {
+        Ok(detail) => detail.listing,
+        Err(e) => {
+            eprintln!("Error fetching listing: {}", e);
+            eprintln!(
+                "\nUpdate config.toml with a valid MLS number from your accessible MLS board"
+            );
+            return Err(e.into());
+        }
+    };
+
+    let mapped = reso::to_reso(&listing);
+    println!("\nRESO Property record:");
+    println!("{}", serde_json::to_string_pretty(&mapped.record)?);
+    print_report("Repliers fields without a RESO equivalent", &mapped.report);
+
+    let back = reso::from_reso(&mapped.record);
+    println!(
+        "\nConverted back: status {:?}/{:?}, class {:?}, property type {:?}",
+        back.record.status,
+        back.record.last_status,
+        back.record.class,
+        back.record
+            .details
+            .as_ref()
+            .and_then(|d| d.property_type.as_deref())
+    );
+    print_report("RESO fields without a Repliers equivalent", &back.report);
+
+    Ok(())
+}
+
+fn print_report(title: &str, report: &MappingReport) {
+    if report.is_complete() {
+        println!("\n{}: none", title);
+        return;
+    }
+    println!("\n{} ({}):", title, report.unmapped.len());
+    for field in &report.unmapped {
+        println!("  - {} = {} ({:?})", field.field, field.value, field.reason);
+    }
+}
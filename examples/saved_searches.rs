@@ -0,0 +1,60 @@
+//! Example: Saved searches with new-match alerts
+//!
+//! Demonstrates keeping buyer criteria in a saved search registry and re-running them.
+//! The first run reports every match as new; later runs report only listings that are
+//! new since the previous run or whose price or status changed.
+//!
+//! Usage:
+//!   cargo run --example saved_searches
+//!
+//! The registry (searches plus the listings each one has already seen) is stored in
+//! saved_searches.json. A sample search is added when the registry is empty; edit the
+//! file to add your own criteria and notification channels.
+
+use repliers_beta::saved_search::{
+    NotifyChannel, SavedSearch, SavedSearchRegistry, SavedSearchRunner,
+};
+use repliers_beta::{ListingSearchRequest, RepliersClient};
+
+const REGISTRY_FILE: &str = "saved_searches.json";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = RepliersClient::from_env()?;
+
+    let mut registry = SavedSearchRegistry::load(REGISTRY_FILE)?;
+    if registry.is_empty() {
+        println!(
+            "No saved searches yet, adding a sample one to {}\n",
+            REGISTRY_FILE
+        );
+        registry.insert(SavedSearch {
+            name: "toronto-family-homes".to_string(),
+            request: ListingSearchRequest::builder()
+                .city("Toronto")
+                .add_status("Active")
+                .min_bedrooms(3)
+                .max_price(1_500_000.0)
+                .build(),
+            owner: Some("buyer@example.com".to_string()),
+            notify: NotifyChannel::Stdout,
+            max_results: Some(50),
+        });
+    }
+
+    let mut runner = SavedSearchRunner::new(client, registry);
+    let report = runner.run_all().await?;
+
+    println!(
+        "Ran {} saved search(es): {} with alerts, {} unchanged, {} failed",
+        runner.registry().len(),
+        report.digests.len(),
+        report.unchanged,
+        report.failures.len()
+    );
+    for (name, error) in &report.failures {
+        eprintln!("  {} failed: {}", name, error);
+    }
+
+    Ok(())
+}
@@ -14,12 +14,15 @@
 //!   This example reads parameters from config.toml
 //!   Copy config.toml.example to config.toml and adjust values as needed
 
-use repliers_beta::{config::Config, ListingSearchRequest, RepliersClient};
+mod common;
+
+use common::ExampleConfig;
+use repliers_beta::{ListingSearchRequest, RepliersClient};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration from config.toml
-    let config = Config::load_or_exit();
+    let config = ExampleConfig::load_or_exit();
 
     // Load API key from environment (.env file or REPLIERS_API_KEY env var)
     let client = RepliersClient::from_env()?;
@@ -37,6 +40,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         property_type: Some(cfg.property_type.clone()),
         page: Some(cfg.page),
         results_per_page: Some(cfg.results_per_page),
+        ..Default::default()
     };
 
     println!("Searching for listings in {}...", cfg.city);
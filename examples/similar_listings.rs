@@ -9,12 +9,15 @@
 //!   This example reads parameters from config.toml
 //!   Copy config.toml.example to config.toml and adjust values as needed
 
-use repliers_beta::{config::Config, models::search::SimilarListingsRequest, RepliersClient};
+mod common;
+
+use common::ExampleConfig;
+use repliers_beta::{models::search::SimilarListingsRequest, RepliersClient};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration from config.toml
-    let config = Config::load_or_exit();
+    let config = ExampleConfig::load_or_exit();
 
     let client = RepliersClient::from_env()?;
 
@@ -40,7 +43,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
             for (i, listing) in similar.similar.iter().enumerate() {
                 println!("Similar listing #{}", i + 1);
-                println!("{:#}", listing);
+                println!("{}", serde_json::to_string_pretty(listing)?);
             }
         }
         Err(e) => {
@@ -0,0 +1,102 @@
+//! Example: Receive listing webhooks
+//!
+//! Demonstrates running a local webhook receiver that verifies signatures and decodes
+//! listing events into the client's `Listing` / `DeletedListing` types.
+//!
+//! Usage:
+//!   cargo run --example webhook_receiver --features webhooks
+//!
+//! Send a signed test event with `--self-test`, or post a fixture yourself:
+//!   BODY='{"event":"listing.updated","data":{"mlsNumber":"N12345678","listPrice":899000}}'
+//!   SIG=$(printf '%s' "$BODY" | openssl dgst -sha256 -hmac "$REPLIERS_WEBHOOK_SECRET" | cut -d' ' -f2)
+//!   curl -X POST localhost:8787/webhooks/repliers -H "x-repliers-signature: $SIG" -d "$BODY"
+//!
+//! Configuration:
+//!   REPLIERS_WEBHOOK_SECRET - shared secret (signatures are not checked when unset)
+//!   WEBHOOK_PORT            - port to listen on (default 8787)
+
+use repliers_beta::webhooks::{self, WebhookConfig, WebhookEvent, DEFAULT_SIGNATURE_HEADER};
+use tokio::sync::mpsc;
+
+const PATH: &str = "/webhooks/repliers";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenvy::dotenv().ok();
+
+    let secret = std::env::var("REPLIERS_WEBHOOK_SECRET").ok();
+    let port: u16 = std::env::var("WEBHOOK_PORT")
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(8787);
+
+    let config = match &secret {
+        Some(secret) => WebhookConfig::with_secret(secret.clone()),
+        None => {
+            println!("⚠️  REPLIERS_WEBHOOK_SECRET not set, accepting unsigned requests");
+            WebhookConfig::default()
+        }
+    };
+
+    let (tx, mut rx) = mpsc::channel::<WebhookEvent>(100);
+    let app = webhooks::router(PATH, config, tx);
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await?;
+    println!("Listening on http://127.0.0.1:{}{}", port, PATH);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    if std::env::args().any(|arg| arg == "--self-test") {
+        send_test_event(port, secret.as_deref()).await?;
+    }
+
+    while let Some(event) = rx.recv().await {
+        match &event {
+            WebhookEvent::ListingCreated(listing) | WebhookEvent::ListingUpdated(listing) => {
+                println!(
+                    "{:<16} {} {:?} {}",
+                    event.event_name(),
+                    listing.mls_number,
+                    listing.list_price,
+                    listing.street_address().unwrap_or_default()
+                );
+            }
+            WebhookEvent::ListingDeleted(deleted) => {
+                println!(
+                    "{:<16} {} (board {})",
+                    event.event_name(),
+                    deleted.mls_number,
+                    deleted.board_id
+                );
+            }
+            WebhookEvent::Other { event, .. } => println!("unhandled event  {}", event),
+        }
+    }
+
+    Ok(())
+}
+
+/// Posts a signed `listing.updated` fixture to the local receiver
+async fn send_test_event(
+    port: u16,
+    secret: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let body = serde_json::to_vec(&serde_json::json!({
+        "event": "listing.updated",
+        "data": {
+            "mlsNumber": "N12345678",
+            "listPrice": "899000",
+            "address": { "streetNumber": "123", "streetName": "Main", "streetSuffix": "St" }
+        }
+    }))?;
+
+    let mut request = reqwest::Client::new()
+        .post(format!("http://127.0.0.1:{}{}", port, PATH))
+        .header("content-type", "application/json");
+    if let Some(secret) = secret {
+        request = request.header(DEFAULT_SIGNATURE_HEADER, webhooks::sign(secret, &body));
+    }
+
+    let response = request.body(body).send().await?;
+    println!("Self-test delivery answered {}", response.status());
+    Ok(())
+}
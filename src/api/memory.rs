@@ -0,0 +1,530 @@
+//! In-memory implementation of the endpoints
+
+use super::RepliersApi;
+use crate::error::ApiErrorContext;
+use crate::models::geo::GeoPoint;
+use crate::models::{
+    parse_timestamp, AddressHistoryQuery, AddressHistoryResponse, DeletedListing,
+    DeletedListingTimestamps, DeletedListingsQuery, DeletedListingsResponse, HistoryEntry, Listing,
+    ListingDetail, ListingHistoryEntry, ListingSearchRequest, ListingSearchResponse,
+    NLPSearchResponse, SimilarListingsRequest, SimilarListingsResponse,
+};
+use crate::RepliersError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Number of comparables included in a listing detail
+const DETAIL_COMPARABLES: usize = 5;
+
+/// Listings and deletions, with the API's query semantics
+///
+/// Shared by [`InMemoryApi`] and the `testing` feature's fake server so both answer
+/// identically.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ListingData {
+    listings: BTreeMap<String, Listing>,
+    deleted: Vec<DeletedListing>,
+}
+
+impl ListingData {
+    pub(crate) fn insert(&mut self, listing: Listing) {
+        self.listings.insert(listing.mls_number.clone(), listing);
+    }
+
+    pub(crate) fn delete(&mut self, mls_number: &str, deleted_at: DateTime<Utc>) -> bool {
+        let Some(listing) = self.listings.remove(mls_number) else {
+            return false;
+        };
+        self.deleted.push(DeletedListing {
+            board_id: listing.board_id.unwrap_or_default(),
+            mls_number: listing.mls_number,
+            resource: listing.resource.unwrap_or_else(|| "Property".to_string()),
+            address: listing.address.unwrap_or_default(),
+            timestamps: DeletedListingTimestamps {
+                listing_updated: deleted_at.to_rfc3339(),
+            },
+        });
+        true
+    }
+
+    pub(crate) fn insert_deleted(&mut self, deleted: DeletedListing) {
+        self.deleted.push(deleted);
+    }
+
+    pub(crate) fn get(&self, mls_number: &str) -> Option<&Listing> {
+        self.listings.get(mls_number)
+    }
+
+    pub(crate) fn listings(&self) -> impl Iterator<Item = &Listing> {
+        self.listings.values()
+    }
+
+    pub(crate) fn search(&self, request: &ListingSearchRequest) -> ListingSearchResponse {
+        request.search_in(self.listings.values().cloned())
+    }
+
+    /// Finds a listing, honouring an optional board ID
+    fn find(&self, mls_number: &str, board_id: Option<&str>) -> Option<&Listing> {
+        let listing = self.listings.get(mls_number)?;
+        match board_id {
+            Some(board_id) if board_id.parse().ok() != listing.board_id => None,
+            _ => Some(listing),
+        }
+    }
+
+    /// The listing, with similar listings as comparables and other listings at the same
+    /// address as history
+    pub(crate) fn detail(&self, mls_number: &str, board_id: Option<&str>) -> Option<ListingDetail> {
+        let listing = self.find(mls_number, board_id)?;
+
+        let mut comparables = self.similar_to(listing, None, None, None);
+        comparables.truncate(DETAIL_COMPARABLES);
+        let history = self
+            .listings
+            .values()
+            .filter(|other| other.mls_number != listing.mls_number && same_address(listing, other))
+            .map(|other| ListingHistoryEntry {
+                mls_number: Some(other.mls_number.clone()),
+                listing_type: other.listing_type.clone(),
+                list_price: other.list_price,
+                list_date: other.list_date.clone(),
+                last_status: other.last_status.clone(),
+                sold_price: other.sold_price,
+                sold_date: other.sold_date.clone(),
+                ..Default::default()
+            })
+            .collect();
+
+        Some(ListingDetail {
+            listing: listing.clone(),
+            comparables: Some(comparables),
+            history: Some(history),
+        })
+    }
+
+    pub(crate) fn similar(
+        &self,
+        request: &SimilarListingsRequest,
+    ) -> Option<SimilarListingsResponse> {
+        let listing = self.find(&request.mls_number, request.board_id.as_deref())?;
+        let similar = self.similar_to(
+            listing,
+            request.list_price_range,
+            request.radius,
+            request.sort_by.as_deref(),
+        );
+        let count = similar.len() as u32;
+        Some(SimilarListingsResponse {
+            similar,
+            page: 1,
+            num_pages: u32::from(count > 0),
+            page_size: count,
+            count,
+        })
+    }
+
+    /// Listings of the same class and property type as `target`, within the price range
+    /// and radius, closest in price first unless `sort_by` is set
+    fn similar_to(
+        &self,
+        target: &Listing,
+        price_range: Option<f64>,
+        radius: Option<f64>,
+        sort_by: Option<&str>,
+    ) -> Vec<Listing> {
+        let property_type = |l: &Listing| l.details.as_ref().and_then(|d| d.property_type.clone());
+        let center = target
+            .coordinates()
+            .map(|(lat, long)| GeoPoint::new(lat, long));
+
+        let mut similar: Vec<Listing> = self
+            .listings
+            .values()
+            .filter(|l| l.mls_number != target.mls_number)
+            .filter(|l| l.class == target.class && property_type(l) == property_type(target))
+            .filter(|l| match (price_range, l.list_price, target.list_price) {
+                (Some(range), Some(price), Some(target_price)) => {
+                    (price - target_price).abs() <= range
+                }
+                (Some(_), _, _) => false,
+                (None, _, _) => true,
+            })
+            .filter(|l| match (radius, &center, l.coordinates()) {
+                (Some(radius), Some(center), Some((lat, long))) => {
+                    center.distance_km(&GeoPoint::new(lat, long)) <= radius
+                }
+                (Some(_), _, _) => false,
+                (None, _, _) => true,
+            })
+            .cloned()
+            .collect();
+
+        match sort_by {
+            Some(sort_by) => ListingSearchRequest {
+                sort_by: Some(sort_by.to_string()),
+                ..Default::default()
+            }
+            .sort_listings(&mut similar),
+            None => {
+                let distance = |l: &Listing| match (l.list_price, target.list_price) {
+                    (Some(price), Some(target_price)) => (price - target_price).abs(),
+                    _ => f64::INFINITY,
+                };
+                similar.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+            }
+        }
+        similar
+    }
+
+    /// Every listing at the address, most recently listed first; `None` when there is none
+    pub(crate) fn address_history(
+        &self,
+        query: &AddressHistoryQuery,
+    ) -> Option<AddressHistoryResponse> {
+        let matches = |wanted: &Option<String>, value: Option<&String>| {
+            wanted
+                .as_ref()
+                .is_none_or(|wanted| value.is_some_and(|v| v.eq_ignore_ascii_case(wanted)))
+        };
+
+        let mut listings: Vec<&Listing> = self
+            .listings
+            .values()
+            .filter(|l| {
+                let Some(address) = &l.address else {
+                    return false;
+                };
+                address.street_number.as_deref() == Some(query.street_number.as_str())
+                    && address
+                        .street_name
+                        .as_deref()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(&query.street_name))
+                    && matches(&query.city, address.city.as_ref())
+                    && matches(&query.zip, address.zip.as_ref())
+                    && matches(&query.state, address.state.as_ref())
+                    && matches(
+                        &query.board_id,
+                        l.board_id.map(|id| id.to_string()).as_ref(),
+                    )
+            })
+            .collect();
+        listings.sort_by(|a, b| b.list_date.cmp(&a.list_date));
+
+        let address = listings
+            .first()?
+            .address
+            .as_ref()
+            .and_then(|a| a.street_line())
+            .unwrap_or_else(|| format!("{} {}", query.street_number, query.street_name));
+        let history = listings
+            .into_iter()
+            .map(|l| HistoryEntry {
+                mls_number: l.mls_number.clone(),
+                list_price: l.list_price,
+                sold_price: l.sold_price,
+                status: l
+                    .last_status
+                    .clone()
+                    .or_else(|| l.status.clone())
+                    .unwrap_or_default(),
+                list_date: l.list_date.clone(),
+                sold_date: l.sold_date.clone(),
+                property_type: l.details.as_ref().and_then(|d| d.property_type.clone()),
+                bedrooms: l.bedrooms(),
+            })
+            .collect();
+
+        Some(AddressHistoryResponse { history, address })
+    }
+
+    /// Deleted listings, most recent first, filtered by date and board and paginated
+    pub(crate) fn deleted(&self, query: &DeletedListingsQuery) -> DeletedListingsResponse {
+        let updated_on = query
+            .updated_on
+            .as_deref()
+            .map(|v| v.get(..10).unwrap_or(v));
+        let min = query.min_updated_on.as_deref().and_then(parse_timestamp);
+        let max = query.max_updated_on.as_deref().and_then(parse_timestamp);
+
+        let mut deleted: Vec<&DeletedListing> = self
+            .deleted
+            .iter()
+            .filter(|d| {
+                let updated = &d.timestamps.listing_updated;
+                let at = parse_timestamp(updated);
+                updated_on.is_none_or(|day| updated.starts_with(day))
+                    && min.is_none_or(|min| at.is_some_and(|at| at >= min))
+                    && max.is_none_or(|max| at.is_some_and(|at| at <= max))
+                    && query
+                        .board_id
+                        .as_ref()
+                        .is_none_or(|board_id| board_id.parse().ok() == Some(d.board_id))
+            })
+            .collect();
+        deleted.sort_by(|a, b| {
+            b.timestamps
+                .listing_updated
+                .cmp(&a.timestamps.listing_updated)
+        });
+
+        let page = query.page.unwrap_or(1).max(1);
+        let page_size = query.results_per_page.unwrap_or(100).max(1);
+        let count = deleted.len() as u32;
+        let listings = deleted
+            .into_iter()
+            .skip((page as usize - 1).saturating_mul(page_size as usize))
+            .take(page_size as usize)
+            .cloned()
+            .collect();
+
+        DeletedListingsResponse {
+            listings,
+            page,
+            num_pages: count.div_ceil(page_size),
+            page_size,
+            count,
+        }
+    }
+
+    /// Extracts a city ("in Toronto"), a bedroom count ("3 bedroom") and a maximum price
+    /// ("under $800k") from the prompt
+    pub(crate) fn nlp(prompt: &str, board_id: Option<&str>) -> NLPSearchResponse {
+        let words: Vec<&str> = prompt.split_whitespace().collect();
+        let mut params: BTreeMap<String, Value> = BTreeMap::new();
+        for pair in words.windows(2) {
+            let next = pair[1].trim_matches(|c: char| !c.is_alphanumeric() && c != '$' && c != '.');
+            match pair[0].to_ascii_lowercase().as_str() {
+                "in" if next.starts_with(char::is_uppercase) => {
+                    params.insert("city".to_string(), json!(next));
+                }
+                "under" | "below" => {
+                    if let Some(price) = parse_price(next) {
+                        params.insert("maxPrice".to_string(), json!(price));
+                    }
+                }
+                number => {
+                    let bedrooms = number.parse::<u32>();
+                    if let (Ok(bedrooms), true) = (bedrooms, next.to_lowercase().starts_with("bed"))
+                    {
+                        params.insert("minBedrooms".to_string(), json!(bedrooms));
+                    }
+                }
+            }
+        }
+        if let Some(board_id) = board_id {
+            params.insert("boardId".to_string(), json!(board_id));
+        }
+
+        let url = reqwest::Url::parse_with_params(
+            "https://api.repliers.io/listings",
+            params.iter().map(|(k, v)| match v {
+                Value::String(s) => (k.clone(), s.clone()),
+                other => (k.clone(), other.to_string()),
+            }),
+        )
+        .map(String::from)
+        .unwrap_or_default();
+
+        NLPSearchResponse {
+            url,
+            params: params.into_iter().collect::<HashMap<_, _>>(),
+            prompt: prompt.to_string(),
+        }
+    }
+}
+
+/// Whether two listings share a street number, street name and city
+fn same_address(a: &Listing, b: &Listing) -> bool {
+    let (Some(a), Some(b)) = (&a.address, &b.address) else {
+        return false;
+    };
+    let eq = |a: &Option<String>, b: &Option<String>| match (a, b) {
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        _ => false,
+    };
+    eq(&a.street_number, &b.street_number)
+        && eq(&a.street_name, &b.street_name)
+        && eq(&a.city, &b.city)
+}
+
+/// Parses "$800k", "$1.2m" or "$750,000"
+fn parse_price(text: &str) -> Option<u64> {
+    let text = text
+        .trim_start_matches('$')
+        .replace(',', "")
+        .to_ascii_lowercase();
+    let (number, multiplier) = match text.chars().last()? {
+        'k' => (&text[..text.len() - 1], 1_000.0),
+        'm' => (&text[..text.len() - 1], 1_000_000.0),
+        _ => (text.as_str(), 1.0),
+    };
+    number
+        .parse::<f64>()
+        .ok()
+        .map(|n| (n * multiplier).round() as u64)
+}
+
+/// An in-memory stand-in for the Repliers API
+///
+/// Answers every [`RepliersApi`] method from the listings it holds, with the same
+/// semantics as the `testing` feature's fake server:
+///
+/// - search filters, sorts and paginates with [`ListingSearchRequest::search_in`]
+/// - similar listings share the class and property type, within the requested price
+///   range and radius
+/// - a listing's detail includes similar listings as comparables and other listings at
+///   the same address as history
+/// - address history and deleted listings are built from the stored data
+/// - AI search understands a city, a bedroom count and a maximum price
+///
+/// Unknown listings and addresses fail with [`RepliersError::NotFound`], as they do
+/// against the API. Clones share the same data.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryApi {
+    data: Arc<RwLock<ListingData>>,
+}
+
+impl InMemoryApi {
+    /// Creates an empty API
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces a listing
+    pub fn insert_listing(&self, listing: Listing) {
+        self.write().insert(listing);
+    }
+
+    /// Adds or replaces several listings
+    pub fn insert_listings(&self, listings: impl IntoIterator<Item = Listing>) {
+        let mut data = self.write();
+        for listing in listings {
+            data.insert(listing);
+        }
+    }
+
+    /// Removes a listing and reports it from [`get_deleted_listings`](RepliersApi::get_deleted_listings)
+    ///
+    /// Returns `false` if no listing with that MLS number exists.
+    pub fn delete_listing(&self, mls_number: &str, deleted_at: DateTime<Utc>) -> bool {
+        self.write().delete(mls_number, deleted_at)
+    }
+
+    /// Adds a deleted-listing record directly
+    pub fn insert_deleted(&self, deleted: DeletedListing) {
+        self.write().insert_deleted(deleted);
+    }
+
+    /// Returns a listing by MLS number
+    pub fn listing(&self, mls_number: &str) -> Option<Listing> {
+        self.read().get(mls_number).cloned()
+    }
+
+    /// Returns all listings, ordered by MLS number
+    pub fn listings(&self) -> Vec<Listing> {
+        self.read().listings().cloned().collect()
+    }
+
+    /// Returns the number of listings
+    pub fn len(&self) -> usize {
+        self.read().listings.len()
+    }
+
+    /// Returns `true` if there are no listings
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, ListingData> {
+        self.data.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, ListingData> {
+        self.data.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl FromIterator<Listing> for InMemoryApi {
+    fn from_iter<I: IntoIterator<Item = Listing>>(iter: I) -> Self {
+        let api = Self::new();
+        api.insert_listings(iter);
+        api
+    }
+}
+
+/// Builds the error the API returns for an unknown resource
+fn not_found(path: String, message: &str) -> RepliersError {
+    let body = json!({ "message": message }).to_string();
+    let context = ApiErrorContext::new(
+        StatusCode::NOT_FOUND,
+        Method::GET,
+        path,
+        &HeaderMap::new(),
+        &body,
+    );
+    RepliersError::from_response(context, None)
+}
+
+#[async_trait]
+impl RepliersApi for InMemoryApi {
+    async fn search_listings(
+        &self,
+        request: ListingSearchRequest,
+    ) -> Result<ListingSearchResponse, RepliersError> {
+        Ok(self.read().search(&request))
+    }
+
+    async fn ai_search_listings(
+        &self,
+        prompt: &str,
+        board_id: Option<&str>,
+    ) -> Result<NLPSearchResponse, RepliersError> {
+        Ok(ListingData::nlp(prompt, board_id))
+    }
+
+    async fn get_listing(
+        &self,
+        mls_number: &str,
+        board_id: Option<&str>,
+    ) -> Result<ListingDetail, RepliersError> {
+        self.read()
+            .detail(mls_number, board_id)
+            .ok_or_else(|| not_found(format!("/listings/{}", mls_number), "Listing not found"))
+    }
+
+    async fn get_similar_listings(
+        &self,
+        request: SimilarListingsRequest,
+    ) -> Result<SimilarListingsResponse, RepliersError> {
+        self.read().similar(&request).ok_or_else(|| {
+            not_found(
+                format!("/listings/{}/similar", request.mls_number),
+                "Listing not found",
+            )
+        })
+    }
+
+    async fn get_address_history(
+        &self,
+        query: AddressHistoryQuery,
+    ) -> Result<AddressHistoryResponse, RepliersError> {
+        self.read().address_history(&query).ok_or_else(|| {
+            not_found(
+                "/listings/history".to_string(),
+                "No history for this address",
+            )
+        })
+    }
+
+    async fn get_deleted_listings(
+        &self,
+        query: DeletedListingsQuery,
+    ) -> Result<DeletedListingsResponse, RepliersError> {
+        Ok(self.read().deleted(&query))
+    }
+}
@@ -0,0 +1,235 @@
+//! Endpoint trait for code that should not depend on the concrete client
+//!
+//! [`RepliersApi`] covers the six listing endpoints with the same signatures as the
+//! inherent [`RepliersClient`] methods. Write application code against the trait and it
+//! accepts the real client in production and an [`InMemoryApi`] (or your own mock) in
+//! tests.
+//!
+//! # Examples
+//!
+//! ```
+//! use repliers_beta::{InMemoryApi, Listing, ListingSearchRequest, RepliersApi, RepliersError};
+//!
+//! async fn cheapest(api: &impl RepliersApi, city: &str) -> Result<Option<Listing>, RepliersError> {
+//!     let mut request = ListingSearchRequest::builder().city(city).build();
+//!     request.sort_by = Some("listPriceAsc".to_string());
+//!     request.results_per_page = Some(1);
+//!     Ok(api.search_listings(request).await?.listings.into_iter().next())
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let api = InMemoryApi::new();
+//! api.insert_listings(serde_json::from_str::<Vec<Listing>>(
+//!     r#"[
+//!         {"mlsNumber": "C1", "listPrice": 900000, "address": {"city": "Toronto"}},
+//!         {"mlsNumber": "C2", "listPrice": 650000, "address": {"city": "Toronto"}}
+//!     ]"#,
+//! )?);
+//!
+//! let listing = cheapest(&api, "Toronto").await?.unwrap();
+//! assert_eq!(listing.mls_number, "C2");
+//! # Ok(())
+//! # }
+//! ```
+
+mod memory;
+
+pub use memory::InMemoryApi;
+#[cfg(feature = "testing")]
+pub(crate) use memory::ListingData;
+
+use crate::models::{
+    AddressHistoryQuery, AddressHistoryResponse, DeletedListingsQuery, DeletedListingsResponse,
+    ListingDetail, ListingSearchRequest, ListingSearchResponse, NLPSearchResponse,
+    SimilarListingsRequest, SimilarListingsResponse,
+};
+use crate::{RepliersClient, RepliersError};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// The Repliers listing endpoints
+///
+/// Implemented by [`RepliersClient`], [`InMemoryApi`], and references and `Arc`s of any
+/// implementation.
+#[async_trait]
+pub trait RepliersApi: Send + Sync {
+    /// Search listings (`POST /listings`)
+    async fn search_listings(
+        &self,
+        request: ListingSearchRequest,
+    ) -> Result<ListingSearchResponse, RepliersError>;
+
+    /// Convert a natural language prompt into search parameters (`POST /nlp`)
+    async fn ai_search_listings(
+        &self,
+        prompt: &str,
+        board_id: Option<&str>,
+    ) -> Result<NLPSearchResponse, RepliersError>;
+
+    /// Get a single listing with comparables and history (`GET /listings/{mlsNumber}`)
+    async fn get_listing(
+        &self,
+        mls_number: &str,
+        board_id: Option<&str>,
+    ) -> Result<ListingDetail, RepliersError>;
+
+    /// Find listings similar to a given one (`GET /listings/{mlsNumber}/similar`)
+    async fn get_similar_listings(
+        &self,
+        request: SimilarListingsRequest,
+    ) -> Result<SimilarListingsResponse, RepliersError>;
+
+    /// Get the MLS history of an address (`GET /listings/history`)
+    async fn get_address_history(
+        &self,
+        query: AddressHistoryQuery,
+    ) -> Result<AddressHistoryResponse, RepliersError>;
+
+    /// Get listings removed from the MLS (`GET /listings/deleted`)
+    async fn get_deleted_listings(
+        &self,
+        query: DeletedListingsQuery,
+    ) -> Result<DeletedListingsResponse, RepliersError>;
+}
+
+#[async_trait]
+impl RepliersApi for RepliersClient {
+    async fn search_listings(
+        &self,
+        request: ListingSearchRequest,
+    ) -> Result<ListingSearchResponse, RepliersError> {
+        RepliersClient::search_listings(self, request).await
+    }
+
+    async fn ai_search_listings(
+        &self,
+        prompt: &str,
+        board_id: Option<&str>,
+    ) -> Result<NLPSearchResponse, RepliersError> {
+        RepliersClient::ai_search_listings(self, prompt, board_id).await
+    }
+
+    async fn get_listing(
+        &self,
+        mls_number: &str,
+        board_id: Option<&str>,
+    ) -> Result<ListingDetail, RepliersError> {
+        RepliersClient::get_listing(self, mls_number, board_id).await
+    }
+
+    async fn get_similar_listings(
+        &self,
+        request: SimilarListingsRequest,
+    ) -> Result<SimilarListingsResponse, RepliersError> {
+        RepliersClient::get_similar_listings(self, request).await
+    }
+
+    async fn get_address_history(
+        &self,
+        query: AddressHistoryQuery,
+    ) -> Result<AddressHistoryResponse, RepliersError> {
+        RepliersClient::get_address_history(self, query).await
+    }
+
+    async fn get_deleted_listings(
+        &self,
+        query: DeletedListingsQuery,
+    ) -> Result<DeletedListingsResponse, RepliersError> {
+        RepliersClient::get_deleted_listings(self, query).await
+    }
+}
+
+#[async_trait]
+impl<T: RepliersApi + ?Sized> RepliersApi for Arc<T> {
+    async fn search_listings(
+        &self,
+        request: ListingSearchRequest,
+    ) -> Result<ListingSearchResponse, RepliersError> {
+        (**self).search_listings(request).await
+    }
+
+    async fn ai_search_listings(
+        &self,
+        prompt: &str,
+        board_id: Option<&str>,
+    ) -> Result<NLPSearchResponse, RepliersError> {
+        (**self).ai_search_listings(prompt, board_id).await
+    }
+
+    async fn get_listing(
+        &self,
+        mls_number: &str,
+        board_id: Option<&str>,
+    ) -> Result<ListingDetail, RepliersError> {
+        (**self).get_listing(mls_number, board_id).await
+    }
+
+    async fn get_similar_listings(
+        &self,
+        request: SimilarListingsRequest,
+    ) -> Result<SimilarListingsResponse, RepliersError> {
+        (**self).get_similar_listings(request).await
+    }
+
+    async fn get_address_history(
+        &self,
+        query: AddressHistoryQuery,
+    ) -> Result<AddressHistoryResponse, RepliersError> {
+        (**self).get_address_history(query).await
+    }
+
+    async fn get_deleted_listings(
+        &self,
+        query: DeletedListingsQuery,
+    ) -> Result<DeletedListingsResponse, RepliersError> {
+        (**self).get_deleted_listings(query).await
+    }
+}
+
+#[async_trait]
+impl<T: RepliersApi + ?Sized> RepliersApi for &T {
+    async fn search_listings(
+        &self,
+        request: ListingSearchRequest,
+    ) -> Result<ListingSearchResponse, RepliersError> {
+        (**self).search_listings(request).await
+    }
+
+    async fn ai_search_listings(
+        &self,
+        prompt: &str,
+        board_id: Option<&str>,
+    ) -> Result<NLPSearchResponse, RepliersError> {
+        (**self).ai_search_listings(prompt, board_id).await
+    }
+
+    async fn get_listing(
+        &self,
+        mls_number: &str,
+        board_id: Option<&str>,
+    ) -> Result<ListingDetail, RepliersError> {
+        (**self).get_listing(mls_number, board_id).await
+    }
+
+    async fn get_similar_listings(
+        &self,
+        request: SimilarListingsRequest,
+    ) -> Result<SimilarListingsResponse, RepliersError> {
+        (**self).get_similar_listings(request).await
+    }
+
+    async fn get_address_history(
+        &self,
+        query: AddressHistoryQuery,
+    ) -> Result<AddressHistoryResponse, RepliersError> {
+        (**self).get_address_history(query).await
+    }
+
+    async fn get_deleted_listings(
+        &self,
+        query: DeletedListingsQuery,
+    ) -> Result<DeletedListingsResponse, RepliersError> {
+        (**self).get_deleted_listings(query).await
+    }
+}
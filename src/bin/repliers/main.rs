@@ -0,0 +1,624 @@
+//! `repliers`: query the Repliers API from the command line
+//!
+//! ```text
+//! repliers search --city Toronto --status A --min-beds 3 --limit 50
+//! repliers listing C1234567 --format json
+//! repliers deleted --since 2024-01-01 --all --format ndjson > deleted.ndjson
+//! repliers export toronto.csv --city Toronto --status A
+//! ```
+//!
+//! Settings come from the flags, then the profile selected with `--profile` (or
+//! `REPLIERS_PROFILE`) on its own, or, without one, `REPLIERS_*` variables (a `.env` file
+//! is honoured) over the profile file's default profile. The profile file is described
+//! in [`repliers_beta::config`].
+//!
+//! Build with `cargo install --path . --features cli`.
+
+mod output;
+
+use clap::{Args, Parser, Subcommand};
+use futures::stream::{self, StreamExt};
+use output::{Format, Kind, Output};
+use repliers_beta::config::ConfigLoader;
+use repliers_beta::export::csv::{ColumnSpec, CsvExporter};
+use repliers_beta::export::geojson::{GeoJsonExporter, GeoJsonOptions};
+use repliers_beta::export::ndjson::NdjsonWriter;
+use repliers_beta::{
+    AddressHistoryQuery, DeletedListingsQuery, ListingSearchRequest, RepliersClient, RepliersError,
+    SearchStreamOptions, SimilarListingsRequest,
+};
+use serde_json::json;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "repliers",
+    version,
+    about = "Query the Repliers real estate API"
+)]
+struct Cli {
+    #[command(flatten)]
+    connection: Connection,
+
+    /// Output format
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t,
+        global = true,
+        help_heading = "Output"
+    )]
+    format: Format,
+
+    /// Table, CSV and GeoJSON columns as comma-separated `[header=]path[:text|date|price]`
+    /// entries, e.g. `MLS=mlsNumber,Price=listPrice:price,City=address.city`
+    #[arg(long, global = true, help_heading = "Output")]
+    columns: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Connection")]
+struct Connection {
+    /// Profile to use from the profile file, ignoring REPLIERS_* variables
+    #[arg(long, env = "REPLIERS_PROFILE", global = true)]
+    profile: Option<String>,
+
+    /// Profile file [default: ~/.config/repliers/config.toml]
+    #[arg(long, env = "REPLIERS_CONFIG", global = true)]
+    config: Option<PathBuf>,
+
+    /// API key, overriding the profile and REPLIERS_API_KEY
+    #[arg(long, global = true)]
+    api_key: Option<String>,
+
+    /// API base URL
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+
+    /// Board ID for multi-MLS accounts
+    #[arg(long, global = true)]
+    board_id: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Search listings, following result pages
+    Search {
+        #[command(flatten)]
+        filters: SearchFilters,
+
+        #[command(flatten)]
+        pages: Pages,
+    },
+
+    /// Turn a plain-English prompt into search parameters (production keys only)
+    AiSearch {
+        /// What to look for, e.g. "3 bedroom condo in Toronto under 800k"
+        prompt: String,
+    },
+
+    /// Show one listing
+    Listing {
+        /// MLS number
+        mls_number: String,
+    },
+
+    /// Show listings similar to a listing
+    Similar {
+        /// MLS number of the reference listing
+        mls_number: String,
+
+        /// Search radius in kilometres
+        #[arg(long)]
+        radius: Option<f64>,
+
+        /// Allowed difference from the reference list price
+        #[arg(long)]
+        price_range: Option<f64>,
+    },
+
+    /// Show the listing history of an address (production keys only)
+    History {
+        /// Street number, e.g. 2031
+        #[arg(long)]
+        street_number: String,
+
+        /// Street name, e.g. "N. Mt. Juliet Road"
+        #[arg(long)]
+        street_name: String,
+
+        /// City (this or --zip is required)
+        #[arg(long, required_unless_present = "zip")]
+        city: Option<String>,
+
+        /// ZIP/postal code
+        #[arg(long)]
+        zip: Option<String>,
+
+        /// State/province
+        #[arg(long)]
+        state: Option<String>,
+    },
+
+    /// Show listings removed from the feed, following result pages
+    Deleted {
+        /// Only deletions on this date (YYYY-MM-DD)
+        #[arg(long, conflicts_with_all = ["since", "until"])]
+        on: Option<String>,
+
+        /// Deletions on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Deletions on or before this date (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+
+        #[command(flatten)]
+        pages: Pages,
+    },
+
+    /// Export every listing of a search to a file
+    ///
+    /// The format follows the extension: .csv, .json, .geojson, .ndjson/.jsonl
+    /// (optionally .gz) and .parquet (when built with the `parquet` feature).
+    Export {
+        /// Output file
+        path: PathBuf,
+
+        #[command(flatten)]
+        filters: SearchFilters,
+
+        /// Stop after this many listings [default: all]
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Listings per request
+        #[arg(long, default_value_t = 100)]
+        page_size: u32,
+
+        /// Parquet columns as comma-separated `[name=]path[:type]` entries, where type is
+        /// text, integer, float, boolean, date, timestamp or list,
+        /// e.g. `mls=mlsNumber,price=listPrice:float,listed=listDate:date`
+        #[arg(long)]
+        schema: Option<String>,
+    },
+}
+
+/// Pagination of listing commands
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Pagination")]
+struct Pages {
+    /// Stop after this many results
+    #[arg(long, default_value_t = 100, conflicts_with = "all")]
+    limit: usize,
+
+    /// Fetch every page
+    #[arg(long)]
+    all: bool,
+
+    /// Results per request
+    #[arg(long, default_value_t = 100)]
+    page_size: u32,
+}
+
+impl Pages {
+    fn max_results(&self) -> Option<usize> {
+        (!self.all).then_some(self.limit)
+    }
+}
+
+/// Listing search filters; list flags take comma-separated values
+#[derive(Debug, Args)]
+#[command(next_help_heading = "Filters")]
+struct SearchFilters {
+    /// City
+    #[arg(long)]
+    city: Option<String>,
+
+    /// Area/region
+    #[arg(long)]
+    area: Option<String>,
+
+    /// Neighborhood
+    #[arg(long)]
+    neighborhood: Option<String>,
+
+    /// Availability: A (available) and/or U (unavailable)
+    #[arg(long, value_delimiter = ',')]
+    status: Vec<String>,
+
+    /// Last status codes, e.g. New,Sld,Pc
+    #[arg(long, value_delimiter = ',')]
+    last_status: Vec<String>,
+
+    /// Property classes, e.g. ResidentialProperty,CondoProperty
+    #[arg(long, value_delimiter = ',')]
+    class: Vec<String>,
+
+    /// Transaction types: Sale and/or Lease
+    #[arg(long = "type", value_delimiter = ',')]
+    listing_type: Vec<String>,
+
+    /// Property types, e.g. Detached,"Condo Apartment"
+    #[arg(long, value_delimiter = ',')]
+    property_type: Vec<String>,
+
+    /// Styles, e.g. 2-Storey,Bungalow
+    #[arg(long, value_delimiter = ',')]
+    style: Vec<String>,
+
+    /// Minimum list price
+    #[arg(long)]
+    min_price: Option<f64>,
+
+    /// Maximum list price
+    #[arg(long)]
+    max_price: Option<f64>,
+
+    /// Minimum bedrooms
+    #[arg(long)]
+    min_beds: Option<u32>,
+
+    /// Maximum bedrooms
+    #[arg(long)]
+    max_beds: Option<u32>,
+
+    /// Minimum bathrooms
+    #[arg(long)]
+    min_baths: Option<u32>,
+
+    /// Maximum bathrooms
+    #[arg(long)]
+    max_baths: Option<u32>,
+
+    /// Minimum square footage
+    #[arg(long)]
+    min_sqft: Option<u32>,
+
+    /// Maximum square footage
+    #[arg(long)]
+    max_sqft: Option<u32>,
+
+    /// Listed on or after (YYYY-MM-DD)
+    #[arg(long)]
+    listed_since: Option<String>,
+
+    /// Listed on or before (YYYY-MM-DD)
+    #[arg(long)]
+    listed_until: Option<String>,
+
+    /// Sold on or after (YYYY-MM-DD)
+    #[arg(long)]
+    sold_since: Option<String>,
+
+    /// Sold on or before (YYYY-MM-DD)
+    #[arg(long)]
+    sold_until: Option<String>,
+
+    /// Updated on or after (YYYY-MM-DD)
+    #[arg(long)]
+    updated_since: Option<String>,
+
+    /// Updated on or before (YYYY-MM-DD)
+    #[arg(long)]
+    updated_until: Option<String>,
+
+    /// Keywords to search for in the listing text
+    #[arg(long)]
+    keywords: Option<String>,
+
+    /// Centre latitude of a radius search
+    #[arg(long, requires_all = ["long", "radius"], allow_hyphen_values = true)]
+    lat: Option<f64>,
+
+    /// Centre longitude of a radius search
+    #[arg(long, requires_all = ["lat", "radius"], allow_hyphen_values = true)]
+    long: Option<f64>,
+
+    /// Radius in kilometres around --lat/--long
+    #[arg(long, requires_all = ["lat", "long"])]
+    radius: Option<f64>,
+
+    /// Sort order, e.g. updatedOnDesc or listPriceAsc
+    #[arg(long)]
+    sort_by: Option<String>,
+}
+
+impl SearchFilters {
+    fn request(self, board_id: Option<String>, page_size: u32) -> ListingSearchRequest {
+        let list = |values: Vec<String>| (!values.is_empty()).then_some(values);
+        ListingSearchRequest {
+            city: self.city,
+            area: self.area,
+            neighborhood: self.neighborhood,
+            status: list(self.status),
+            last_status: list(self.last_status),
+            class: list(self.class),
+            listing_type: list(self.listing_type),
+            property_type: list(self.property_type),
+            style: list(self.style),
+            min_price: self.min_price,
+            max_price: self.max_price,
+            min_bedrooms: self.min_beds,
+            max_bedrooms: self.max_beds,
+            min_bathrooms: self.min_baths,
+            max_bathrooms: self.max_baths,
+            min_sqft: self.min_sqft,
+            max_sqft: self.max_sqft,
+            min_list_date: self.listed_since,
+            max_list_date: self.listed_until,
+            min_sold_date: self.sold_since,
+            max_sold_date: self.sold_until,
+            min_updated_on: self.updated_since,
+            max_updated_on: self.updated_until,
+            keywords: self.keywords,
+            lat: self.lat,
+            long: self.long,
+            radius: self.radius,
+            sort_by: self.sort_by,
+            board_id,
+            page: Some(1),
+            results_per_page: Some(page_size),
+            ..Default::default()
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            if matches!(e.downcast_ref(), Some(RepliersError::InvalidApiKey)) {
+                eprintln!("Set REPLIERS_API_KEY, pass --api-key or select a --profile");
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let output = Output::new(cli.format, cli.columns.as_deref())?;
+    let (client, board_id) = connect(cli.connection)?;
+
+    match cli.command {
+        Command::Search { filters, pages } => {
+            let request = filters.request(board_id, pages.page_size);
+            let options = SearchStreamOptions {
+                max_results: pages.max_results(),
+                ..Default::default()
+            };
+            let listings = client.search_listings_stream_with(request, options);
+            output.write_stream(Kind::Listing, listings).await?;
+        }
+        Command::AiSearch { prompt } => {
+            let response = client
+                .ai_search_listings(&prompt, board_id.as_deref())
+                .await?;
+            if cli.format == Format::Json {
+                output.write_one(Kind::Parameter, &response).await?;
+            } else {
+                let mut params: Vec<_> = response.params.into_iter().collect();
+                params.sort_by(|a, b| a.0.cmp(&b.0));
+                let rows = std::iter::once(json!({"name": "url", "value": response.url}))
+                    .chain(
+                        params
+                            .into_iter()
+                            .map(|(name, value)| json!({"name": name, "value": value})),
+                    )
+                    .map(Ok);
+                output
+                    .write_stream(Kind::Parameter, stream::iter(rows))
+                    .await?;
+            }
+        }
+        Command::Listing { mls_number } => {
+            let listing = client.get_listing(&mls_number, board_id.as_deref()).await?;
+            output.write_one(Kind::Listing, &listing).await?;
+        }
+        Command::Similar {
+            mls_number,
+            radius,
+            price_range,
+        } => {
+            let request = SimilarListingsRequest {
+                mls_number,
+                board_id,
+                radius,
+                list_price_range: price_range,
+                ..Default::default()
+            };
+            let response = client.get_similar_listings(request).await?;
+            let listings = stream::iter(response.similar.into_iter().map(Ok));
+            output.write_stream(Kind::Listing, listings).await?;
+        }
+        Command::History {
+            street_number,
+            street_name,
+            city,
+            zip,
+            state,
+        } => {
+            let query = AddressHistoryQuery {
+                street_number,
+                street_name,
+                city,
+                zip,
+                state,
+                board_id,
+            };
+            let response = client.get_address_history(query).await?;
+            let entries = stream::iter(response.history.into_iter().map(Ok));
+            output.write_stream(Kind::History, entries).await?;
+        }
+        Command::Deleted {
+            on,
+            since,
+            until,
+            pages,
+        } => {
+            let query = DeletedListingsQuery {
+                updated_on: on,
+                min_updated_on: since,
+                max_updated_on: until,
+                board_id,
+                page: Some(1),
+                results_per_page: Some(pages.page_size),
+            };
+            let deleted = client.deleted_listings_stream(query);
+            let deleted = match pages.max_results() {
+                Some(limit) => deleted.take(limit).boxed(),
+                None => deleted.boxed(),
+            };
+            output.write_stream(Kind::Deleted, deleted).await?;
+        }
+        Command::Export {
+            path,
+            filters,
+            limit,
+            page_size,
+            schema,
+        } => {
+            let request = filters.request(board_id, page_size);
+            let options = SearchStreamOptions {
+                max_results: limit,
+                prefetch: 2,
+                ..Default::default()
+            };
+            let listings = client.search_listings_stream_with(request, options);
+            let columns = cli.columns.as_deref();
+            let written = export(&path, columns, schema.as_deref(), listings).await?;
+            eprintln!("Exported {} listings to {}", written, path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds the client from the flags, the selected profile and the environment
+fn connect(connection: Connection) -> Result<(RepliersClient, Option<String>), RepliersError> {
+    dotenvy::dotenv().ok();
+
+    let mut loader = match connection.config {
+        Some(path) => ConfigLoader::new().file(path),
+        None => ConfigLoader::new().default_file(),
+    };
+    // A profile chosen by name is used as written, so a key in `.env` cannot shadow it
+    if let Some(profile) = connection.profile {
+        loader = loader.profile(profile).without_env();
+    }
+    if let Some(api_key) = connection.api_key {
+        loader = loader.api_key(api_key);
+    }
+    if let Some(base_url) = connection.base_url {
+        loader = loader.base_url(base_url);
+    }
+    if let Some(board_id) = connection.board_id {
+        loader = loader.board_id(board_id);
+    }
+
+    let config = loader.load()?;
+    Ok((config.client()?, config.board_id().map(str::to_string)))
+}
+
+/// Writes a listing stream to a file in the format given by its extension
+///
+/// `columns` applies to CSV and GeoJSON files and `schema` to Parquet files. The file is
+/// finished even when the stream fails, keeping the listings fetched so far.
+async fn export<S>(
+    path: &Path,
+    columns: Option<&str>,
+    schema: Option<&str>,
+    listings: S,
+) -> Result<usize, RepliersError>
+where
+    S: futures::Stream<Item = Result<repliers_beta::Listing, RepliersError>>,
+{
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let (name, gzip) = match name.strip_suffix(".gz") {
+        Some(name) => (name, true),
+        None => (name.as_str(), false),
+    };
+    let extension = name.rsplit_once('.').map_or("", |(_, ext)| ext);
+    if gzip && !matches!(extension, "ndjson" | "jsonl") {
+        return Err(RepliersError::InvalidConfig(
+            "Only NDJSON exports can be gzipped".to_string(),
+        ));
+    }
+    if schema.is_some() && extension != "parquet" {
+        return Err(RepliersError::InvalidConfig(
+            "--schema only applies to .parquet exports".to_string(),
+        ));
+    }
+    let spec = || columns.map(ColumnSpec::parse).transpose();
+
+    match extension {
+        "csv" => {
+            let mut exporter = CsvExporter::create(path, spec()?.unwrap_or_default())?;
+            let result = exporter.write_stream(listings).await;
+            exporter.finish()?;
+            result
+        }
+        "geojson" => {
+            let options = GeoJsonOptions {
+                properties: spec()?,
+                ..Default::default()
+            };
+            let mut exporter = GeoJsonExporter::create(path, options)?;
+            let result = exporter.write_stream(listings).await;
+            let skipped = exporter.report().skipped.len();
+            exporter.finish()?;
+            if skipped > 0 {
+                eprintln!("Skipped {} listings without coordinates", skipped);
+            }
+            result
+        }
+        "ndjson" | "jsonl" => {
+            let mut writer = NdjsonWriter::create(path)?;
+            let result = writer.write_stream(listings).await;
+            writer.finish()?;
+            result
+        }
+        "json" => {
+            let file = File::create(path)
+                .map_err(|e| RepliersError::Storage(format!("{}: {}", path.display(), e)))?;
+            output::write_json_array(BufWriter::new(file), listings).await
+        }
+        #[cfg(feature = "parquet")]
+        "parquet" => {
+            use repliers_beta::export::parquet::{ParquetExporter, ParquetSchema};
+
+            if columns.is_some() {
+                return Err(RepliersError::InvalidConfig(
+                    "--columns does not apply to .parquet exports; use --schema".to_string(),
+                ));
+            }
+            let schema = match schema {
+                Some(schema) => ParquetSchema::parse(schema)?,
+                None => ParquetSchema::listings(),
+            };
+            let mut exporter = ParquetExporter::create(path, schema)?;
+            let result = exporter.write_stream(listings).await;
+            exporter.finish()?;
+            result
+        }
+        _ => Err(RepliersError::InvalidConfig(format!(
+            "Cannot tell the export format of '{}' (expected .csv, .json, .geojson, .ndjson, .jsonl{})",
+            path.display(),
+            if cfg!(feature = "parquet") { ", .parquet" } else { "" }
+        ))),
+    }
+}
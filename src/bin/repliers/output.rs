@@ -0,0 +1,246 @@
+//! Rendering records to standard output
+//!
+//! Table and CSV output go through the library's [`CsvExporter`], so both share its
+//! column specs and date/price formatting; tables are simply CSV rows laid out in
+//! aligned columns.
+
+use clap::ValueEnum;
+use futures::{Stream, StreamExt};
+use repliers_beta::export::csv::{Column, ColumnFormat, ColumnSpec, CsvExporter};
+use repliers_beta::export::ndjson::{Compression, NdjsonWriter};
+use repliers_beta::RepliersError;
+use serde::Serialize;
+use std::io::{self, BufWriter, Write};
+
+/// Longest cell shown in a table before it is cut short
+const MAX_CELL_WIDTH: usize = 40;
+
+/// Output format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Aligned columns for reading in a terminal
+    #[default]
+    Table,
+
+    /// A JSON document
+    Json,
+
+    /// One JSON record per line
+    Ndjson,
+
+    /// CSV with a header row
+    Csv,
+}
+
+/// Record layouts with their default columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Listing,
+    History,
+    Deleted,
+    Parameter,
+}
+
+impl Kind {
+    /// Columns shown when `--columns` is not given
+    ///
+    /// Tables get a compact layout; listing CSVs get the exporter's full default.
+    fn columns(self, format: Format) -> ColumnSpec {
+        match (self, format) {
+            (Self::Listing, Format::Csv) => ColumnSpec::default(),
+            (Self::Listing, _) => ColumnSpec::new([
+                Column::named("MLS", "mlsNumber"),
+                Column::named("Status", "lastStatus"),
+                Column::named("Price", "listPrice").format(ColumnFormat::Price),
+                Column::named("Sold", "soldPrice").format(ColumnFormat::Price),
+                Column::named("Beds", "details.numBedrooms"),
+                Column::named("Baths", "details.numBathrooms"),
+                Column::named("Type", "details.propertyType"),
+                Column::named("No.", "address.streetNumber"),
+                Column::named("Street", "address.streetName"),
+                Column::named("Unit", "address.unitNumber"),
+                Column::named("City", "address.city"),
+                Column::named("Listed", "listDate").format(ColumnFormat::Date),
+            ]),
+            (Self::History, _) => ColumnSpec::new([
+                Column::named("MLS", "mls_number"),
+                Column::named("Status", "status"),
+                Column::named("List Price", "list_price").format(ColumnFormat::Price),
+                Column::named("Sold Price", "sold_price").format(ColumnFormat::Price),
+                Column::named("Listed", "list_date").format(ColumnFormat::Date),
+                Column::named("Sold", "sold_date").format(ColumnFormat::Date),
+                Column::named("Type", "property_type"),
+                Column::named("Beds", "bedrooms"),
+            ]),
+            (Self::Deleted, _) => ColumnSpec::new([
+                Column::named("MLS", "mlsNumber"),
+                Column::named("Board", "boardId"),
+                Column::named("No.", "address.streetNumber"),
+                Column::named("Street", "address.streetName"),
+                Column::named("City", "address.city"),
+                Column::named("Updated", "timestamps.listingUpdated").format(ColumnFormat::Date),
+            ]),
+            (Self::Parameter, _) => ColumnSpec::new([
+                Column::named("Parameter", "name"),
+                Column::named("Value", "value").format(ColumnFormat::Text),
+            ]),
+        }
+    }
+}
+
+/// Writes records to standard output in one format
+#[derive(Debug)]
+pub struct Output {
+    format: Format,
+    columns: Option<ColumnSpec>,
+}
+
+impl Output {
+    /// `columns` is a [`ColumnSpec`] string overriding the default columns of table and
+    /// CSV output
+    pub fn new(format: Format, columns: Option<&str>) -> Result<Self, RepliersError> {
+        Ok(Self {
+            format,
+            columns: columns.map(ColumnSpec::parse).transpose()?,
+        })
+    }
+
+    /// Writes a single record; JSON output is the bare object rather than an array
+    pub async fn write_one<T: Serialize>(
+        &self,
+        kind: Kind,
+        record: &T,
+    ) -> Result<(), RepliersError> {
+        if self.format == Format::Json {
+            let mut stdout = io::stdout().lock();
+            serde_json::to_writer_pretty(&mut stdout, record).map_err(|e| {
+                RepliersError::ParseError(format!("Cannot serialize record: {}", e))
+            })?;
+            return writeln!(stdout).map_err(output_error);
+        }
+        let records = futures::stream::iter([Ok::<_, RepliersError>(record)]);
+        self.write_stream(kind, records).await.map(drop)
+    }
+
+    /// Writes every record of a stream, returning how many were written
+    ///
+    /// CSV, NDJSON and JSON are written as records arrive; tables are laid out once the
+    /// stream ends. Records received before an error are still written.
+    pub async fn write_stream<T, S>(&self, kind: Kind, stream: S) -> Result<usize, RepliersError>
+    where
+        T: Serialize,
+        S: Stream<Item = Result<T, RepliersError>>,
+    {
+        let stdout = BufWriter::new(io::stdout());
+        let columns = || {
+            self.columns
+                .clone()
+                .unwrap_or_else(|| kind.columns(self.format))
+        };
+        match self.format {
+            Format::Json => write_json_array(stdout, stream).await,
+            Format::Ndjson => {
+                let mut writer = NdjsonWriter::new(stdout, Compression::None);
+                let result = writer.write_stream(stream).await;
+                writer.finish()?;
+                result
+            }
+            Format::Csv => {
+                let mut exporter = CsvExporter::new(stdout, columns());
+                let result = exporter.write_stream(stream).await;
+                exporter.finish()?;
+                result
+            }
+            Format::Table => {
+                let mut exporter = CsvExporter::new(Vec::new(), columns());
+                let result = exporter.write_stream(stream).await;
+                let csv = exporter.finish()?;
+                let rows = print_table(stdout, &csv)?;
+                eprintln!("({} {})", rows, if rows == 1 { "row" } else { "rows" });
+                result
+            }
+        }
+    }
+}
+
+/// Writes a stream of records as a JSON array, one pretty-printed element at a time
+pub async fn write_json_array<W, T, S>(mut writer: W, stream: S) -> Result<usize, RepliersError>
+where
+    W: Write,
+    T: Serialize,
+    S: Stream<Item = Result<T, RepliersError>>,
+{
+    let mut stream = std::pin::pin!(stream);
+    let mut written = 0;
+    let mut result = Ok(());
+    writer.write_all(b"[").map_err(output_error)?;
+    while let Some(record) = stream.next().await {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        };
+        let separator: &[u8] = if written == 0 { b"\n" } else { b",\n" };
+        writer.write_all(separator).map_err(output_error)?;
+        serde_json::to_writer_pretty(&mut writer, &record)
+            .map_err(|e| RepliersError::ParseError(format!("Cannot serialize record: {}", e)))?;
+        written += 1;
+    }
+    writer.write_all(b"\n]\n").map_err(output_error)?;
+    writer.flush().map_err(output_error)?;
+    result.map(|()| written)
+}
+
+/// Lays out CSV rows as a table, returning the number of data rows
+fn print_table(mut writer: impl Write, csv: &[u8]) -> Result<usize, RepliersError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(csv);
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| RepliersError::ParseError(e.to_string()))?;
+        rows.push(record.iter().map(cell).collect());
+    }
+
+    let columns = rows.first().map_or(0, Vec::len);
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            rows.iter()
+                .map(|row| row.get(i).map_or(0, |c| c.chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for (index, row) in rows.iter().enumerate() {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        writeln!(writer, "{}", line.join("  ").trim_end()).map_err(output_error)?;
+        if index == 0 {
+            let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+            writeln!(writer, "{}", rule.join("  ")).map_err(output_error)?;
+        }
+    }
+    writer.flush().map_err(output_error)?;
+    Ok(rows.len().saturating_sub(1))
+}
+
+/// Flattens a cell onto one line and cuts it to [`MAX_CELL_WIDTH`] characters
+fn cell(value: &str) -> String {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if value.chars().count() <= MAX_CELL_WIDTH {
+        return value;
+    }
+    let mut cut: String = value.chars().take(MAX_CELL_WIDTH - 1).collect();
+    cut.push('…');
+    cut
+}
+
+fn output_error(e: io::Error) -> RepliersError {
+    RepliersError::Storage(format!("Cannot write output: {}", e))
+}
@@ -3,8 +3,21 @@
 //! This module contains the main client struct and methods for interacting
 //! with the Repliers API.
 
-use crate::error::RepliersError;
-use reqwest::Client;
+use crate::error::{ApiErrorContext, RepliersError};
+use crate::rate_limit::{RateLimit, RateLimiter};
+use crate::retry::{parse_retry_after, RetryPolicy};
+use crate::transport::{HttpTransport, RecordingTransport, Transport};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Proxy};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default base URL for the Repliers API
+pub const DEFAULT_BASE_URL: &str = "https://api.repliers.io";
+
+/// Default `User-Agent` sent with every request
+pub const DEFAULT_USER_AGENT: &str = concat!("repliers_beta/", env!("CARGO_PKG_VERSION"));
 
 /// The main client for interacting with the Repliers API
 ///
@@ -15,6 +28,10 @@ use reqwest::Client;
 ///
 /// let client = RepliersClient::new("your_api_key".to_string());
 /// ```
+///
+/// Use [`RepliersClient::builder`] to customize the base URL, timeouts, headers or
+/// the underlying HTTP client.
+#[derive(Clone)]
 pub struct RepliersClient {
     /// HTTP client for making requests
     client: Client,
@@ -22,6 +39,12 @@ pub struct RepliersClient {
     api_key: String,
     /// Base URL for the Repliers API
     base_url: String,
+    /// Policy for retrying transient failures
+    retry_policy: RetryPolicy,
+    /// Optional rate limiter shared by all clones of this client
+    rate_limiter: Option<RateLimiter>,
+    /// Transport that executes the built requests
+    transport: Arc<dyn Transport>,
 }
 
 impl RepliersClient {
@@ -39,20 +62,52 @@ impl RepliersClient {
     /// let client = RepliersClient::new("your_api_key".to_string());
     /// ```
     pub fn new(api_key: String) -> Self {
+        let client = Client::new();
         Self {
-            client: Client::new(),
+            transport: Arc::new(HttpTransport::new(client.clone())),
+            client,
             api_key,
-            base_url: "https://api.repliers.io".to_string(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: None,
         }
     }
 
+    /// Creates a builder for configuring a client
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use repliers_beta::RepliersClient;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = RepliersClient::builder()
+    ///     .api_key("your_api_key")
+    ///     .base_url("http://localhost:8080")
+    ///     .timeout(Duration::from_secs(30))
+    ///     .connect_timeout(Duration::from_secs(5))
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> RepliersClientBuilder {
+        RepliersClientBuilder::default()
+    }
+
     /// Creates a new client by reading the API key from the environment
     ///
-    /// Looks for the `REPLIERS_API_KEY` environment variable.
+    /// Looks for the `REPLIERS_API_KEY` environment variable. The following optional
+    /// variables are also honoured:
+    ///
+    /// * `REPLIERS_BASE_URL` - Overrides the API base URL
+    /// * `REPLIERS_TIMEOUT_SECS` - Per-request timeout in seconds
+    /// * `REPLIERS_CONNECT_TIMEOUT_SECS` - Connection timeout in seconds
     ///
     /// # Errors
     ///
-    /// Returns an error if the environment variable is not set.
+    /// Returns an error if `REPLIERS_API_KEY` is not set, or if one of the optional
+    /// variables has an invalid value.
     ///
     /// # Examples
     ///
@@ -70,7 +125,19 @@ impl RepliersClient {
         let api_key =
             std::env::var("REPLIERS_API_KEY").map_err(|_| RepliersError::InvalidApiKey)?;
 
-        Ok(Self::new(api_key))
+        let mut builder = Self::builder().api_key(api_key);
+
+        if let Ok(base_url) = std::env::var("REPLIERS_BASE_URL") {
+            builder = builder.base_url(base_url);
+        }
+        if let Some(timeout) = env_duration_secs("REPLIERS_TIMEOUT_SECS")? {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = env_duration_secs("REPLIERS_CONNECT_TIMEOUT_SECS")? {
+            builder = builder.connect_timeout(timeout);
+        }
+
+        builder.build()
     }
 
     /// Returns a reference to the base URL
@@ -78,6 +145,18 @@ impl RepliersClient {
         &self.base_url
     }
 
+    /// Returns the retry policy used by this client
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Returns the rate limiter, if one is configured
+    ///
+    /// Use [`RateLimiter::budget`] to inspect the remaining request budget.
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_ref()
+    }
+
     /// Returns a reference to the HTTP client
     pub(crate) fn client(&self) -> &Client {
         &self.client
@@ -104,20 +183,86 @@ impl RepliersClient {
     ///
     /// # Errors
     ///
-    /// Returns `RepliersError::ApiError` if the response status is not successful
+    /// Returns the `RepliersError` variant matching the status (e.g. `NotFound`,
+    /// `RateLimited`) if the response status is not successful
     pub(crate) async fn check_response(
         response: reqwest::Response,
+        method: &reqwest::Method,
+        path: &str,
     ) -> Result<reqwest::Response, RepliersError> {
         if !response.status().is_success() {
             let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(RepliersError::ApiError(format!(
-                "Status {}: {}",
-                status, error_text
-            )));
+            let path = path.to_string();
+            let headers = response.headers().clone();
+            let error_text = response.text().await.unwrap_or_default();
+
+            let context = ApiErrorContext::new(status, method.clone(), path, &headers, &error_text);
+            return Err(RepliersError::from_response(
+                context,
+                parse_retry_after(&headers),
+            ));
+        }
+        Ok(response)
+    }
+
+    /// Helper method to send a request, retrying transient failures
+    ///
+    /// Retries are only attempted when `retryable` is true and the request body can be
+    /// cloned. Non-success responses that are not retried are turned into errors via
+    /// [`check_response`](Self::check_response).
+    ///
+    /// # Errors
+    ///
+    /// Returns the last transport or API error once all attempts are exhausted.
+    pub(crate) async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        retryable: bool,
+    ) -> Result<reqwest::Response, RepliersError> {
+        let request = request.build()?;
+        let method = request.method().clone();
+        let path = request.url().path().to_string();
+        let policy = &self.retry_policy;
+        let mut attempt = 1;
+
+        loop {
+            let can_retry = retryable && attempt < policy.max_attempts;
+            let current = match request.try_clone() {
+                Some(current) if can_retry => current,
+                _ => {
+                    let response = self.execute(request).await?;
+                    return Self::check_response(response, &method, &path).await;
+                }
+            };
+
+            let delay = match self.execute(current).await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) if policy.is_retryable_status(response.status()) => {
+                    policy.delay_for(attempt, Some(response.headers()))
+                }
+                Ok(response) => return Self::check_response(response, &method, &path).await,
+                Err(RepliersError::RequestFailed(e)) if policy.is_retryable_error(&e) => {
+                    policy.delay_for(attempt, None)
+                }
+                Err(e) => return Err(e),
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Executes a single request through the transport, applying the rate limiter if
+    /// configured
+    async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response, RepliersError> {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+
+        let response = self.transport.execute(request).await?;
+
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.update_from_headers(response.headers());
         }
         Ok(response)
     }
@@ -150,3 +295,214 @@ impl RepliersClient {
     // - get_address_history (endpoints/history.rs)
     // - get_deleted_listings (endpoints/deleted.rs)
 }
+
+/// Builder for configuring a [`RepliersClient`]
+///
+/// Created with [`RepliersClient::builder`]. When a pre-built `reqwest::Client` is supplied
+/// via [`http_client`](Self::http_client), the timeout, user-agent, header and proxy
+/// settings are ignored because they are baked into that client.
+///
+/// A custom [`Transport`] set via [`transport`](Self::transport) replaces the network
+/// layer entirely, e.g. to replay recorded responses in tests. [`record`](Self::record)
+/// records whichever transport the client ends up using.
+#[derive(Debug, Default)]
+pub struct RepliersClientBuilder {
+    api_key: Option<String>,
+    base_url: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    default_headers: HeaderMap,
+    proxies: Vec<Proxy>,
+    no_proxy: bool,
+    http_client: Option<Client>,
+    retry_policy: Option<RetryPolicy>,
+    rate_limiter: Option<RateLimiter>,
+    transport: Option<Arc<dyn Transport>>,
+    record_path: Option<PathBuf>,
+}
+
+impl RepliersClientBuilder {
+    /// Sets the API key used for authentication
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Sets the base URL (defaults to `https://api.repliers.io`)
+    ///
+    /// Useful for pointing the client at a staging host or a local mock server.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Sets the total timeout applied to each request
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for establishing a connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header (defaults to `repliers_beta/<version>`)
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Adds a header sent with every request
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.default_headers.insert(name, value);
+        self
+    }
+
+    /// Replaces the set of headers sent with every request
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Routes requests through the given proxy
+    ///
+    /// May be called multiple times to add several proxies.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Disables all proxies, including those picked up from the environment
+    pub fn no_proxy(mut self) -> Self {
+        self.no_proxy = true;
+        self
+    }
+
+    /// Uses a pre-built `reqwest::Client` instead of constructing one
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Sets the policy for retrying transient failures
+    ///
+    /// Defaults to [`RetryPolicy::default`]; use [`RetryPolicy::none`] to disable retries.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Limits outgoing requests to the given rate
+    ///
+    /// The limiter is shared by all clones of the built client.
+    pub fn rate_limit(mut self, limit: RateLimit) -> Self {
+        self.rate_limiter = Some(RateLimiter::new(limit));
+        self
+    }
+
+    /// Uses an existing rate limiter, e.g. to share one budget between several clients
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Executes requests through a custom transport instead of the network
+    ///
+    /// See [`crate::transport`] for the recording and replay transports.
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Records every request and response into a cassette file
+    ///
+    /// The client's transport (its own HTTP client unless [`transport`](Self::transport)
+    /// is set) is wrapped in a [`RecordingTransport`], and the cassette is written when
+    /// the last clone of the client is dropped.
+    pub fn record(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record_path = Some(path.into());
+        self
+    }
+
+    /// Builds the [`RepliersClient`]
+    ///
+    /// # Errors
+    ///
+    /// Returns `RepliersError::InvalidApiKey` if no API key was set,
+    /// `RepliersError::InvalidConfig` if the base URL is not a valid URL, or
+    /// `RepliersError::RequestFailed` if the HTTP client could not be constructed.
+    pub fn build(self) -> Result<RepliersClient, RepliersError> {
+        let api_key = self.api_key.ok_or(RepliersError::InvalidApiKey)?;
+
+        let base_url = self
+            .base_url
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        let base_url = base_url.trim_end_matches('/').to_string();
+        reqwest::Url::parse(&base_url).map_err(|e| {
+            RepliersError::InvalidConfig(format!("invalid base URL '{}': {}", base_url, e))
+        })?;
+
+        let client = match self.http_client {
+            Some(client) => client,
+            None => {
+                let mut builder = Client::builder()
+                    .user_agent(
+                        self.user_agent
+                            .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+                    )
+                    .default_headers(self.default_headers);
+
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if self.no_proxy {
+                    builder = builder.no_proxy();
+                }
+                for proxy in self.proxies {
+                    builder = builder.proxy(proxy);
+                }
+
+                builder.build()?
+            }
+        };
+
+        let mut transport = self
+            .transport
+            .unwrap_or_else(|| Arc::new(HttpTransport::new(client.clone())));
+        if let Some(path) = self.record_path {
+            transport = Arc::new(RecordingTransport::wrap(transport, path));
+        }
+
+        Ok(RepliersClient {
+            transport,
+            client,
+            api_key,
+            base_url,
+            retry_policy: self.retry_policy.unwrap_or_default(),
+            rate_limiter: self.rate_limiter,
+        })
+    }
+}
+
+/// Reads an optional duration in whole seconds from an environment variable
+fn env_duration_secs(name: &str) -> Result<Option<Duration>, RepliersError> {
+    match std::env::var(name) {
+        Ok(value) => value
+            .trim()
+            .parse::<u64>()
+            .map(|secs| Some(Duration::from_secs(secs)))
+            .map_err(|_| {
+                RepliersError::InvalidConfig(format!(
+                    "{} must be a whole number of seconds, got '{}'",
+                    name, value
+                ))
+            }),
+        Err(_) => Ok(None),
+    }
+}
@@ -1,176 +1,805 @@
-//! Configuration module for examples
+//! Layered client configuration with named profiles
 //!
-//! This module provides configuration loading from a TOML file for all examples.
-//! It allows externalizing test data and parameters instead of hardcoding them in examples.
+//! A [`Config`] is resolved from four layers, each overriding the one before it:
+//!
+//! 1. Built-in defaults ([`RetryPolicy::default`], no rate limit, the public API URL)
+//! 2. A TOML file: its top-level settings, then the selected `[profiles.<name>]` table
+//! 3. `REPLIERS_*` environment variables
+//! 4. Explicit overrides passed to the [`ConfigLoader`]
+//!
+//! The file holds settings shared by every profile at the top level, and one table per
+//! profile. The profile is chosen with [`ConfigLoader::profile`], then `REPLIERS_PROFILE`,
+//! then the file's `default_profile`:
+//!
+//! ```toml
+//! default_profile = "test"
+//! timeout_secs = 30
+//!
+//! [profiles.test]
+//! api_key = "your-test-key"
+//!
+//! [profiles.prod]
+//! api_key = "your-production-key"
+//! base_url = "https://api.repliers.io"
+//! board_id = "1"
+//!
+//! [profiles.prod.retry]
+//! max_attempts = 5
+//! max_delay_ms = 60000
+//!
+//! [profiles.prod.rate_limit]
+//! requests_per_minute = 120
+//! burst = 10
+//! ```
+//!
+//! | Setting | Environment variable |
+//! |---|---|
+//! | `api_key` | `REPLIERS_API_KEY` |
+//! | `base_url` | `REPLIERS_BASE_URL` |
+//! | `board_id` | `REPLIERS_BOARD_ID` |
+//! | `timeout_secs` | `REPLIERS_TIMEOUT_SECS` |
+//! | `connect_timeout_secs` | `REPLIERS_CONNECT_TIMEOUT_SECS` |
+//! | `retry.max_attempts` | `REPLIERS_RETRY_MAX_ATTEMPTS` |
+//! | `retry.base_delay_ms` | `REPLIERS_RETRY_BASE_DELAY_MS` |
+//! | `retry.max_delay_ms` | `REPLIERS_RETRY_MAX_DELAY_MS` |
+//! | `retry.jitter` | `REPLIERS_RETRY_JITTER` |
+//! | `retry.retry_search` | `REPLIERS_RETRY_SEARCH` |
+//! | `rate_limit.requests_per_second` | `REPLIERS_RATE_LIMIT_PER_SECOND` |
+//! | `rate_limit.requests_per_minute` | `REPLIERS_RATE_LIMIT_PER_MINUTE` |
+//! | `rate_limit.burst` | `REPLIERS_RATE_LIMIT_BURST` |
+//!
+//! Every layer is validated as it is read, so a [`ConfigError::InvalidValue`] names the
+//! setting as it was written and the layer it came from. Settings that must agree with
+//! each other (such as the retry delays) are checked again once the layers are merged,
+//! since each may come from a different layer.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use repliers_beta::config::{Config, ConfigLoader};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // ~/.config/repliers/config.toml (or $REPLIERS_CONFIG), then the environment
+//! let client = Config::load()?.client()?;
+//!
+//! // A specific file and profile, with the board pinned by the caller
+//! let config = ConfigLoader::new()
+//!     .file("repliers.toml")
+//!     .profile("prod")
+//!     .board_id("1")
+//!     .load()?;
+//! let client = config.client()?;
+//! # Ok(())
+//! # }
+//! ```
 
+use crate::client::{RepliersClient, RepliersClientBuilder};
+use crate::error::{ConfigError, RepliersError};
+use crate::rate_limit::RateLimit;
+use crate::retry::RetryPolicy;
 use serde::Deserialize;
-use std::fs;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 
-/// Main configuration structure
-#[derive(Debug, Deserialize)]
-pub struct Config {
-    pub search: SearchConfig,
-    pub ai_search: AiSearchConfig,
-    pub listing: ListingConfig,
-    pub similar: SimilarConfig,
-    pub address_history: AddressHistoryConfig,
-    pub deleted: DeletedConfig,
-    pub discovery: DiscoveryConfig,
-    pub export: ExportConfig,
-    pub demo: DemoConfig,
-}
-
-/// Search listings configuration
-#[derive(Debug, Deserialize)]
-pub struct SearchConfig {
-    pub example1: SearchExample,
-    pub example2: SearchExample,
-}
-
-/// Individual search example
-#[derive(Debug, Deserialize)]
-pub struct SearchExample {
-    pub city: String,
-    pub status: Vec<String>,
-    pub min_price: f64,
-    pub max_price: f64,
-    pub bedrooms: u32,
-    pub property_type: Vec<String>,
-    pub page: u32,
-    pub results_per_page: u32,
-}
-
-/// AI search configuration
-#[derive(Debug, Deserialize)]
-pub struct AiSearchConfig {
-    pub prompt: String,
-}
-
-/// Single listing configuration
-#[derive(Debug, Deserialize)]
-pub struct ListingConfig {
-    pub mls_number: String,
-    #[serde(default)]
-    pub board_id: Option<String>,
+/// Environment variable naming the configuration file
+pub const CONFIG_ENV: &str = "REPLIERS_CONFIG";
+
+/// Environment variable selecting the profile
+pub const PROFILE_ENV: &str = "REPLIERS_PROFILE";
+
+/// Settings read from the environment, keyed by their name in the file
+const ENV_VARS: [(&str, &str); 13] = [
+    ("api_key", "REPLIERS_API_KEY"),
+    ("base_url", "REPLIERS_BASE_URL"),
+    ("board_id", "REPLIERS_BOARD_ID"),
+    ("timeout_secs", "REPLIERS_TIMEOUT_SECS"),
+    ("connect_timeout_secs", "REPLIERS_CONNECT_TIMEOUT_SECS"),
+    ("retry.max_attempts", "REPLIERS_RETRY_MAX_ATTEMPTS"),
+    ("retry.base_delay_ms", "REPLIERS_RETRY_BASE_DELAY_MS"),
+    ("retry.max_delay_ms", "REPLIERS_RETRY_MAX_DELAY_MS"),
+    ("retry.jitter", "REPLIERS_RETRY_JITTER"),
+    ("retry.retry_search", "REPLIERS_RETRY_SEARCH"),
+    (
+        "rate_limit.requests_per_second",
+        "REPLIERS_RATE_LIMIT_PER_SECOND",
+    ),
+    (
+        "rate_limit.requests_per_minute",
+        "REPLIERS_RATE_LIMIT_PER_MINUTE",
+    ),
+    ("rate_limit.burst", "REPLIERS_RATE_LIMIT_BURST"),
+];
+
+/// Layer a setting was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    /// The TOML configuration file
+    File,
+
+    /// `REPLIERS_*` environment variables
+    Environment,
+
+    /// Overrides passed to [`ConfigLoader`]
+    Override,
+
+    /// The result of merging every layer
+    Merged,
 }
 
-/// Similar listings configuration
-#[derive(Debug, Deserialize)]
-pub struct SimilarConfig {
-    pub mls_number: String,
-    pub radius: f64,
-    pub list_price_range: f64,
-}
-
-/// Address history configuration
-#[derive(Debug, Deserialize)]
-pub struct AddressHistoryConfig {
-    pub street_number: String,
-    pub street_name: String,
-    pub city: String,
-    pub state: String,
-    #[serde(default)]
-    pub zip: Option<String>,
-    #[serde(default)]
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::File => "config file",
+            Self::Environment => "environment",
+            Self::Override => "overrides",
+            Self::Merged => "combined settings",
+        })
+    }
+}
+
+/// Client settings of one layer or profile; unset fields fall through to lower layers
+#[derive(Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// API key sent with every request
+    pub api_key: Option<String>,
+
+    /// API base URL
+    pub base_url: Option<String>,
+
+    /// MLS board to query, for keys with multi-board access
     pub board_id: Option<String>,
+
+    /// Per-request timeout in seconds
+    pub timeout_secs: Option<u64>,
+
+    /// Connection timeout in seconds
+    pub connect_timeout_secs: Option<u64>,
+
+    /// Retry settings
+    pub retry: RetrySettings,
+
+    /// Client-side rate limit
+    pub rate_limit: RateLimitSettings,
+}
+
+/// Retry settings applied on top of [`RetryPolicy::default`]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetrySettings {
+    /// Total attempts per request, including the first (1 disables retries)
+    pub max_attempts: Option<u32>,
+
+    /// Delay before the first retry, in milliseconds
+    pub base_delay_ms: Option<u64>,
+
+    /// Upper bound on any single delay, in milliseconds
+    pub max_delay_ms: Option<u64>,
+
+    /// Whether to randomize delays
+    pub jitter: Option<bool>,
+
+    /// Whether searches (sent as POST) may be retried
+    pub retry_search: Option<bool>,
 }
 
-/// Deleted listings configuration
-#[derive(Debug, Deserialize)]
-pub struct DeletedConfig {
-    pub min_updated_on: String,
-    pub max_updated_on: String,
-    pub page: u32,
-    pub results_per_page: u32,
-}
-
-/// Discovery example configuration
-#[derive(Debug, Deserialize)]
-pub struct DiscoveryConfig {
-    pub test_cities: Vec<String>,
-    pub property_types: Vec<String>,
-    pub statuses: Vec<String>,
-    pub price_ranges: Vec<PriceRange>,
-}
-
-/// Price range for discovery
-#[derive(Debug, Deserialize)]
-pub struct PriceRange {
-    pub label: String,
-    pub min_price: Option<f64>,
-    pub max_price: Option<f64>,
-}
-
-/// Export listings configuration
-#[derive(Debug, Deserialize)]
-pub struct ExportConfig {
-    pub city: String,
-    pub status: Vec<String>,
-    pub results_per_page: u32,
-    pub output_file: String,
-}
-
-/// Comprehensive demo configuration
-#[derive(Debug, Deserialize)]
-pub struct DemoConfig {
-    pub city: String,
-    pub status: Vec<String>,
-    pub min_price: f64,
-    pub max_price: f64,
-    pub bedrooms: u32,
-    pub property_type: Vec<String>,
-    pub results_per_page: u32,
-    pub mls_number: String,
-    pub similar_radius: f64,
-    pub similar_price_range: f64,
-    pub deleted_min_date: String,
-    pub deleted_max_date: String,
-    pub deleted_results_per_page: u32,
-    pub output_file: String,
+/// Client-side rate limit; no limit is applied unless a rate is set
+///
+/// A rate set in a higher layer replaces both rates of the layers below it, so switching
+/// from a per-second to a per-minute limit needs no unsetting.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitSettings {
+    /// Sustained requests per second
+    pub requests_per_second: Option<f64>,
+
+    /// Sustained requests per minute
+    pub requests_per_minute: Option<f64>,
+
+    /// Requests that may be sent back-to-back; defaults to the per-second rate, rounded
+    /// up (at least 1)
+    pub burst: Option<u32>,
 }
 
-impl Config {
-    /// Load configuration from a TOML file
+impl Settings {
+    /// Returns these settings with every field set in `over` replaced
+    pub fn merge(self, over: Settings) -> Settings {
+        Settings {
+            api_key: over.api_key.or(self.api_key),
+            base_url: over.base_url.or(self.base_url),
+            board_id: over.board_id.or(self.board_id),
+            timeout_secs: over.timeout_secs.or(self.timeout_secs),
+            connect_timeout_secs: over.connect_timeout_secs.or(self.connect_timeout_secs),
+            retry: RetrySettings {
+                max_attempts: over.retry.max_attempts.or(self.retry.max_attempts),
+                base_delay_ms: over.retry.base_delay_ms.or(self.retry.base_delay_ms),
+                max_delay_ms: over.retry.max_delay_ms.or(self.retry.max_delay_ms),
+                jitter: over.retry.jitter.or(self.retry.jitter),
+                retry_search: over.retry.retry_search.or(self.retry.retry_search),
+            },
+            rate_limit: {
+                let burst = over.rate_limit.burst.or(self.rate_limit.burst);
+                let rates = if over.rate_limit.has_rate() {
+                    over.rate_limit
+                } else {
+                    self.rate_limit
+                };
+                RateLimitSettings { burst, ..rates }
+            },
+        }
+    }
+
+    /// Checks every set value, naming settings through `key`
+    fn validate(&self, layer: Layer, key: impl Fn(&str) -> String) -> Result<(), ConfigError> {
+        let invalid = |field: &str, message: &str| ConfigError::InvalidValue {
+            key: key(field),
+            layer,
+            message: message.to_string(),
+        };
+
+        if self.api_key.as_deref().is_some_and(|k| k.trim().is_empty()) {
+            return Err(invalid("api_key", "must not be empty"));
+        }
+        if let Some(base_url) = &self.base_url {
+            match reqwest::Url::parse(base_url) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+                Ok(_) => return Err(invalid("base_url", "must be an http or https URL")),
+                Err(e) => return Err(invalid("base_url", &e.to_string())),
+            }
+        }
+        if self
+            .board_id
+            .as_deref()
+            .is_some_and(|b| b.trim().is_empty())
+        {
+            return Err(invalid("board_id", "must not be empty"));
+        }
+        if self.timeout_secs == Some(0) {
+            return Err(invalid("timeout_secs", "must be at least 1"));
+        }
+        if self.connect_timeout_secs == Some(0) {
+            return Err(invalid("connect_timeout_secs", "must be at least 1"));
+        }
+
+        if self.retry.max_attempts == Some(0) {
+            return Err(invalid("retry.max_attempts", "must be at least 1"));
+        }
+
+        let rate_limit = &self.rate_limit;
+        for (field, rate) in [
+            (
+                "rate_limit.requests_per_second",
+                rate_limit.requests_per_second,
+            ),
+            (
+                "rate_limit.requests_per_minute",
+                rate_limit.requests_per_minute,
+            ),
+        ] {
+            if rate.is_some_and(|r| !r.is_finite() || r <= 0.0) {
+                return Err(invalid(field, "must be a positive number"));
+            }
+        }
+        if rate_limit.burst == Some(0) {
+            return Err(invalid("rate_limit.burst", "must be at least 1"));
+        }
+        self.validate_combination(layer, key)
+    }
+
+    /// Checks the settings that must agree with each other
+    fn validate_combination(
+        &self,
+        layer: Layer,
+        key: impl Fn(&str) -> String,
+    ) -> Result<(), ConfigError> {
+        let invalid = |field: &str, message: &str| ConfigError::InvalidValue {
+            key: key(field),
+            layer,
+            message: message.to_string(),
+        };
+
+        if let (Some(base), Some(max)) = (self.retry.base_delay_ms, self.retry.max_delay_ms) {
+            if base > max {
+                return Err(invalid(
+                    "retry.base_delay_ms",
+                    &format!("{} must not exceed retry.max_delay_ms ({})", base, max),
+                ));
+            }
+        }
+        let rate_limit = &self.rate_limit;
+        if rate_limit.requests_per_second.is_some() && rate_limit.requests_per_minute.is_some() {
+            return Err(invalid(
+                "rate_limit.requests_per_minute",
+                "cannot be combined with rate_limit.requests_per_second",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sets one field from its textual value, as read from the environment
+    fn set(&mut self, field: &str, value: String) -> Result<(), String> {
+        match field {
+            "api_key" => self.api_key = Some(value),
+            "base_url" => self.base_url = Some(value),
+            "board_id" => self.board_id = Some(value),
+            "timeout_secs" => self.timeout_secs = Some(parse(&value)?),
+            "connect_timeout_secs" => self.connect_timeout_secs = Some(parse(&value)?),
+            "retry.max_attempts" => self.retry.max_attempts = Some(parse(&value)?),
+            "retry.base_delay_ms" => self.retry.base_delay_ms = Some(parse(&value)?),
+            "retry.max_delay_ms" => self.retry.max_delay_ms = Some(parse(&value)?),
+            "retry.jitter" => self.retry.jitter = Some(parse_bool(&value)?),
+            "retry.retry_search" => self.retry.retry_search = Some(parse_bool(&value)?),
+            "rate_limit.requests_per_second" => {
+                self.rate_limit.requests_per_second = Some(parse(&value)?)
+            }
+            "rate_limit.requests_per_minute" => {
+                self.rate_limit.requests_per_minute = Some(parse(&value)?)
+            }
+            "rate_limit.burst" => self.rate_limit.burst = Some(parse(&value)?),
+            _ => unreachable!("unknown setting {}", field),
+        }
+        Ok(())
+    }
+}
+
+impl RateLimitSettings {
+    fn has_rate(&self) -> bool {
+        self.requests_per_second.is_some() || self.requests_per_minute.is_some()
+    }
+}
+
+// The API key is left out so settings can be logged
+impl fmt::Debug for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Settings")
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field("base_url", &self.base_url)
+            .field("board_id", &self.board_id)
+            .field("timeout_secs", &self.timeout_secs)
+            .field("connect_timeout_secs", &self.connect_timeout_secs)
+            .field("retry", &self.retry)
+            .field("rate_limit", &self.rate_limit)
+            .finish()
+    }
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<T, String>
+where
+    T::Err: fmt::Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e| format!("'{}': {}", value, e))
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "1" | "on" => Ok(true),
+        "false" | "no" | "0" | "off" => Ok(false),
+        _ => Err(format!("'{}' is not true or false", value)),
+    }
+}
+
+/// Where the configuration file comes from
+#[derive(Debug, Clone, Default)]
+enum FileSource {
+    /// No file
+    #[default]
+    None,
+
+    /// `REPLIERS_CONFIG`, or `~/.config/repliers/config.toml` if it exists
+    Default,
+
+    /// A file that must exist
+    Path(PathBuf),
+}
+
+/// Where environment variables are read from
+#[derive(Debug, Clone, Default)]
+enum EnvSource {
+    #[default]
+    Process,
+    Vars(HashMap<String, String>),
+    Disabled,
+}
+
+impl EnvSource {
+    /// Returns a variable's value; empty values count as unset
+    fn get(&self, name: &str) -> Option<String> {
+        let value = match self {
+            Self::Process => std::env::var(name).ok(),
+            Self::Vars(vars) => vars.get(name).cloned(),
+            Self::Disabled => None,
+        };
+        value.filter(|v| !v.trim().is_empty())
+    }
+}
+
+/// Contents of a configuration file
+#[derive(Debug, Default)]
+struct ConfigFile {
+    default_profile: Option<String>,
+    shared: Settings,
+    profiles: BTreeMap<String, Settings>,
+}
+
+impl ConfigFile {
+    fn read(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parse_error = |message: String| ConfigError::Parse {
+            path: path.to_path_buf(),
+            message,
+        };
+
+        let mut table: toml::Table = contents
+            .parse()
+            .map_err(|e: toml::de::Error| parse_error(e.message().to_string()))?;
+        let default_profile = match table.remove("default_profile") {
+            None => None,
+            Some(toml::Value::String(name)) => Some(name),
+            Some(other) => {
+                return Err(parse_error(format!(
+                    "default_profile must be a string, found {}",
+                    other.type_str()
+                )))
+            }
+        };
+        let profiles = match table.remove("profiles") {
+            None => BTreeMap::new(),
+            Some(toml::Value::Table(profiles)) => profiles
+                .into_iter()
+                .map(|(name, value)| {
+                    let settings = value.try_into().map_err(|e: toml::de::Error| {
+                        parse_error(format!("[profiles.{}]: {}", name, e.message()))
+                    })?;
+                    Ok((name, settings))
+                })
+                .collect::<Result<_, ConfigError>>()?,
+            Some(other) => {
+                return Err(parse_error(format!(
+                    "profiles must be a table, found {}",
+                    other.type_str()
+                )))
+            }
+        };
+        let shared = toml::Value::Table(table)
+            .try_into()
+            .map_err(|e: toml::de::Error| parse_error(e.message().to_string()))?;
+
+        Ok(Self {
+            default_profile,
+            shared,
+            profiles,
+        })
+    }
+}
+
+/// Resolves a [`Config`] from a file, the environment and overrides
+///
+/// A new loader reads no file and takes variables from the process environment; it
+/// does not load `.env` files (see [`Config::load`]).
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    file: FileSource,
+    profile: Option<String>,
+    env: EnvSource,
+    overrides: Settings,
+}
+
+impl ConfigLoader {
+    /// Creates a loader that reads only the environment
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the given file, which must exist
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = FileSource::Path(path.into());
+        self
+    }
+
+    /// Reads the file named by `REPLIERS_CONFIG`, which must exist, or else
+    /// `~/.config/repliers/config.toml` if it exists
+    pub fn default_file(mut self) -> Self {
+        self.file = FileSource::Default;
+        self
+    }
+
+    /// Selects a profile, taking precedence over `REPLIERS_PROFILE` and the file's
+    /// `default_profile`
+    pub fn profile(mut self, name: impl Into<String>) -> Self {
+        self.profile = Some(name.into());
+        self
+    }
+
+    /// Reads environment variables from `vars` instead of the process environment
+    pub fn env_vars<K, V>(mut self, vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env = EnvSource::Vars(
+            vars.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Ignores environment variables, including `REPLIERS_CONFIG` and `REPLIERS_PROFILE`
+    pub fn without_env(mut self) -> Self {
+        self.env = EnvSource::Disabled;
+        self
+    }
+
+    /// Applies settings over every other layer; fields set by earlier calls are kept
+    /// unless `overrides` sets them again
+    pub fn overrides(mut self, overrides: Settings) -> Self {
+        self.overrides = std::mem::take(&mut self.overrides).merge(overrides);
+        self
+    }
+
+    /// Overrides the API key
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.overrides.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Overrides the API base URL
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.overrides.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Overrides the board ID
+    pub fn board_id(mut self, board_id: impl Into<String>) -> Self {
+        self.overrides.board_id = Some(board_id.into());
+        self
+    }
+
+    /// Reads and validates every layer
+    ///
+    /// # Errors
     ///
-    /// # Arguments
+    /// Returns an error if the file cannot be read or parsed, the selected profile is
+    /// not defined, any layer holds an invalid value, or the merged settings contradict
+    /// each other. A missing API key is only reported when a client is built.
+    pub fn load(self) -> Result<Config, ConfigError> {
+        let (path, file) = self.read_file()?;
+
+        let profile = self
+            .profile
+            .clone()
+            .or_else(|| self.env.get(PROFILE_ENV))
+            .or_else(|| file.as_ref().and_then(|f| f.default_profile.clone()));
+
+        let mut settings = Settings::default();
+        if let Some(file) = file {
+            file.shared.validate(Layer::File, str::to_string)?;
+            settings = settings.merge(file.shared);
+
+            if let Some(name) = &profile {
+                let mut profiles = file.profiles;
+                let selected = profiles
+                    .remove(name)
+                    .ok_or_else(|| unknown_profile(name, &path))?;
+                selected.validate(Layer::File, |field| format!("profiles.{}.{}", name, field))?;
+                settings = settings.merge(selected);
+            }
+        } else if let Some(name) = &profile {
+            return Err(unknown_profile(name, &path));
+        }
+
+        settings = settings.merge(self.read_env()?);
+
+        self.overrides.validate(Layer::Override, str::to_string)?;
+        settings = settings.merge(self.overrides);
+        settings.validate_combination(Layer::Merged, str::to_string)?;
+
+        Ok(Config {
+            path,
+            profile,
+            settings,
+        })
+    }
+
+    /// Locates and reads the configuration file, if there is one
+    fn read_file(&self) -> Result<(Option<PathBuf>, Option<ConfigFile>), ConfigError> {
+        let (path, required) = match &self.file {
+            FileSource::None => return Ok((None, None)),
+            FileSource::Path(path) => (path.clone(), true),
+            FileSource::Default => match self.env.get(CONFIG_ENV) {
+                Some(path) => (PathBuf::from(path), true),
+                None => match default_path() {
+                    Some(path) => (path, false),
+                    None => return Ok((None, None)),
+                },
+            },
+        };
+        if !required && !path.exists() {
+            return Ok((None, None));
+        }
+        let file = ConfigFile::read(&path)?;
+        Ok((Some(path), Some(file)))
+    }
+
+    fn read_env(&self) -> Result<Settings, ConfigError> {
+        let mut settings = Settings::default();
+        for (field, var) in ENV_VARS {
+            if let Some(value) = self.env.get(var) {
+                settings
+                    .set(field, value)
+                    .map_err(|message| ConfigError::InvalidValue {
+                        key: var.to_string(),
+                        layer: Layer::Environment,
+                        message,
+                    })?;
+            }
+        }
+        settings.validate(Layer::Environment, env_var)?;
+        Ok(settings)
+    }
+}
+
+fn unknown_profile(name: &str, path: &Option<PathBuf>) -> ConfigError {
+    ConfigError::UnknownProfile {
+        name: name.to_string(),
+        location: match path {
+            Some(path) => path.display().to_string(),
+            None => "any configuration file (none was found)".to_string(),
+        },
+    }
+}
+
+/// Names the environment variable of a setting
+fn env_var(field: &str) -> String {
+    ENV_VARS
+        .iter()
+        .find(|(f, _)| *f == field)
+        .map_or(field, |(_, var)| *var)
+        .to_string()
+}
+
+/// `~/.config/repliers/config.toml`
+fn default_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(
+        PathBuf::from(home)
+            .join(".config")
+            .join("repliers")
+            .join("config.toml"),
+    )
+}
+
+/// Resolved client configuration
+///
+/// Built by [`Config::load`] or a [`ConfigLoader`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    path: Option<PathBuf>,
+    profile: Option<String>,
+    settings: Settings,
+}
+
+impl Config {
+    /// Loads `.env`, then the default file and the environment
     ///
-    /// * `path` - Path to the config.toml file
+    /// Equivalent to `ConfigLoader::new().default_file().load()` after loading a `.env`
+    /// file if present.
     ///
     /// # Errors
     ///
-    /// Returns an error if the file cannot be read or parsed
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
-        let content = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
-        Ok(config)
+    /// See [`ConfigLoader::load`].
+    pub fn load() -> Result<Self, ConfigError> {
+        dotenvy::dotenv().ok();
+        ConfigLoader::new().default_file().load()
+    }
+
+    /// Creates a loader for choosing the file, profile and overrides
+    pub fn loader() -> ConfigLoader {
+        ConfigLoader::new()
     }
 
-    /// Load configuration from default location (./config.toml)
+    /// Path of the configuration file that was read, if any
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Name of the selected profile, if any
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Merged settings of every layer
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// API key, if one was configured
+    pub fn api_key(&self) -> Option<&str> {
+        self.settings.api_key.as_deref()
+    }
+
+    /// Board ID to pass to requests, if one was configured
+    pub fn board_id(&self) -> Option<&str> {
+        self.settings.board_id.as_deref()
+    }
+
+    /// Retry policy: the defaults with every configured retry setting applied
+    pub fn retry_policy(&self) -> RetryPolicy {
+        let retry = &self.settings.retry;
+        let mut policy = RetryPolicy::default();
+        if let Some(max_attempts) = retry.max_attempts {
+            policy = policy.max_attempts(max_attempts);
+        }
+        if let Some(ms) = retry.base_delay_ms {
+            policy = policy.base_delay(Duration::from_millis(ms));
+        }
+        if let Some(ms) = retry.max_delay_ms {
+            policy = policy.max_delay(Duration::from_millis(ms));
+        }
+        if let Some(jitter) = retry.jitter {
+            policy = policy.jitter(jitter);
+        }
+        if let Some(retry_search) = retry.retry_search {
+            policy = policy.retry_search(retry_search);
+        }
+        policy
+    }
+
+    /// Rate limit, if a rate was configured
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        let settings = &self.settings.rate_limit;
+        let per_second = settings
+            .requests_per_second
+            .or(settings.requests_per_minute.map(|r| r / 60.0))?;
+        let default_burst = (per_second.ceil() as u32).max(1);
+        let limit = RateLimit {
+            refill_per_second: per_second,
+            burst: 1,
+        };
+        Some(limit.burst(settings.burst.unwrap_or(default_burst)))
+    }
+
+    /// Creates a client builder with every configured setting applied
     ///
     /// # Errors
     ///
-    /// Returns an error if the file cannot be read or parsed
-    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        Self::from_file("config.toml")
+    /// Returns [`ConfigError::MissingApiKey`] if no layer set an API key.
+    pub fn client_builder(&self) -> Result<RepliersClientBuilder, ConfigError> {
+        let api_key = self.api_key().ok_or(ConfigError::MissingApiKey)?;
+        let mut builder = RepliersClient::builder()
+            .api_key(api_key)
+            .retry_policy(self.retry_policy());
+        if let Some(base_url) = &self.settings.base_url {
+            builder = builder.base_url(base_url);
+        }
+        if let Some(secs) = self.settings.timeout_secs {
+            builder = builder.timeout(Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.settings.connect_timeout_secs {
+            builder = builder.connect_timeout(Duration::from_secs(secs));
+        }
+        if let Some(limit) = self.rate_limit() {
+            builder = builder.rate_limit(limit);
+        }
+        Ok(builder)
     }
 
-    /// Load configuration from default location or return a helpful error message
+    /// Builds a client from the configuration
     ///
-    /// This is a convenience method that provides a user-friendly error message
-    /// if the config file is not found.
-    pub fn load_or_exit() -> Self {
-        match Self::load() {
-            Ok(config) => config,
-            Err(e) => {
-                eprintln!("Error loading configuration: {}", e);
-                eprintln!("\nMake sure you have:");
-                eprintln!("1. Created a config.toml file in the project root");
-                eprintln!("2. Used config.toml.example as a template:");
-                eprintln!("   cp config.toml.example config.toml");
-                eprintln!("3. Updated the values in config.toml with your test data");
-                std::process::exit(1);
-            }
-        }
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::InvalidApiKey`] if no API key was configured, or an
+    /// error if the client cannot be built.
+    pub fn client(&self) -> Result<RepliersClient, RepliersError> {
+        self.client_builder()?.build()
     }
 }
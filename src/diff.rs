@@ -0,0 +1,328 @@
+//! Snapshot diffing
+//!
+//! A [`Snapshot`] is a set of listings keyed by MLS number, taken from a search, a JSON
+//! export or the local store. Comparing two snapshots with [`Snapshot::diff`] yields
+//! [`ListingChange`] events: new and removed listings, price changes, status changes
+//! (including `lastStatus` moves such as going conditional or selling) and changes to
+//! any other field.
+//!
+//! # Examples
+//!
+//! ```
+//! use repliers_beta::diff::{DiffOptions, ListingChange, Snapshot};
+//! use repliers_beta::Listing;
+//!
+//! let before = Snapshot::new(vec![Listing {
+//!     mls_number: "C1234567".to_string(),
+//!     list_price: Some(900_000.0),
+//!     ..Default::default()
+//! }]);
+//! let after = Snapshot::new(vec![Listing {
+//!     mls_number: "C1234567".to_string(),
+//!     list_price: Some(855_000.0),
+//!     ..Default::default()
+//! }]);
+//!
+//! let changes = before.diff(&after, &DiffOptions::default());
+//! assert!(matches!(
+//!     changes[0],
+//!     ListingChange::PriceChanged { pct: Some(pct), .. } if pct.round() == -5.0
+//! ));
+//! ```
+
+use crate::models::{Listing, ListingSearchResponse};
+use crate::storage::storage_error;
+use crate::RepliersError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// A change between two snapshots of the same listing set
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ListingChange {
+    /// The listing appears only in the newer snapshot
+    New { listing: Box<Listing> },
+
+    /// The list price changed; `pct` is the change relative to `old`, in percent, or
+    /// `None` when the old price was zero
+    #[serde(rename_all = "camelCase")]
+    PriceChanged {
+        mls_number: String,
+        old: f64,
+        new: f64,
+        pct: Option<f64>,
+    },
+
+    /// The status or last status changed (e.g. "New" to "Sc" or "Sld")
+    #[serde(rename_all = "camelCase")]
+    StatusChanged {
+        mls_number: String,
+        old_status: Option<String>,
+        new_status: Option<String>,
+        old_last_status: Option<String>,
+        new_last_status: Option<String>,
+    },
+
+    /// The listing appears only in the older snapshot
+    Removed { listing: Box<Listing> },
+
+    /// Any other field changed; `field` is a dotted JSON path such as `details.style`
+    #[serde(rename_all = "camelCase")]
+    FieldChanged {
+        mls_number: String,
+        field: String,
+        old: Value,
+        new: Value,
+    },
+}
+
+impl ListingChange {
+    /// Returns the MLS number the change refers to
+    pub fn mls_number(&self) -> &str {
+        match self {
+            Self::New { listing } | Self::Removed { listing } => &listing.mls_number,
+            Self::PriceChanged { mls_number, .. }
+            | Self::StatusChanged { mls_number, .. }
+            | Self::FieldChanged { mls_number, .. } => mls_number,
+        }
+    }
+}
+
+/// Options controlling which differences are reported
+#[derive(Debug, Clone)]
+pub struct DiffOptions {
+    /// Fields that never produce [`ListingChange::FieldChanged`] events
+    ///
+    /// Entries are dotted JSON paths (e.g. `details.description`); ignoring an object
+    /// ignores everything below it. Defaults to `timestamps`, `updatedOn` and
+    /// `daysOnMarket`, which change on every refresh.
+    pub ignored_fields: BTreeSet<String>,
+
+    /// Whether to report changes to fields other than price and status
+    pub field_changes: bool,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            ignored_fields: ["timestamps", "updatedOn", "daysOnMarket"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            field_changes: true,
+        }
+    }
+}
+
+impl DiffOptions {
+    /// Adds a field to the ignore list
+    pub fn ignore(mut self, field: impl Into<String>) -> Self {
+        self.ignored_fields.insert(field.into());
+        self
+    }
+
+    /// Enables or disables [`ListingChange::FieldChanged`] events
+    pub fn field_changes(mut self, enabled: bool) -> Self {
+        self.field_changes = enabled;
+        self
+    }
+
+    fn is_ignored(&self, path: &str) -> bool {
+        self.ignored_fields.iter().any(|ignored| {
+            path == ignored
+                || path
+                    .strip_prefix(ignored.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    }
+}
+
+/// A set of listings keyed by MLS number
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Snapshot {
+    listings: BTreeMap<String, Listing>,
+}
+
+impl Snapshot {
+    /// Builds a snapshot; later listings replace earlier ones with the same MLS number
+    pub fn new(listings: impl IntoIterator<Item = Listing>) -> Self {
+        listings.into_iter().collect()
+    }
+
+    /// Parses a JSON export: either an array of listings or a search response
+    pub fn from_json_str(json: &str) -> Result<Self, RepliersError> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Export {
+            Listings(Vec<Listing>),
+            Response(ListingSearchResponse),
+        }
+
+        let export: Export = serde_json::from_str(json)
+            .map_err(|e| RepliersError::ParseError(format!("Invalid listing export: {}", e)))?;
+        Ok(match export {
+            Export::Listings(listings) => Self::new(listings),
+            Export::Response(response) => Self::new(response.listings),
+        })
+    }
+
+    /// Reads a JSON export from disk (see [`Snapshot::from_json_str`])
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self, RepliersError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| storage_error(path.display(), e))?;
+        Self::from_json_str(&json)
+    }
+
+    /// Takes a snapshot of every listing in a local store
+    #[cfg(feature = "store")]
+    pub fn from_store(store: &crate::store::ListingStore) -> Result<Self, RepliersError> {
+        Ok(Self::new(store.listings()?))
+    }
+
+    /// Returns the listing with the given MLS number
+    pub fn get(&self, mls_number: &str) -> Option<&Listing> {
+        self.listings.get(mls_number)
+    }
+
+    /// Returns the number of listings in the snapshot
+    pub fn len(&self) -> usize {
+        self.listings.len()
+    }
+
+    /// Returns `true` if the snapshot holds no listings
+    pub fn is_empty(&self) -> bool {
+        self.listings.is_empty()
+    }
+
+    /// Iterates over the listings in MLS number order
+    pub fn iter(&self) -> impl Iterator<Item = &Listing> {
+        self.listings.values()
+    }
+
+    /// Compares this (older) snapshot with a newer one
+    ///
+    /// Changes are ordered by MLS number, with removals last.
+    pub fn diff(&self, newer: &Snapshot, options: &DiffOptions) -> Vec<ListingChange> {
+        let mut changes = Vec::new();
+        for (mls_number, listing) in &newer.listings {
+            match self.listings.get(mls_number) {
+                Some(previous) => changes.extend(diff_listing(previous, listing, options)),
+                None => changes.push(ListingChange::New {
+                    listing: Box::new(listing.clone()),
+                }),
+            }
+        }
+        for (mls_number, listing) in &self.listings {
+            if !newer.listings.contains_key(mls_number) {
+                changes.push(ListingChange::Removed {
+                    listing: Box::new(listing.clone()),
+                });
+            }
+        }
+        changes
+    }
+}
+
+impl FromIterator<Listing> for Snapshot {
+    fn from_iter<I: IntoIterator<Item = Listing>>(iter: I) -> Self {
+        Self {
+            listings: iter
+                .into_iter()
+                .map(|listing| (listing.mls_number.clone(), listing))
+                .collect(),
+        }
+    }
+}
+
+/// Compares two versions of the same listing
+///
+/// Never produces `New` or `Removed` events.
+pub fn diff_listing(old: &Listing, new: &Listing, options: &DiffOptions) -> Vec<ListingChange> {
+    let mut changes = Vec::new();
+    let mls_number = &new.mls_number;
+
+    if let (Some(old_price), Some(new_price)) = (old.list_price, new.list_price) {
+        if old_price != new_price {
+            let pct = (old_price != 0.0).then(|| (new_price - old_price) / old_price * 100.0);
+            changes.push(ListingChange::PriceChanged {
+                mls_number: mls_number.clone(),
+                old: old_price,
+                new: new_price,
+                pct,
+            });
+        }
+    }
+
+    if old.status != new.status || old.last_status != new.last_status {
+        changes.push(ListingChange::StatusChanged {
+            mls_number: mls_number.clone(),
+            old_status: old.status.clone(),
+            new_status: new.status.clone(),
+            old_last_status: old.last_status.clone(),
+            new_last_status: new.last_status.clone(),
+        });
+    }
+
+    if options.field_changes {
+        let mut old_fields = BTreeMap::new();
+        let mut new_fields = BTreeMap::new();
+        flatten(
+            "",
+            &serde_json::to_value(old).unwrap_or_default(),
+            &mut old_fields,
+        );
+        flatten(
+            "",
+            &serde_json::to_value(new).unwrap_or_default(),
+            &mut new_fields,
+        );
+
+        let paths: BTreeSet<&String> = old_fields.keys().chain(new_fields.keys()).collect();
+        for path in paths {
+            let (old_value, new_value) = (old_fields.get(path), new_fields.get(path));
+            if old_value == new_value || options.is_ignored(path) {
+                continue;
+            }
+            // Prices and statuses are covered by the dedicated events above, unless a
+            // price appeared or disappeared entirely
+            let covered = match path.as_str() {
+                "status" | "lastStatus" => true,
+                "listPrice" => old_value.is_some() && new_value.is_some(),
+                _ => false,
+            };
+            if covered {
+                continue;
+            }
+            changes.push(ListingChange::FieldChanged {
+                mls_number: mls_number.clone(),
+                field: path.clone(),
+                old: old_value.cloned().unwrap_or(Value::Null),
+                new: new_value.cloned().unwrap_or(Value::Null),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Flattens nested objects into dotted paths; arrays and scalars are leaves
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&path, value, out);
+            }
+        }
+        Value::Null => {}
+        _ => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
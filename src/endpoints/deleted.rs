@@ -40,13 +40,8 @@ impl RepliersClient {
     ) -> Result<DeletedListingsResponse, RepliersError> {
         let url = format!("{}/listings/deleted", self.base_url());
 
-        let response = self
-            .get_request(&url)
-            .query(&query)
-            .send()
-            .await?;
-
-        let response = Self::check_response(response).await?;
+        let request = self.get_request(&url).query(&query);
+        let response = self.send(request, true).await?;
         let deleted_response = response.json::<DeletedListingsResponse>().await?;
 
         Ok(deleted_response)
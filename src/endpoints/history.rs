@@ -42,13 +42,8 @@ impl RepliersClient {
     ) -> Result<AddressHistoryResponse, RepliersError> {
         let url = format!("{}/listings/history", self.base_url());
 
-        let response = self
-            .get_request(&url)
-            .query(&query)
-            .send()
-            .await?;
-
-        let response = Self::check_response(response).await?;
+        let request = self.get_request(&url).query(&query);
+        let response = self.send(request, true).await?;
         let history_response = response.json::<AddressHistoryResponse>().await?;
 
         Ok(history_response)
@@ -2,7 +2,9 @@
 //!
 //! GET https://api.repliers.io/listings/{mlsNumber}
 
+use crate::models::ListingDetail;
 use crate::{RepliersClient, RepliersError};
+use serde::de::DeserializeOwned;
 
 impl RepliersClient {
     /// Get detailed information for a single listing
@@ -22,6 +24,11 @@ impl RepliersClient {
     /// let client = RepliersClient::new("api_key".to_string());
     ///
     /// let listing = client.get_listing("N12345678", None).await?;
+    /// println!("{} is listed at {:?}", listing.mls_number, listing.list_price);
+    ///
+    /// for comparable in listing.comparables() {
+    ///     println!("Comparable: {}", comparable.mls_number);
+    /// }
     /// # Ok(())
     /// # }
     /// ```
@@ -29,7 +36,30 @@ impl RepliersClient {
         &self,
         mls_number: &str,
         board_id: Option<&str>,
+    ) -> Result<ListingDetail, RepliersError> {
+        self.get_listing_as(mls_number, board_id).await
+    }
+
+    /// Get a single listing as untyped JSON
+    ///
+    /// Fallback for when the full original structure is needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response is not valid JSON.
+    pub async fn get_listing_raw(
+        &self,
+        mls_number: &str,
+        board_id: Option<&str>,
     ) -> Result<serde_json::Value, RepliersError> {
+        self.get_listing_as(mls_number, board_id).await
+    }
+
+    async fn get_listing_as<T: DeserializeOwned>(
+        &self,
+        mls_number: &str,
+        board_id: Option<&str>,
+    ) -> Result<T, RepliersError> {
         let url = format!("{}/listings/{}", self.base_url(), mls_number);
 
         let mut request = self.get_request(&url);
@@ -38,9 +68,8 @@ impl RepliersClient {
             request = request.query(&[("boardId", bid)]);
         }
 
-        let response = request.send().await?;
-        let response = Self::check_response(response).await?;
-        let listing_response = response.json::<serde_json::Value>().await?;
+        let response = self.send(request, true).await?;
+        let listing_response = response.json::<T>().await?;
 
         Ok(listing_response)
     }
@@ -43,13 +43,9 @@ impl RepliersClient {
             board_id: board_id.map(|s| s.to_string()),
         };
 
-        let response = self
-            .post_request(&url)
-            .json(&request)
-            .send()
-            .await?;
-
-        let response = Self::check_response(response).await?;
+        // NLP requests are not idempotent and are never retried
+        let http_request = self.post_request(&url).json(&request);
+        let response = self.send(http_request, false).await?;
         let nlp_response = response.json::<NLPSearchResponse>().await?;
 
         Ok(nlp_response)
@@ -2,8 +2,9 @@
 //!
 //! POST https://api.repliers.io/listings
 
-use crate::models::{ListingSearchRequest, ListingSearchResponse};
+use crate::models::{ListingSearchRequest, ListingSearchResponse, RawListingSearchResponse};
 use crate::{RepliersClient, RepliersError};
+use serde::de::DeserializeOwned;
 
 impl RepliersClient {
     /// Search for listings based on various criteria
@@ -36,6 +37,10 @@ impl RepliersClient {
     ///
     /// let results = client.search_listings(request).await?;
     /// println!("Found {} listings", results.count);
+    ///
+    /// for listing in &results.listings {
+    ///     println!("{}: {:?}", listing.mls_number, listing.list_price);
+    /// }
     /// # Ok(())
     /// # }
     /// ```
@@ -43,16 +48,35 @@ impl RepliersClient {
         &self,
         request: ListingSearchRequest,
     ) -> Result<ListingSearchResponse, RepliersError> {
+        self.search_listings_as(request).await
+    }
+
+    /// Search for listings, returning each listing as untyped JSON
+    ///
+    /// Use this when a board returns fields the typed [`Listing`](crate::Listing) model
+    /// does not cover and the full original structure is needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response cannot be parsed.
+    pub async fn search_listings_raw(
+        &self,
+        request: ListingSearchRequest,
+    ) -> Result<RawListingSearchResponse, RepliersError> {
+        self.search_listings_as(request).await
+    }
+
+    async fn search_listings_as<L: DeserializeOwned>(
+        &self,
+        request: ListingSearchRequest,
+    ) -> Result<ListingSearchResponse<L>, RepliersError> {
         let url = format!("{}/listings", self.base_url());
 
+        let http_request = self.post_request(&url).json(&request);
         let response = self
-            .post_request(&url)
-            .json(&request)
-            .send()
+            .send(http_request, self.retry_policy().retry_search)
             .await?;
-
-        let response = Self::check_response(response).await?;
-        let search_response = response.json::<ListingSearchResponse>().await?;
+        let search_response = response.json::<ListingSearchResponse<L>>().await?;
 
         Ok(search_response)
     }
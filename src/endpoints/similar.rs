@@ -2,7 +2,11 @@
 //!
 //! GET https://api.repliers.io/listings/{mlsNumber}/similar
 
-use crate::{models::search::SimilarListingsRequest, models::search::SimilarListingsResponse, RepliersClient, RepliersError};
+use crate::models::search::{
+    RawSimilarListingsResponse, SimilarListingsRequest, SimilarListingsResponse,
+};
+use crate::{RepliersClient, RepliersError};
+use serde::de::DeserializeOwned;
 
 impl RepliersClient {
     /// Find listings similar to a given property
@@ -33,6 +37,25 @@ impl RepliersClient {
         &self,
         request: SimilarListingsRequest,
     ) -> Result<SimilarListingsResponse, RepliersError> {
+        self.get_similar_listings_as(request).await
+    }
+
+    /// Find similar listings, returning each listing as untyped JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the response cannot be parsed.
+    pub async fn get_similar_listings_raw(
+        &self,
+        request: SimilarListingsRequest,
+    ) -> Result<RawSimilarListingsResponse, RepliersError> {
+        self.get_similar_listings_as(request).await
+    }
+
+    async fn get_similar_listings_as<L: DeserializeOwned>(
+        &self,
+        request: SimilarListingsRequest,
+    ) -> Result<SimilarListingsResponse<L>, RepliersError> {
         let url = format!("{}/listings/{}/similar", self.base_url(), request.mls_number);
 
         let mut http_request = self.get_request(&url);
@@ -60,9 +83,8 @@ impl RepliersClient {
             http_request = http_request.query(&params);
         }
 
-        let response = http_request.send().await?;
-        let response = Self::check_response(response).await?;
-        let similar_response = response.json::<SimilarListingsResponse>().await?;
+        let response = self.send(http_request, true).await?;
+        let similar_response = response.json::<SimilarListingsResponse<L>>().await?;
 
         Ok(similar_response)
     }
@@ -1,31 +1,291 @@
 //! Error types for the Repliers API client
 
+use crate::config::Layer;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 
+/// Headers checked, in order, for a server-assigned request ID
+const REQUEST_ID_HEADERS: [&str; 3] = ["x-request-id", "x-amzn-requestid", "x-correlation-id"];
+
 /// Error types that can occur when using the Repliers API client
+///
+/// Non-success HTTP responses are mapped to a dedicated variant per status class, each
+/// carrying an [`ApiErrorContext`] with the request method, endpoint path and decoded body.
 #[derive(Error, Debug)]
 pub enum RepliersError {
     /// HTTP request failed
     #[error("HTTP request failed: {0}")]
     RequestFailed(#[from] reqwest::Error),
 
-    /// API returned an error response
+    /// The requested resource does not exist (HTTP 404)
+    #[error("Not found: {0}")]
+    NotFound(Box<ApiErrorContext>),
+
+    /// The API key was missing or rejected (HTTP 401)
+    #[error("Unauthorized: {0}")]
+    Unauthorized(Box<ApiErrorContext>),
+
+    /// The API key does not grant access to the resource (HTTP 403)
+    #[error("Forbidden: {0}")]
+    Forbidden(Box<ApiErrorContext>),
+
+    /// Too many requests (HTTP 429)
+    #[error("Rate limited: {context}")]
+    RateLimited {
+        /// Details of the failed request
+        context: Box<ApiErrorContext>,
+        /// Delay requested by the server's `Retry-After` header, if any
+        retry_after: Option<Duration>,
+    },
+
+    /// The request parameters were rejected (HTTP 400 or 422)
+    #[error("Validation failed: {0}")]
+    Validation(Box<ApiErrorContext>),
+
+    /// The API failed to process the request (HTTP 5xx)
+    #[error("Server error: {0}")]
+    Server(Box<ApiErrorContext>),
+
+    /// API returned any other error response
     #[error("API returned error: {0}")]
-    ApiError(String),
+    ApiError(Box<ApiErrorContext>),
 
     /// Invalid or missing API key
     #[error("Invalid API key")]
     InvalidApiKey,
 
+    /// Client configuration is invalid (e.g. malformed base URL)
+    #[error("Invalid client configuration: {0}")]
+    InvalidConfig(String),
+
+    /// Request parameters are invalid and were not sent (e.g. malformed GeoJSON)
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    /// Reading or writing local state failed (watermarks, stores, exports)
+    #[error("Storage error: {0}")]
+    Storage(String),
+
     /// Failed to parse response
     #[error("Failed to parse response: {0}")]
     ParseError(String),
 }
 
-// Note: Additional error types can be added as needed for more granular error handling:
-// - RateLimitExceeded: For HTTP 429 rate limiting errors
-// - ResourceNotFound: For HTTP 404 errors when a listing is not found
-// - ValidationError: For invalid request parameters
-// - AuthenticationError: For HTTP 401/403 authentication/authorization failures
-//
-// The current error types provide sufficient coverage for the proof-of-concept implementation.
+/// Details of a failed API request
+///
+/// The path never includes the query string, and the API key is sent as a header, so the
+/// context is safe to log.
+#[derive(Debug, Clone)]
+pub struct ApiErrorContext {
+    /// HTTP status returned by the API
+    pub status: StatusCode,
+
+    /// HTTP method of the request
+    pub method: Method,
+
+    /// Endpoint path (e.g. `/listings/N12345678`)
+    pub path: String,
+
+    /// Request ID reported by the server, if any
+    pub request_id: Option<String>,
+
+    /// Decoded JSON error body, if the body was valid JSON
+    pub body: Option<serde_json::Value>,
+
+    /// Human-readable error message extracted from the body
+    pub message: String,
+}
+
+impl ApiErrorContext {
+    /// Builds a context from the parts of a failed response
+    pub(crate) fn new(
+        status: StatusCode,
+        method: Method,
+        path: impl Into<String>,
+        headers: &HeaderMap,
+        body_text: &str,
+    ) -> Self {
+        let request_id = REQUEST_ID_HEADERS.iter().find_map(|name| {
+            headers
+                .get(*name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        });
+
+        let body = serde_json::from_str::<serde_json::Value>(body_text).ok();
+        let message = body.as_ref().and_then(extract_message).unwrap_or_else(|| {
+            let text = body_text.trim();
+            if text.is_empty() {
+                status
+                    .canonical_reason()
+                    .unwrap_or("Unknown error")
+                    .to_string()
+            } else {
+                text.to_string()
+            }
+        });
+
+        Self {
+            status,
+            method,
+            path: path.into(),
+            request_id,
+            body,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ApiErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} returned {}: {}",
+            self.method, self.path, self.status, self.message
+        )?;
+        if let Some(request_id) = &self.request_id {
+            write!(f, " (request id {})", request_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Pulls a message out of common error body shapes
+fn extract_message(body: &serde_json::Value) -> Option<String> {
+    ["message", "error", "errors", "detail"]
+        .iter()
+        .find_map(|key| match body.get(*key)? {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Null => None,
+            other => Some(other.to_string()),
+        })
+}
+
+impl RepliersError {
+    /// Maps a failed response to the matching error variant
+    pub(crate) fn from_response(context: ApiErrorContext, retry_after: Option<Duration>) -> Self {
+        let context = Box::new(context);
+        match context.status {
+            StatusCode::NOT_FOUND => Self::NotFound(context),
+            StatusCode::UNAUTHORIZED => Self::Unauthorized(context),
+            StatusCode::FORBIDDEN => Self::Forbidden(context),
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited {
+                context,
+                retry_after,
+            },
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => Self::Validation(context),
+            status if status.is_server_error() => Self::Server(context),
+            _ => Self::ApiError(context),
+        }
+    }
+
+    /// Returns the request context for errors returned by the API
+    pub fn context(&self) -> Option<&ApiErrorContext> {
+        match self {
+            Self::NotFound(context)
+            | Self::Unauthorized(context)
+            | Self::Forbidden(context)
+            | Self::RateLimited { context, .. }
+            | Self::Validation(context)
+            | Self::Server(context)
+            | Self::ApiError(context) => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the HTTP status, if the error came from an HTTP response
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::RequestFailed(e) => e.status(),
+            _ => self.context().map(|c| c.status),
+        }
+    }
+
+    /// Returns the server-suggested delay for rate-limited requests
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Returns true if retrying the same request later may succeed
+    ///
+    /// Covers rate limiting, server errors, and timeouts or connection failures.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited { .. } | Self::Server(_) => true,
+            Self::RequestFailed(e) => e.is_timeout() || e.is_connect(),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the requested resource does not exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::NotFound(_))
+    }
+
+    /// Returns true if the API key was missing, invalid or lacked permission
+    pub fn is_auth_error(&self) -> bool {
+        matches!(
+            self,
+            Self::Unauthorized(_) | Self::Forbidden(_) | Self::InvalidApiKey
+        )
+    }
+
+    /// Returns true if the request was rejected for exceeding the rate limit
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, Self::RateLimited { .. })
+    }
+}
+
+/// Errors raised while loading a [`Config`](crate::config::Config)
+///
+/// Converts into [`RepliersError::InvalidConfig`], or [`RepliersError::InvalidApiKey`]
+/// when no API key was configured, so it can be propagated with `?` alongside API errors.
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    /// The configuration file could not be read
+    #[error("Cannot read {}: {source}", .path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The configuration file is not valid TOML or contains unknown settings
+    #[error("Cannot parse {}: {message}", .path.display())]
+    Parse { path: PathBuf, message: String },
+
+    /// The selected profile is not defined in the configuration file
+    #[error("Profile '{name}' is not defined in {location}")]
+    UnknownProfile { name: String, location: String },
+
+    /// A setting was given a value it cannot take
+    #[error("Invalid {key} in {layer}: {message}")]
+    InvalidValue {
+        /// Setting name, as written in the layer it came from (e.g. `retry.max_attempts`
+        /// or `REPLIERS_RETRY_MAX_ATTEMPTS`)
+        key: String,
+        /// Layer that supplied the value
+        layer: Layer,
+        message: String,
+    },
+
+    /// No layer supplied an API key
+    #[error("No API key configured")]
+    MissingApiKey,
+}
+
+impl From<ConfigError> for RepliersError {
+    fn from(error: ConfigError) -> Self {
+        match error {
+            ConfigError::MissingApiKey => Self::InvalidApiKey,
+            error => Self::InvalidConfig(error.to_string()),
+        }
+    }
+}
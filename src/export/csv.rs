@@ -0,0 +1,541 @@
+//! CSV export with configurable columns
+//!
+//! A [`ColumnSpec`] maps dotted paths into a listing (e.g. `address.city` or
+//! `details.numBedrooms`) to CSV columns. [`CsvExporter`] then writes any serializable
+//! record, typed [`Listing`](crate::Listing)s or raw JSON, one row at a time:
+//!
+//! - arrays are joined into one cell or exploded into one row per element
+//!   ([`ArrayMode`])
+//! - dates and prices are formatted consistently ([`ColumnFormat`], [`CsvOptions`])
+//! - rows are written as they arrive, so a paginated search can be exported with
+//!   [`CsvExporter::write_stream`] without holding every page in memory
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use repliers_beta::export::csv::{ColumnSpec, CsvExporter};
+//! use repliers_beta::{ListingSearchRequest, RepliersClient};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = RepliersClient::from_env()?;
+//! let request = ListingSearchRequest::builder().city("Toronto").build();
+//!
+//! let spec = ColumnSpec::parse("MLS=mlsNumber,Price=listPrice,City=address.city,Beds=details.numBedrooms")?;
+//! let mut exporter = CsvExporter::create("toronto.csv", spec)?;
+//! let rows = exporter.write_stream(client.search_listings_stream(request)).await?;
+//! exporter.finish()?;
+//! println!("Wrote {} rows", rows);
+//! # Ok(())
+//! # }
+//! ```
+
+use super::lookup_path;
+use crate::models::parse_timestamp;
+use crate::storage::storage_error;
+use crate::RepliersError;
+use chrono::format::{Item, StrftimeItems};
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// How a column's values are rendered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColumnFormat {
+    /// Infer from the path: fields ending in `Price` are prices, fields ending in `Date`
+    /// or `On` and anything under `timestamps` are dates, everything else is text
+    #[default]
+    Auto,
+
+    /// Values as they appear in the JSON
+    Text,
+
+    /// Dates and timestamps, rendered with [`CsvOptions::date_format`]
+    Date,
+
+    /// Amounts, rendered with [`CsvOptions::price_decimals`] decimals
+    Price,
+}
+
+impl ColumnFormat {
+    fn resolve(self, path: &str) -> Self {
+        if self != Self::Auto {
+            return self;
+        }
+        let field = path.rsplit('.').next().unwrap_or(path);
+        if field.ends_with("Price") || field == "price" {
+            Self::Price
+        } else if field.ends_with("Date")
+            || field.ends_with("On")
+            || field == "date"
+            || path.starts_with("timestamps.")
+        {
+            Self::Date
+        } else {
+            Self::Text
+        }
+    }
+}
+
+/// A single CSV column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    /// Header row label
+    pub header: String,
+
+    /// Dotted path into the record (see [`lookup_path`](super::lookup_path))
+    pub path: String,
+
+    /// How values are rendered
+    pub format: ColumnFormat,
+}
+
+impl Column {
+    /// Creates a column whose header is its path
+    pub fn new(path: impl Into<String>) -> Self {
+        let path = path.into();
+        Self {
+            header: path.clone(),
+            path,
+            format: ColumnFormat::Auto,
+        }
+    }
+
+    /// Creates a column with a custom header
+    pub fn named(header: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            header: header.into(),
+            ..Self::new(path)
+        }
+    }
+
+    /// Overrides the inferred format
+    pub fn format(mut self, format: ColumnFormat) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+/// The ordered columns of an export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSpec {
+    columns: Vec<Column>,
+}
+
+impl Default for ColumnSpec {
+    /// A general-purpose listing layout: identifiers, status, prices, dates, address,
+    /// size and coordinates
+    fn default() -> Self {
+        Self::new([
+            Column::named("MLS Number", "mlsNumber"),
+            Column::named("Board", "boardId"),
+            Column::named("Status", "status"),
+            Column::named("Last Status", "lastStatus"),
+            Column::named("Type", "type"),
+            Column::named("List Price", "listPrice"),
+            Column::named("Sold Price", "soldPrice"),
+            Column::named("List Date", "listDate"),
+            Column::named("Sold Date", "soldDate"),
+            Column::named("Days on Market", "daysOnMarket"),
+            Column::named("Street Number", "address.streetNumber"),
+            Column::named("Street Name", "address.streetName"),
+            Column::named("Street Suffix", "address.streetSuffix"),
+            Column::named("Unit", "address.unitNumber"),
+            Column::named("City", "address.city"),
+            Column::named("Neighborhood", "address.neighborhood"),
+            Column::named("Postal Code", "address.zip"),
+            Column::named("Property Type", "details.propertyType"),
+            Column::named("Style", "details.style"),
+            Column::named("Bedrooms", "details.numBedrooms"),
+            Column::named("Bathrooms", "details.numBathrooms"),
+            Column::named("Sqft", "details.sqft"),
+            Column::named("Latitude", "map.latitude"),
+            Column::named("Longitude", "map.longitude"),
+            Column::named("Updated", "updatedOn"),
+        ])
+    }
+}
+
+impl ColumnSpec {
+    /// Creates a spec from columns
+    pub fn new(columns: impl IntoIterator<Item = Column>) -> Self {
+        Self {
+            columns: columns.into_iter().collect(),
+        }
+    }
+
+    /// Parses a comma-separated spec such as
+    /// `MLS=mlsNumber,City=address.city,listPrice:price,Listed=listDate:date`
+    ///
+    /// Each entry is `[header=]path[:format]`, where format is `text`, `date`, `price` or
+    /// `auto`. The header defaults to the path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::InvalidConfig`] for empty specs, empty paths or unknown
+    /// formats.
+    pub fn parse(spec: &str) -> Result<Self, RepliersError> {
+        let mut columns = Vec::new();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (header, rest) = match entry.split_once('=') {
+                Some((header, rest)) => (Some(header.trim()), rest.trim()),
+                None => (None, entry),
+            };
+            let (path, format) = match rest.rsplit_once(':') {
+                Some((path, format)) => (path.trim(), parse_format(format.trim())?),
+                None => (rest, ColumnFormat::Auto),
+            };
+            if path.is_empty() {
+                return Err(RepliersError::InvalidConfig(format!(
+                    "CSV column '{}' has no path",
+                    entry
+                )));
+            }
+            let column = match header {
+                Some(header) => Column::named(header, path),
+                None => Column::new(path),
+            };
+            columns.push(column.format(format));
+        }
+        if columns.is_empty() {
+            return Err(RepliersError::InvalidConfig(
+                "CSV column spec is empty".to_string(),
+            ));
+        }
+        Ok(Self { columns })
+    }
+
+    /// Appends a column
+    pub fn column(mut self, column: Column) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Returns the columns in order
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+}
+
+fn parse_format(format: &str) -> Result<ColumnFormat, RepliersError> {
+    match format.to_ascii_lowercase().as_str() {
+        "auto" => Ok(ColumnFormat::Auto),
+        "text" => Ok(ColumnFormat::Text),
+        "date" => Ok(ColumnFormat::Date),
+        "price" => Ok(ColumnFormat::Price),
+        other => Err(RepliersError::InvalidConfig(format!(
+            "Unknown CSV column format '{}' (expected text, date, price or auto)",
+            other
+        ))),
+    }
+}
+
+/// How array values (images, agents, rooms...) are written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArrayMode {
+    /// Join the elements into one cell with the separator
+    Join(String),
+
+    /// Write one row per element
+    ///
+    /// Every array column of a record is exploded in step: row `i` holds element `i` of
+    /// each array (empty when an array is shorter), and non-array columns repeat on
+    /// every row.
+    Explode,
+}
+
+impl Default for ArrayMode {
+    fn default() -> Self {
+        Self::Join("; ".to_string())
+    }
+}
+
+/// Formatting options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// How array values are written
+    pub arrays: ArrayMode,
+
+    /// `strftime` format for date columns (default `%Y-%m-%d`)
+    pub date_format: String,
+
+    /// Decimal places for price columns (default 0)
+    pub price_decimals: usize,
+
+    /// Field delimiter (default `,`)
+    pub delimiter: u8,
+
+    /// Whether to write the header row (default `true`)
+    pub header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            arrays: ArrayMode::default(),
+            date_format: "%Y-%m-%d".to_string(),
+            price_decimals: 0,
+            delimiter: b',',
+            header: true,
+        }
+    }
+}
+
+/// Writes records as CSV rows
+///
+/// The header row is written before the first record (or by [`finish`](Self::finish)
+/// if there were none). Call `finish` to flush the output; dropping the exporter
+/// flushes too, but silently ignores errors.
+#[derive(Debug)]
+pub struct CsvExporter<W: Write> {
+    writer: ::csv::Writer<W>,
+    spec: ColumnSpec,
+    formats: Vec<ColumnFormat>,
+    options: CsvOptions,
+    header_written: bool,
+    rows: usize,
+}
+
+impl CsvExporter<BufWriter<File>> {
+    /// Creates (or truncates) a CSV file with default options
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>, spec: ColumnSpec) -> Result<Self, RepliersError> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| storage_error(path.display(), e))?;
+        Ok(Self::new(BufWriter::new(file), spec))
+    }
+}
+
+impl<W: Write> CsvExporter<W> {
+    /// Creates an exporter with default options
+    pub fn new(writer: W, spec: ColumnSpec) -> Self {
+        Self::build(writer, spec, CsvOptions::default())
+    }
+
+    /// Creates an exporter with the given options
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::InvalidConfig`] if `date_format` is not a valid
+    /// `strftime` format.
+    pub fn with_options(
+        writer: W,
+        spec: ColumnSpec,
+        options: CsvOptions,
+    ) -> Result<Self, RepliersError> {
+        if StrftimeItems::new(&options.date_format).any(|item| item == Item::Error) {
+            return Err(RepliersError::InvalidConfig(format!(
+                "Invalid CSV date format: {}",
+                options.date_format
+            )));
+        }
+        Ok(Self::build(writer, spec, options))
+    }
+
+    fn build(writer: W, spec: ColumnSpec, options: CsvOptions) -> Self {
+        let writer = ::csv::WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .flexible(false)
+            .from_writer(writer);
+        let formats = spec
+            .columns
+            .iter()
+            .map(|c| c.format.resolve(&c.path))
+            .collect();
+        Self {
+            writer,
+            spec,
+            formats,
+            options,
+            header_written: false,
+            rows: 0,
+        }
+    }
+
+    /// Returns the number of data rows written so far
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Writes one record, returning the number of rows it produced
+    ///
+    /// A record produces one row, or more when arrays are exploded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::ParseError`] if the record cannot be serialized and
+    /// [`RepliersError::Storage`] if writing fails.
+    pub fn write<T: Serialize + ?Sized>(&mut self, record: &T) -> Result<usize, RepliersError> {
+        let value = serde_json::to_value(record)
+            .map_err(|e| RepliersError::ParseError(format!("Cannot serialize record: {}", e)))?;
+        self.write_header()?;
+
+        let values: Vec<Value> = self
+            .spec
+            .columns
+            .iter()
+            .map(|column| lookup_path(&value, &column.path))
+            .collect();
+        let cells: Vec<Vec<String>> = values
+            .iter()
+            .zip(&self.formats)
+            .map(|(value, format)| self.render(value, *format))
+            .collect();
+
+        let rows = match &self.options.arrays {
+            ArrayMode::Join(separator) => {
+                let row: Vec<String> = cells.iter().map(|c| c.join(separator)).collect();
+                self.write_row(&row)?;
+                1
+            }
+            ArrayMode::Explode => {
+                let count = values
+                    .iter()
+                    .zip(&cells)
+                    .filter(|(value, _)| value.is_array())
+                    .map(|(_, cells)| cells.len())
+                    .max()
+                    .unwrap_or(0)
+                    .max(1);
+                for i in 0..count {
+                    let row: Vec<&str> = values
+                        .iter()
+                        .zip(&cells)
+                        .map(|(value, cells)| match value.is_array() {
+                            true => cells.get(i).map(String::as_str).unwrap_or(""),
+                            false => cells[0].as_str(),
+                        })
+                        .collect();
+                    self.write_row(&row)?;
+                }
+                count
+            }
+        };
+        self.rows += rows;
+        Ok(rows)
+    }
+
+    /// Writes every record of an iterator, returning the number of rows produced
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first record that fails; see [`write`](Self::write).
+    pub fn write_all<T: Serialize>(
+        &mut self,
+        records: impl IntoIterator<Item = T>,
+    ) -> Result<usize, RepliersError> {
+        let mut rows = 0;
+        for record in records {
+            rows += self.write(&record)?;
+        }
+        Ok(rows)
+    }
+
+    /// Writes every record of a stream as it arrives, returning the number of rows
+    /// produced
+    ///
+    /// Accepts the streams returned by
+    /// [`search_listings_stream`](crate::RepliersClient::search_listings_stream), so
+    /// only one page is held in memory at a time.
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first stream or write error. Rows written before the error remain
+    /// in the output.
+    pub async fn write_stream<T, S>(&mut self, stream: S) -> Result<usize, RepliersError>
+    where
+        T: Serialize,
+        S: Stream<Item = Result<T, RepliersError>>,
+    {
+        let mut stream = std::pin::pin!(stream);
+        let mut rows = 0;
+        while let Some(record) = stream.next().await {
+            rows += self.write(&record?)?;
+        }
+        Ok(rows)
+    }
+
+    /// Writes the header if needed, flushes and returns the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if writing or flushing fails.
+    pub fn finish(mut self) -> Result<W, RepliersError> {
+        self.write_header()?;
+        self.writer
+            .into_inner()
+            .map_err(|e| storage_error("CSV export failed", e.error()))
+    }
+
+    fn write_header(&mut self) -> Result<(), RepliersError> {
+        if self.header_written {
+            return Ok(());
+        }
+        self.header_written = true;
+        if self.options.header {
+            let headers: Vec<String> = self.spec.columns.iter().map(|c| c.header.clone()).collect();
+            self.write_row(&headers)?;
+        }
+        Ok(())
+    }
+
+    fn write_row<S: AsRef<[u8]>>(&mut self, row: &[S]) -> Result<(), RepliersError> {
+        self.writer
+            .write_record(row)
+            .map_err(|e| storage_error("CSV export failed", e))
+    }
+
+    /// Renders a resolved value into one cell per array element (one cell for scalars)
+    fn render(&self, value: &Value, format: ColumnFormat) -> Vec<String> {
+        match value {
+            Value::Array(items) => items
+                .iter()
+                .flat_map(|item| self.render(item, format))
+                .collect(),
+            value => vec![self.render_scalar(value, format)],
+        }
+    }
+
+    fn render_scalar(&self, value: &Value, format: ColumnFormat) -> String {
+        match (format, value) {
+            (_, Value::Null) => String::new(),
+            (ColumnFormat::Price, value) => match number(value) {
+                Some(amount) => format!("{:.*}", self.options.price_decimals, amount),
+                None => text(value),
+            },
+            (ColumnFormat::Date, Value::String(s)) => match parse_timestamp(s) {
+                Some(date) => date.format(&self.options.date_format).to_string(),
+                None => s.clone(),
+            },
+            (_, value) => text(value),
+        }
+    }
+}
+
+/// Reads a JSON number or numeric string
+pub(super) fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().replace(',', "").parse().ok(),
+        _ => None,
+    }
+}
+
+/// Renders a value as it appears in the JSON, dropping the `.0` of whole floats
+pub(super) fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => match n.as_f64() {
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < 1e15 => {
+                format!("{}", f as i64)
+            }
+            _ => n.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,363 @@
+//! GeoJSON export for maps and GIS tools
+//!
+//! [`GeoJsonExporter`] writes listings as a GeoJSON `FeatureCollection`, one `Point`
+//! feature per listing located at its map coordinates. Features are written as they
+//! arrive, so large result sets and paginated searches are never held in memory.
+//!
+//! Properties default to every field of the record except `map`; pass a
+//! [`ColumnSpec`] to select (and rename) a flat set instead, which is what most GIS
+//! attribute tables expect. Listings without usable coordinates are skipped and
+//! reported by [`GeoJsonExporter::report`], or rejected with
+//! [`MissingCoordinates::Fail`].
+//!
+//! Address history entries carry no coordinates of their own; write them with
+//! [`GeoJsonExporter::write_at`] and the location of the property they belong to.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use repliers_beta::export::csv::ColumnSpec;
+//! use repliers_beta::export::geojson::{GeoJsonExporter, GeoJsonOptions};
+//! use repliers_beta::{ListingSearchRequest, RepliersClient};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = RepliersClient::from_env()?;
+//! let request = ListingSearchRequest::builder().city("Toronto").build();
+//!
+//! let options = GeoJsonOptions {
+//!     properties: Some(ColumnSpec::parse("mls=mlsNumber,price=listPrice,beds=details.numBedrooms")?),
+//!     ..Default::default()
+//! };
+//! let mut exporter = GeoJsonExporter::create("toronto.geojson", options)?;
+//! exporter.write_stream(client.search_listings_stream(request)).await?;
+//! println!("Skipped without coordinates: {:?}", exporter.report().skipped);
+//! exporter.finish()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::csv::ColumnSpec;
+use super::lookup_path;
+use crate::models::geo::GeoPoint;
+use crate::storage::storage_error;
+use crate::RepliersError;
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// What to do with records that have no usable coordinates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingCoordinates {
+    /// Leave them out and list them in the [`GeoJsonReport`]
+    #[default]
+    Skip,
+
+    /// Fail with [`RepliersError::InvalidRequest`]
+    Fail,
+}
+
+/// Export options
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeoJsonOptions {
+    /// Properties to include; `None` includes every field except `map`
+    pub properties: Option<ColumnSpec>,
+
+    /// Dotted path of the latitude (default `map.latitude`)
+    pub latitude: String,
+
+    /// Dotted path of the longitude (default `map.longitude`)
+    pub longitude: String,
+
+    /// Dotted path of the feature ID (default `mlsNumber`); `None` omits IDs
+    pub id: Option<String>,
+
+    /// Handling of records without coordinates
+    pub missing: MissingCoordinates,
+}
+
+impl Default for GeoJsonOptions {
+    fn default() -> Self {
+        Self {
+            properties: None,
+            latitude: "map.latitude".to_string(),
+            longitude: "map.longitude".to_string(),
+            id: Some("mlsNumber".to_string()),
+            missing: MissingCoordinates::Skip,
+        }
+    }
+}
+
+/// Counts of what was written
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GeoJsonReport {
+    /// Number of features written
+    pub features: usize,
+
+    /// IDs of records skipped for lack of coordinates (`#<n>` for records without an
+    /// ID, numbered from 0 in the order they were written)
+    pub skipped: Vec<String>,
+}
+
+/// Streams records into a GeoJSON `FeatureCollection`
+///
+/// The collection is opened before the first feature and closed by
+/// [`finish`](Self::finish); output that was not finished is not valid GeoJSON.
+#[derive(Debug)]
+pub struct GeoJsonExporter<W: Write> {
+    writer: W,
+    options: GeoJsonOptions,
+    started: bool,
+    records: usize,
+    report: GeoJsonReport,
+}
+
+impl GeoJsonExporter<BufWriter<File>> {
+    /// Creates (or truncates) a GeoJSON file
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>, options: GeoJsonOptions) -> Result<Self, RepliersError> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| storage_error(path.display(), e))?;
+        Ok(Self::new(BufWriter::new(file), options))
+    }
+}
+
+impl<W: Write> GeoJsonExporter<W> {
+    /// Creates an exporter writing to `writer`
+    pub fn new(writer: W, options: GeoJsonOptions) -> Self {
+        Self {
+            writer,
+            options,
+            started: false,
+            records: 0,
+            report: GeoJsonReport::default(),
+        }
+    }
+
+    /// Returns what has been written so far
+    pub fn report(&self) -> &GeoJsonReport {
+        &self.report
+    }
+
+    /// Writes one record at its own coordinates
+    ///
+    /// Returns `false` if the record was skipped for lack of coordinates.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::InvalidRequest`] for records without coordinates under
+    /// [`MissingCoordinates::Fail`], [`RepliersError::ParseError`] if the record cannot be
+    /// serialized and [`RepliersError::Storage`] if writing fails.
+    pub fn write<T: Serialize + ?Sized>(&mut self, record: &T) -> Result<bool, RepliersError> {
+        let value = to_value(record)?;
+        let latitude = coordinate(&lookup_path(&value, &self.options.latitude));
+        let longitude = coordinate(&lookup_path(&value, &self.options.longitude));
+        match (latitude, longitude) {
+            (Some(latitude), Some(longitude)) if is_valid(latitude, longitude) => {
+                self.write_feature(&value, GeoPoint::new(latitude, longitude))?;
+                Ok(true)
+            }
+            _ => {
+                let id = self
+                    .feature_id(&value)
+                    .unwrap_or_else(|| json!(format!("#{}", self.records)));
+                let id = id
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| id.to_string());
+                self.records += 1;
+                match self.options.missing {
+                    MissingCoordinates::Skip => {
+                        self.report.skipped.push(id);
+                        Ok(false)
+                    }
+                    MissingCoordinates::Fail => Err(RepliersError::InvalidRequest(format!(
+                        "Listing {} has no coordinates",
+                        id
+                    ))),
+                }
+            }
+        }
+    }
+
+    /// Writes one record at the given location, ignoring its own coordinates
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::ParseError`] if the record cannot be serialized and
+    /// [`RepliersError::Storage`] if writing fails.
+    pub fn write_at<T: Serialize + ?Sized>(
+        &mut self,
+        record: &T,
+        point: GeoPoint,
+    ) -> Result<(), RepliersError> {
+        let value = to_value(record)?;
+        self.write_feature(&value, point)
+    }
+
+    /// Writes every record of an iterator, returning the number of features written
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first record that fails; see [`write`](Self::write).
+    pub fn write_all<T: Serialize>(
+        &mut self,
+        records: impl IntoIterator<Item = T>,
+    ) -> Result<usize, RepliersError> {
+        let mut written = 0;
+        for record in records {
+            written += usize::from(self.write(&record)?);
+        }
+        Ok(written)
+    }
+
+    /// Writes every record of a stream as it arrives, returning the number of features
+    /// written
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first stream or write error. Call [`finish`](Self::finish) to close
+    /// the collection over the features written so far.
+    pub async fn write_stream<T, S>(&mut self, stream: S) -> Result<usize, RepliersError>
+    where
+        T: Serialize,
+        S: Stream<Item = Result<T, RepliersError>>,
+    {
+        let mut stream = std::pin::pin!(stream);
+        let mut written = 0;
+        while let Some(record) = stream.next().await {
+            written += usize::from(self.write(&record?)?);
+        }
+        Ok(written)
+    }
+
+    /// Closes the collection, flushes and returns the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if writing or flushing fails.
+    pub fn finish(mut self) -> Result<W, RepliersError> {
+        self.start()?;
+        self.write_bytes(b"\n]}\n")?;
+        self.writer
+            .flush()
+            .map_err(|e| storage_error("GeoJSON export failed", e))?;
+        Ok(self.writer)
+    }
+
+    fn start(&mut self) -> Result<(), RepliersError> {
+        if !self.started {
+            self.started = true;
+            self.write_bytes(br#"{"type":"FeatureCollection","features":["#)?;
+        }
+        Ok(())
+    }
+
+    fn write_feature(&mut self, value: &Value, point: GeoPoint) -> Result<(), RepliersError> {
+        let feature = Feature {
+            kind: "Feature",
+            id: self.feature_id(value),
+            geometry: Point {
+                kind: "Point",
+                coordinates: [point.longitude, point.latitude],
+            },
+            properties: Properties(self.properties(value)),
+        };
+
+        self.start()?;
+        let separator: &[u8] = if self.report.features == 0 {
+            b"\n"
+        } else {
+            b",\n"
+        };
+        self.write_bytes(separator)?;
+        serde_json::to_writer(&mut self.writer, &feature)
+            .map_err(|e| storage_error("GeoJSON export failed", e))?;
+        self.records += 1;
+        self.report.features += 1;
+        Ok(())
+    }
+
+    fn feature_id(&self, value: &Value) -> Option<Value> {
+        let id = lookup_path(value, self.options.id.as_deref()?);
+        matches!(id, Value::String(_) | Value::Number(_)).then_some(id)
+    }
+
+    /// Selected properties, in column order
+    fn properties(&self, value: &Value) -> Vec<(String, Value)> {
+        match (&self.options.properties, value) {
+            (Some(spec), value) => spec
+                .columns()
+                .iter()
+                .map(|column| (column.header.clone(), lookup_path(value, &column.path)))
+                .collect(),
+            (None, Value::Object(map)) => map
+                .iter()
+                .filter(|(key, _)| key.as_str() != "map")
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            (None, _) => Vec::new(),
+        }
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), RepliersError> {
+        self.writer
+            .write_all(bytes)
+            .map_err(|e| storage_error("GeoJSON export failed", e))
+    }
+}
+
+#[derive(Serialize)]
+struct Feature {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Value>,
+    geometry: Point,
+    properties: Properties,
+}
+
+#[derive(Serialize)]
+struct Point {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    coordinates: [f64; 2],
+}
+
+/// Properties serialized as an object that keeps its entries' order
+struct Properties(Vec<(String, Value)>);
+
+impl Serialize for Properties {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(key, value)| (key, value)))
+    }
+}
+
+fn to_value<T: Serialize + ?Sized>(record: &T) -> Result<Value, RepliersError> {
+    serde_json::to_value(record)
+        .map_err(|e| RepliersError::ParseError(format!("Cannot serialize record: {}", e)))
+}
+
+/// Reads a coordinate from a JSON number or numeric string
+fn coordinate(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Rejects out-of-range coordinates and the `0, 0` placeholder some boards send for
+/// unmapped listings
+fn is_valid(latitude: f64, longitude: f64) -> bool {
+    latitude.is_finite()
+        && longitude.is_finite()
+        && (-90.0..=90.0).contains(&latitude)
+        && (-180.0..=180.0).contains(&longitude)
+        && !(latitude == 0.0 && longitude == 0.0)
+}
@@ -0,0 +1,53 @@
+//! Exporters that write listings to analysis-friendly file formats
+//!
+//! - [`csv`]: spreadsheet rows with a configurable column mapping
+//! - [`geojson`]: a `FeatureCollection` of listing locations for maps and GIS tools
+//! - [`ndjson`]: one JSON record per line, optionally gzipped, with a matching reader
+//! - `parquet`: typed columnar files for DuckDB, Polars and pandas (`parquet` feature)
+//!
+//! Exporters write incrementally, one record at a time, so they can consume the
+//! auto-paginating search streams without holding every page in memory.
+
+pub mod csv;
+pub mod geojson;
+pub mod ndjson;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+
+use serde_json::Value;
+
+/// Resolves a dotted path (e.g. `address.city`) against a JSON value
+///
+/// Numeric segments index into arrays (`images.0`); any other segment applied to an
+/// array is applied to each element, so `agents.name` yields an array of names. Missing
+/// fields resolve to `Null`.
+pub fn lookup_path(value: &Value, path: &str) -> Value {
+    let segments: Vec<&str> = path.split('.').filter(|s| !s.is_empty()).collect();
+    lookup(value, &segments)
+}
+
+fn lookup(value: &Value, segments: &[&str]) -> Value {
+    let Some((segment, rest)) = segments.split_first() else {
+        return value.clone();
+    };
+    match value {
+        Value::Object(map) => map
+            .get(*segment)
+            .map(|v| lookup(v, rest))
+            .unwrap_or(Value::Null),
+        Value::Array(items) => match segment.parse::<usize>() {
+            Ok(index) => items
+                .get(index)
+                .map(|v| lookup(v, rest))
+                .unwrap_or(Value::Null),
+            Err(_) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| lookup(item, segments))
+                    .filter(|v| !v.is_null())
+                    .collect(),
+            ),
+        },
+        _ => Value::Null,
+    }
+}
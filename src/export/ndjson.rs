@@ -0,0 +1,535 @@
+//! Newline-delimited JSON export and import
+//!
+//! [`NdjsonWriter`] writes one JSON record per line and [`NdjsonReader`] reads them back,
+//! one record at a time, so exports and re-imports of millions of listings, deleted
+//! listings or history entries never hold more than a line in memory. Both work with
+//! any serializable record type and with async streams
+//! ([`NdjsonWriter::write_stream`], [`NdjsonReader::into_stream`]).
+//!
+//! Files whose name ends in `.gz` are gzip-compressed; [`NdjsonReader::open`] detects
+//! compression from the file contents. Gzip output is written as a series of complete
+//! gzip members of [`checkpoint_every`](NdjsonWriter::checkpoint_every) records, which
+//! every gzip reader concatenates transparently.
+//!
+//! [`NdjsonWriter::append`] resumes an existing file after an interrupted export: a
+//! partial last line is dropped from plain files, and a truncated last member is dropped
+//! from gzip files, so a crash loses at most the records written since the last
+//! checkpoint.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use futures::{StreamExt, TryStreamExt};
+//! use repliers_beta::export::ndjson::{NdjsonReader, NdjsonWriter};
+//! use repliers_beta::{Listing, ListingSearchRequest, RepliersClient};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = RepliersClient::from_env()?;
+//! let request = ListingSearchRequest::builder().city("Toronto").build();
+//!
+//! // Resume where the previous run stopped
+//! let mut writer = NdjsonWriter::append("toronto.ndjson.gz")?;
+//! let listings = client
+//!     .search_listings_stream(request)
+//!     .skip(writer.existing_records());
+//! writer.write_stream(listings).await?;
+//! writer.finish()?;
+//!
+//! // Read them back
+//! let mut listings = NdjsonReader::open("toronto.ndjson.gz")?.into_stream::<Listing>();
+//! while let Some(listing) = listings.try_next().await? {
+//!     println!("{}", listing.mls_number);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::storage::storage_error;
+use crate::RepliersError;
+use flate2::bufread::GzDecoder;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// Output compression
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Plain text
+    #[default]
+    None,
+
+    /// Gzip at the default compression level
+    Gzip,
+}
+
+impl Compression {
+    /// Gzip for paths ending in `.gz`, plain text otherwise
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("gz") => Compression::Gzip,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Records per gzip member unless changed with [`NdjsonWriter::checkpoint_every`]
+const DEFAULT_CHECKPOINT_RECORDS: usize = 1_000;
+
+enum Sink<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+
+    /// Left behind when finishing a gzip member failed part-way
+    Broken,
+}
+
+/// Writes records as newline-delimited JSON
+pub struct NdjsonWriter<W: Write> {
+    sink: Sink<W>,
+    records: usize,
+    existing: usize,
+    checkpoint_records: usize,
+    since_checkpoint: usize,
+}
+
+impl NdjsonWriter<BufWriter<File>> {
+    /// Creates (or truncates) an NDJSON file, gzip-compressed if the name ends in `.gz`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, RepliersError> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| storage_error(path.display(), e))?;
+        Ok(Self::new(
+            BufWriter::new(file),
+            Compression::from_path(path),
+        ))
+    }
+
+    /// Opens an NDJSON file for appending, creating it if it does not exist
+    ///
+    /// The records already in the file are counted and reported by
+    /// [`existing_records`](Self::existing_records), so an interrupted export can skip
+    /// what it already wrote. A partial last line left by a crash is removed from plain
+    /// files, and a truncated last gzip member (the records written since the last
+    /// checkpoint) from gzip files.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file cannot be read or opened, or if a
+    /// gzip file is corrupt rather than truncated (it cannot be resumed safely).
+    pub fn append(path: impl AsRef<Path>) -> Result<Self, RepliersError> {
+        let path = path.as_ref();
+        let compression = Compression::from_path(path);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(|e| storage_error(path.display(), e))?;
+
+        let existing = match compression {
+            Compression::None => {
+                resume_plain(&mut file).map_err(|e| storage_error(path.display(), e))?
+            }
+            Compression::Gzip => resume_gzip(&mut file).map_err(|e| {
+                storage_error(
+                    path.display(),
+                    format_args!("cannot resume gzip file: {}", e),
+                )
+            })?,
+        };
+        file.seek(SeekFrom::End(0))
+            .map_err(|e| storage_error(path.display(), e))?;
+
+        let mut writer = Self::new(BufWriter::new(file), compression);
+        writer.existing = existing;
+        Ok(writer)
+    }
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    /// Creates a writer over `writer`
+    pub fn new(writer: W, compression: Compression) -> Self {
+        let sink = match compression {
+            Compression::None => Sink::Plain(writer),
+            Compression::Gzip => Sink::Gzip(GzEncoder::new(writer, flate2::Compression::default())),
+        };
+        Self {
+            sink,
+            records: 0,
+            existing: 0,
+            checkpoint_records: DEFAULT_CHECKPOINT_RECORDS,
+            since_checkpoint: 0,
+        }
+    }
+
+    /// Sets how many records go into each gzip member (default 1000, minimum 1)
+    ///
+    /// Smaller members lose fewer records to a crash at a small cost in compression.
+    /// Has no effect on plain output.
+    pub fn checkpoint_every(mut self, records: usize) -> Self {
+        self.checkpoint_records = records.max(1);
+        self
+    }
+
+    /// Returns the number of records written by this writer
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// Returns the number of records that were already in the file when it was opened
+    /// with [`append`](NdjsonWriter::append)
+    pub fn existing_records(&self) -> usize {
+        self.existing
+    }
+
+    /// Writes one record as a line
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::ParseError`] if the record cannot be serialized and
+    /// [`RepliersError::Storage`] if writing fails.
+    pub fn write<T: Serialize + ?Sized>(&mut self, record: &T) -> Result<(), RepliersError> {
+        let mut line = serde_json::to_vec(record)
+            .map_err(|e| RepliersError::ParseError(format!("Cannot serialize record: {}", e)))?;
+        line.push(b'\n');
+        let result = match &mut self.sink {
+            Sink::Plain(w) => w.write_all(&line),
+            Sink::Gzip(w) => w.write_all(&line),
+            Sink::Broken => Err(broken_sink()),
+        };
+        result.map_err(|e| storage_error("NDJSON export failed", e))?;
+        self.records += 1;
+        self.since_checkpoint += 1;
+        if self.since_checkpoint >= self.checkpoint_records {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+
+    /// Makes every record written so far recoverable by [`append`](NdjsonWriter::append)
+    ///
+    /// Gzip output finishes the current member and starts a new one; both kinds of output
+    /// are flushed to the underlying writer. Called automatically every
+    /// [`checkpoint_every`](Self::checkpoint_every) records.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if writing or flushing fails.
+    pub fn checkpoint(&mut self) -> Result<(), RepliersError> {
+        let result = match std::mem::replace(&mut self.sink, Sink::Broken) {
+            Sink::Plain(mut w) => w.flush().map(|()| Sink::Plain(w)),
+            Sink::Gzip(w) => w.finish().and_then(|mut w| {
+                w.flush()?;
+                Ok(Sink::Gzip(GzEncoder::new(
+                    w,
+                    flate2::Compression::default(),
+                )))
+            }),
+            Sink::Broken => Err(broken_sink()),
+        };
+        self.sink = result.map_err(|e| storage_error("NDJSON export failed", e))?;
+        self.since_checkpoint = 0;
+        Ok(())
+    }
+
+    /// Writes every record of an iterator, returning the number written
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first record that fails; see [`write`](Self::write).
+    pub fn write_all<T: Serialize>(
+        &mut self,
+        records: impl IntoIterator<Item = T>,
+    ) -> Result<usize, RepliersError> {
+        let mut written = 0;
+        for record in records {
+            self.write(&record)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Writes every record of a stream as it arrives, returning the number written
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first stream or write error. Call [`finish`](Self::finish) to keep
+    /// the records written so far.
+    pub async fn write_stream<T, S>(&mut self, stream: S) -> Result<usize, RepliersError>
+    where
+        T: Serialize,
+        S: Stream<Item = Result<T, RepliersError>>,
+    {
+        let mut stream = std::pin::pin!(stream);
+        let mut written = 0;
+        while let Some(record) = stream.next().await {
+            self.write(&record?)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Completes the gzip stream (if any), flushes and returns the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if writing or flushing fails.
+    pub fn finish(self) -> Result<W, RepliersError> {
+        let mut writer = match self.sink {
+            Sink::Plain(w) => w,
+            Sink::Gzip(w) => w
+                .finish()
+                .map_err(|e| storage_error("NDJSON export failed", e))?,
+            Sink::Broken => return Err(storage_error("NDJSON export failed", broken_sink())),
+        };
+        writer
+            .flush()
+            .map_err(|e| storage_error("NDJSON export failed", e))?;
+        Ok(writer)
+    }
+}
+
+impl<W: Write> std::fmt::Debug for NdjsonWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let compression = match self.sink {
+            Sink::Plain(_) => Compression::None,
+            Sink::Gzip(_) | Sink::Broken => Compression::Gzip,
+        };
+        f.debug_struct("NdjsonWriter")
+            .field("compression", &compression)
+            .field("records", &self.records)
+            .field("existing", &self.existing)
+            .field("checkpoint_records", &self.checkpoint_records)
+            .finish()
+    }
+}
+
+/// Counts the complete lines of a plain file and truncates a partial last line
+fn resume_plain(file: &mut File) -> std::io::Result<usize> {
+    let mut reader = BufReader::new(&*file);
+    let mut records = 0;
+    let mut complete = 0u64;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 || line.last() != Some(&b'\n') {
+            break;
+        }
+        complete += read as u64;
+        if line.iter().any(|b| !b.is_ascii_whitespace()) {
+            records += 1;
+        }
+    }
+    if file.metadata()?.len() > complete {
+        file.set_len(complete)?;
+    }
+    Ok(records)
+}
+
+/// Counts the lines of a gzip file member by member, dropping a truncated last member
+///
+/// Fails if a member is corrupt rather than cut short, or ends in a partial line.
+fn resume_gzip(file: &mut File) -> std::io::Result<usize> {
+    let mut input = BufReader::new(&*file);
+    let mut records = 0;
+    let mut complete = 0u64;
+    let mut line = Vec::new();
+    while !input.fill_buf()?.is_empty() {
+        let mut member = BufReader::new(GzDecoder::new(&mut input));
+        let mut member_records = 0;
+        loop {
+            line.clear();
+            match member.read_until(b'\n', &mut line) {
+                Ok(0) => break,
+                Ok(_) if line.last() != Some(&b'\n') => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "last line is incomplete",
+                    ));
+                }
+                Ok(_) => {
+                    if line.iter().any(|b| !b.is_ascii_whitespace()) {
+                        member_records += 1;
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    drop(member);
+                    file.set_len(complete)?;
+                    return Ok(records);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        drop(member);
+        records += member_records;
+        complete = input.stream_position()?;
+    }
+    Ok(records)
+}
+
+fn broken_sink() -> std::io::Error {
+    std::io::Error::other("a previous gzip checkpoint failed")
+}
+
+/// Reads newline-delimited JSON records
+///
+/// Blank lines are skipped. Errors name the line they occurred on.
+pub struct NdjsonReader<R: BufRead> {
+    reader: R,
+    line: usize,
+    buf: String,
+}
+
+impl NdjsonReader<Box<dyn BufRead + Send>> {
+    /// Opens an NDJSON file, decompressing it if it is gzip-compressed
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file cannot be opened.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, RepliersError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| storage_error(path.display(), e))?;
+        let mut reader = BufReader::new(file);
+        let gzip = reader
+            .fill_buf()
+            .map_err(|e| storage_error(path.display(), e))?
+            .starts_with(&[0x1f, 0x8b]);
+        let reader: Box<dyn BufRead + Send> = if gzip {
+            Box::new(BufReader::new(MultiGzDecoder::new(reader)))
+        } else {
+            Box::new(reader)
+        };
+        Ok(Self::new(reader))
+    }
+}
+
+impl<R: BufRead> NdjsonReader<R> {
+    /// Creates a reader over uncompressed NDJSON
+    ///
+    /// Wrap gzip input in [`flate2::bufread::MultiGzDecoder`] and a `BufReader` first,
+    /// or use [`open`](NdjsonReader::open) for files.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: 0,
+            buf: String::new(),
+        }
+    }
+
+    /// Returns the number of lines read so far
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Reads the next record, or `None` at the end of the input
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::ParseError`] for lines that are not valid JSON for `T`
+    /// and [`RepliersError::Storage`] if reading fails.
+    pub fn read<T: DeserializeOwned>(&mut self) -> Result<Option<T>, RepliersError> {
+        loop {
+            self.buf.clear();
+            let read = self
+                .reader
+                .read_line(&mut self.buf)
+                .map_err(|e| storage_error("NDJSON import failed", e))?;
+            if read == 0 {
+                return Ok(None);
+            }
+            self.line += 1;
+            let line = self.buf.trim();
+            if line.is_empty() {
+                continue;
+            }
+            return serde_json::from_str(line).map(Some).map_err(|e| {
+                RepliersError::ParseError(format!("NDJSON line {}: {}", self.line, e))
+            });
+        }
+    }
+
+    /// Returns an iterator over the remaining records
+    pub fn records<T: DeserializeOwned>(self) -> Records<R, T> {
+        Records {
+            reader: self,
+            done: false,
+            _record: PhantomData,
+        }
+    }
+}
+
+impl<R: BufRead + Send + 'static> NdjsonReader<R> {
+    /// Returns a stream of the remaining records
+    ///
+    /// Lines are read and parsed on a blocking thread a bounded batch ahead of the
+    /// consumer, so the stream can feed exporters, a listing store or sync sinks without
+    /// blocking the runtime. Ends after the first error. Must be called within a Tokio
+    /// runtime.
+    pub fn into_stream<T>(self) -> BoxStream<'static, Result<T, RepliersError>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let (sender, receiver) = tokio::sync::mpsc::channel(256);
+        tokio::task::spawn_blocking(move || {
+            for record in self.records::<T>() {
+                if sender.blocking_send(record).is_err() {
+                    break;
+                }
+            }
+        });
+        futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|record| (record, receiver))
+        })
+        .boxed()
+    }
+}
+
+impl<R: BufRead> std::fmt::Debug for NdjsonReader<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NdjsonReader")
+            .field("line", &self.line)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Iterator over the records of an [`NdjsonReader`]
+///
+/// Ends after the first error.
+pub struct Records<R: BufRead, T> {
+    reader: NdjsonReader<R>,
+    done: bool,
+    _record: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T: DeserializeOwned> Iterator for Records<R, T> {
+    type Item = Result<T, RepliersError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let record = self.reader.read().transpose();
+        self.done = !matches!(record, Some(Ok(_)));
+        record
+    }
+}
+
+impl<R: BufRead, T> std::fmt::Debug for Records<R, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Records")
+            .field("reader", &self.reader)
+            .finish()
+    }
+}
@@ -0,0 +1,592 @@
+//! Apache Parquet export for analytics tools
+//!
+//! [`ParquetExporter`] writes listings to a Parquet file with a typed Arrow schema, so
+//! DuckDB, Polars, pandas and Spark load them with proper numeric, date and list
+//! columns instead of re-parsing JSON. Rows are buffered into row groups of
+//! [`ParquetOptions::row_group_size`] and each full group is written as soon as it is
+//! complete, so a paginated search of hundreds of thousands of listings can be streamed
+//! to disk with [`ParquetExporter::write_stream`].
+//!
+//! A [`ParquetSchema`] projects dotted paths (e.g. `address.city`) to typed columns.
+//! [`ParquetSchema::listings`] covers the common fields of the typed
+//! [`Listing`](crate::Listing) model; build your own with [`ParquetSchema::parse`] to
+//! export other records or board-specific fields of the raw JSON. Every column is
+//! nullable and values that do not convert to the column's type are written as nulls.
+//!
+//! Requires the `parquet` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use repliers_beta::export::parquet::{ParquetExporter, ParquetSchema};
+//! use repliers_beta::{ListingSearchRequest, RepliersClient};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = RepliersClient::from_env()?;
+//! let request = ListingSearchRequest::builder().city("Toronto").build();
+//!
+//! let mut exporter = ParquetExporter::create("toronto.parquet", ParquetSchema::listings())?;
+//! let rows = exporter.write_stream(client.search_listings_stream(request)).await?;
+//! exporter.finish()?;
+//! println!("Wrote {} rows", rows);
+//! # Ok(())
+//! # }
+//! ```
+
+use super::csv::{number, text};
+use super::lookup_path;
+use crate::models::parse_timestamp;
+use crate::storage::storage_error;
+use crate::RepliersError;
+use ::parquet::arrow::ArrowWriter;
+use ::parquet::file::properties::WriterProperties;
+use arrow_array::builder::{
+    ArrayBuilder, BooleanBuilder, Date32Builder, Float64Builder, Int64Builder, ListBuilder,
+    StringBuilder, TimestampMillisecondBuilder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::NaiveDate;
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+pub use ::parquet::basic::Compression;
+
+/// Arrow type of a column
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColumnType {
+    /// UTF-8 string; numbers and booleans are written as text, objects and arrays as JSON
+    #[default]
+    Text,
+
+    /// 64-bit signed integer
+    Integer,
+
+    /// 64-bit float; numeric strings such as `"1,250"` are parsed
+    Float,
+
+    /// Boolean; also accepts `"Y"`/`"N"`, `"yes"`/`"no"` and `"true"`/`"false"`
+    Boolean,
+
+    /// Calendar date (`Date32`), from dates and timestamps
+    Date,
+
+    /// Millisecond UTC timestamp
+    Timestamp,
+
+    /// List of strings; scalars become one-element lists
+    TextList,
+}
+
+impl ColumnType {
+    fn data_type(self) -> DataType {
+        match self {
+            ColumnType::Text => DataType::Utf8,
+            ColumnType::Integer => DataType::Int64,
+            ColumnType::Float => DataType::Float64,
+            ColumnType::Boolean => DataType::Boolean,
+            ColumnType::Date => DataType::Date32,
+            ColumnType::Timestamp => {
+                DataType::Timestamp(TimeUnit::Millisecond, Some("+00:00".into()))
+            }
+            ColumnType::TextList => {
+                DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+            }
+        }
+    }
+}
+
+/// One Parquet column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParquetColumn {
+    /// Column name in the file
+    pub name: String,
+
+    /// Dotted path of the value in the record's JSON
+    pub path: String,
+
+    /// Arrow type of the column
+    pub kind: ColumnType,
+}
+
+impl ParquetColumn {
+    /// Creates a column
+    pub fn new(name: impl Into<String>, path: impl Into<String>, kind: ColumnType) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+            kind,
+        }
+    }
+}
+
+/// The columns written to a Parquet file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParquetSchema {
+    columns: Vec<ParquetColumn>,
+}
+
+impl Default for ParquetSchema {
+    fn default() -> Self {
+        Self::listings()
+    }
+}
+
+impl ParquetSchema {
+    /// Creates a schema from columns
+    pub fn new(columns: impl IntoIterator<Item = ParquetColumn>) -> Self {
+        Self {
+            columns: columns.into_iter().collect(),
+        }
+    }
+
+    /// Common fields of the typed [`Listing`](crate::Listing) model, with snake_case
+    /// column names
+    pub fn listings() -> Self {
+        use ColumnType::*;
+        Self::new(
+            [
+                ("mls_number", "mlsNumber", Text),
+                ("board_id", "boardId", Integer),
+                ("status", "status", Text),
+                ("last_status", "lastStatus", Text),
+                ("class", "class", Text),
+                ("type", "type", Text),
+                ("list_price", "listPrice", Float),
+                ("original_price", "originalPrice", Float),
+                ("sold_price", "soldPrice", Float),
+                ("list_date", "listDate", Date),
+                ("sold_date", "soldDate", Date),
+                ("updated_on", "updatedOn", Timestamp),
+                ("days_on_market", "daysOnMarket", Integer),
+                ("street_number", "address.streetNumber", Text),
+                ("street_name", "address.streetName", Text),
+                ("street_suffix", "address.streetSuffix", Text),
+                ("unit_number", "address.unitNumber", Text),
+                ("city", "address.city", Text),
+                ("area", "address.area", Text),
+                ("neighborhood", "address.neighborhood", Text),
+                ("state", "address.state", Text),
+                ("zip", "address.zip", Text),
+                ("latitude", "map.latitude", Float),
+                ("longitude", "map.longitude", Float),
+                ("property_type", "details.propertyType", Text),
+                ("style", "details.style", Text),
+                ("bedrooms", "details.numBedrooms", Integer),
+                ("bedrooms_plus", "details.numBedroomsPlus", Integer),
+                ("bathrooms", "details.numBathrooms", Integer),
+                ("sqft", "details.sqft", Text),
+                ("year_built", "details.yearBuilt", Text),
+                ("garage_spaces", "details.numGarageSpaces", Float),
+                ("parking_spaces", "details.numParkingSpaces", Float),
+                ("maintenance_fee", "condominium.fees.maintenance", Float),
+                ("annual_taxes", "taxes.annualAmount", Float),
+                ("brokerage", "office.brokerageName", Text),
+                ("agents", "agents.name", TextList),
+                ("photo_count", "photoCount", Integer),
+                ("images", "images", TextList),
+            ]
+            .into_iter()
+            .map(|(name, path, kind)| ParquetColumn::new(name, path, kind)),
+        )
+    }
+
+    /// Parses a comma-separated spec such as
+    /// `mls=mlsNumber,price=listPrice:float,listed=listDate:date,rooms=rooms.description:list`
+    ///
+    /// Each entry is `[name=]path[:type]`, where type is `text`, `integer`, `float`,
+    /// `boolean`, `date`, `timestamp` or `list`. The name defaults to the path and the
+    /// type to `text`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::InvalidConfig`] for empty specs, empty paths, unknown
+    /// types or duplicate names.
+    pub fn parse(spec: &str) -> Result<Self, RepliersError> {
+        let mut columns: Vec<ParquetColumn> = Vec::new();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, rest) = match entry.split_once('=') {
+                Some((name, rest)) => (Some(name.trim()), rest.trim()),
+                None => (None, entry),
+            };
+            let (path, kind) = match rest.rsplit_once(':') {
+                Some((path, kind)) => (path.trim(), parse_type(kind.trim())?),
+                None => (rest, ColumnType::Text),
+            };
+            if path.is_empty() {
+                return Err(RepliersError::InvalidConfig(format!(
+                    "Parquet column '{}' has no path",
+                    entry
+                )));
+            }
+            let name = name.unwrap_or(path);
+            if columns.iter().any(|c| c.name == name) {
+                return Err(RepliersError::InvalidConfig(format!(
+                    "Duplicate Parquet column '{}'",
+                    name
+                )));
+            }
+            columns.push(ParquetColumn::new(name, path, kind));
+        }
+        if columns.is_empty() {
+            return Err(RepliersError::InvalidConfig(
+                "Parquet column spec is empty".to_string(),
+            ));
+        }
+        Ok(Self { columns })
+    }
+
+    /// Appends a column
+    pub fn column(mut self, column: ParquetColumn) -> Self {
+        self.columns.push(column);
+        self
+    }
+
+    /// Returns the columns in order
+    pub fn columns(&self) -> &[ParquetColumn] {
+        &self.columns
+    }
+
+    /// Returns the Arrow schema of the file, with every field nullable
+    pub fn arrow_schema(&self) -> SchemaRef {
+        Arc::new(Schema::new(
+            self.columns
+                .iter()
+                .map(|c| Field::new(&c.name, c.kind.data_type(), true))
+                .collect::<Vec<_>>(),
+        ))
+    }
+}
+
+fn parse_type(kind: &str) -> Result<ColumnType, RepliersError> {
+    match kind.to_ascii_lowercase().as_str() {
+        "text" | "string" => Ok(ColumnType::Text),
+        "integer" | "int" => Ok(ColumnType::Integer),
+        "float" | "number" => Ok(ColumnType::Float),
+        "boolean" | "bool" => Ok(ColumnType::Boolean),
+        "date" => Ok(ColumnType::Date),
+        "timestamp" => Ok(ColumnType::Timestamp),
+        "list" => Ok(ColumnType::TextList),
+        other => Err(RepliersError::InvalidConfig(format!(
+            "Unknown Parquet column type '{}' (expected text, integer, float, boolean, date, timestamp or list)",
+            other
+        ))),
+    }
+}
+
+/// Export options
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParquetOptions {
+    /// Rows per row group (default 65,536)
+    pub row_group_size: usize,
+
+    /// Column compression (default Snappy)
+    pub compression: Compression,
+}
+
+impl Default for ParquetOptions {
+    fn default() -> Self {
+        Self {
+            row_group_size: 65_536,
+            compression: Compression::SNAPPY,
+        }
+    }
+}
+
+/// Streams records into a Parquet file, one row group at a time
+///
+/// Rows are buffered until a row group is full; [`finish`](Self::finish) writes the
+/// last, partial group and the file footer. Output that was not finished is not a
+/// readable Parquet file.
+pub struct ParquetExporter<W: Write + Send> {
+    writer: ArrowWriter<W>,
+    schema: SchemaRef,
+    columns: Vec<ParquetColumn>,
+    builders: Vec<ColumnBuilder>,
+    row_group_size: usize,
+    buffered: usize,
+    rows: usize,
+    row_groups: usize,
+}
+
+impl ParquetExporter<BufWriter<File>> {
+    /// Creates (or truncates) a Parquet file
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>, schema: ParquetSchema) -> Result<Self, RepliersError> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| storage_error(path.display(), e))?;
+        Self::new(BufWriter::new(file), schema)
+    }
+}
+
+impl<W: Write + Send> ParquetExporter<W> {
+    /// Creates an exporter writing to `writer` with default options
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file header cannot be written.
+    pub fn new(writer: W, schema: ParquetSchema) -> Result<Self, RepliersError> {
+        Self::with_options(writer, schema, ParquetOptions::default())
+    }
+
+    /// Creates an exporter writing to `writer`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::InvalidConfig`] for schemas without columns and
+    /// [`RepliersError::Storage`] if the file header cannot be written.
+    pub fn with_options(
+        writer: W,
+        schema: ParquetSchema,
+        options: ParquetOptions,
+    ) -> Result<Self, RepliersError> {
+        if schema.columns.is_empty() {
+            return Err(RepliersError::InvalidConfig(
+                "Parquet schema has no columns".to_string(),
+            ));
+        }
+        let row_group_size = options.row_group_size.max(1);
+        let properties = WriterProperties::builder()
+            .set_compression(options.compression)
+            .set_max_row_group_size(row_group_size)
+            .build();
+        let arrow_schema = schema.arrow_schema();
+        let writer = ArrowWriter::try_new(writer, arrow_schema.clone(), Some(properties))
+            .map_err(|e| storage_error("Parquet export failed", e))?;
+        let builders = schema
+            .columns
+            .iter()
+            .map(|c| ColumnBuilder::new(c.kind))
+            .collect();
+
+        Ok(Self {
+            writer,
+            schema: arrow_schema,
+            columns: schema.columns,
+            builders,
+            row_group_size,
+            buffered: 0,
+            rows: 0,
+            row_groups: 0,
+        })
+    }
+
+    /// Returns the number of rows written so far, including buffered rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of complete row groups written so far
+    pub fn row_groups(&self) -> usize {
+        self.row_groups
+    }
+
+    /// Writes one record
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::ParseError`] if the record cannot be serialized and
+    /// [`RepliersError::Storage`] if a full row group cannot be written.
+    pub fn write<T: Serialize + ?Sized>(&mut self, record: &T) -> Result<(), RepliersError> {
+        let value = serde_json::to_value(record)
+            .map_err(|e| RepliersError::ParseError(format!("Cannot serialize record: {}", e)))?;
+        for (column, builder) in self.columns.iter().zip(&mut self.builders) {
+            builder.append(&lookup_path(&value, &column.path));
+        }
+        self.buffered += 1;
+        self.rows += 1;
+        if self.buffered >= self.row_group_size {
+            self.flush_row_group()?;
+        }
+        Ok(())
+    }
+
+    /// Writes every record of an iterator, returning the number of rows written
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first record that fails; see [`write`](Self::write).
+    pub fn write_all<T: Serialize>(
+        &mut self,
+        records: impl IntoIterator<Item = T>,
+    ) -> Result<usize, RepliersError> {
+        let mut written = 0;
+        for record in records {
+            self.write(&record)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Writes every record of a stream as it arrives, returning the number of rows
+    /// written
+    ///
+    /// # Errors
+    ///
+    /// Stops at the first stream or write error. Call [`finish`](Self::finish) to keep
+    /// the rows written so far.
+    pub async fn write_stream<T, S>(&mut self, stream: S) -> Result<usize, RepliersError>
+    where
+        T: Serialize,
+        S: Stream<Item = Result<T, RepliersError>>,
+    {
+        let mut stream = std::pin::pin!(stream);
+        let mut written = 0;
+        while let Some(record) = stream.next().await {
+            self.write(&record?)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Writes the buffered rows and the file footer, and returns the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if writing or flushing fails.
+    pub fn finish(mut self) -> Result<W, RepliersError> {
+        self.flush_row_group()?;
+        let mut writer = self
+            .writer
+            .into_inner()
+            .map_err(|e| storage_error("Parquet export failed", e))?;
+        writer
+            .flush()
+            .map_err(|e| storage_error("Parquet export failed", e))?;
+        Ok(writer)
+    }
+
+    fn flush_row_group(&mut self) -> Result<(), RepliersError> {
+        if self.buffered == 0 {
+            return Ok(());
+        }
+        let columns: Vec<ArrayRef> = self.builders.iter_mut().map(|b| b.finish()).collect();
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)
+            .map_err(|e| storage_error("Parquet export failed", e))?;
+        self.writer
+            .write(&batch)
+            .map_err(|e| storage_error("Parquet export failed", e))?;
+        self.writer
+            .flush()
+            .map_err(|e| storage_error("Parquet export failed", e))?;
+        self.buffered = 0;
+        self.row_groups += 1;
+        Ok(())
+    }
+}
+
+impl<W: Write + Send> std::fmt::Debug for ParquetExporter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParquetExporter")
+            .field("columns", &self.columns)
+            .field("row_group_size", &self.row_group_size)
+            .field("rows", &self.rows)
+            .field("row_groups", &self.row_groups)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Buffers one column of the current row group
+enum ColumnBuilder {
+    Text(StringBuilder),
+    Integer(Int64Builder),
+    Float(Float64Builder),
+    Boolean(BooleanBuilder),
+    Date(Date32Builder),
+    Timestamp(TimestampMillisecondBuilder),
+    TextList(ListBuilder<StringBuilder>),
+}
+
+impl ColumnBuilder {
+    fn new(kind: ColumnType) -> Self {
+        match kind {
+            ColumnType::Text => Self::Text(StringBuilder::new()),
+            ColumnType::Integer => Self::Integer(Int64Builder::new()),
+            ColumnType::Float => Self::Float(Float64Builder::new()),
+            ColumnType::Boolean => Self::Boolean(BooleanBuilder::new()),
+            ColumnType::Date => Self::Date(Date32Builder::new()),
+            ColumnType::Timestamp => {
+                Self::Timestamp(TimestampMillisecondBuilder::new().with_timezone("+00:00"))
+            }
+            ColumnType::TextList => Self::TextList(ListBuilder::new(StringBuilder::new())),
+        }
+    }
+
+    fn append(&mut self, value: &Value) {
+        match self {
+            Self::Text(b) => b.append_option((!value.is_null()).then(|| text(value))),
+            Self::Integer(b) => b.append_option(integer(value)),
+            Self::Float(b) => b.append_option(number(value).filter(|f| f.is_finite())),
+            Self::Boolean(b) => b.append_option(boolean(value)),
+            Self::Date(b) => b.append_option(date(value)),
+            Self::Timestamp(b) => b.append_option(
+                value
+                    .as_str()
+                    .and_then(parse_timestamp)
+                    .map(|dt| dt.timestamp_millis()),
+            ),
+            Self::TextList(b) => match value {
+                Value::Null => b.append_null(),
+                Value::Array(items) => {
+                    for item in items.iter().filter(|v| !v.is_null()) {
+                        b.values().append_value(text(item));
+                    }
+                    b.append(true);
+                }
+                scalar => {
+                    b.values().append_value(text(scalar));
+                    b.append(true);
+                }
+            },
+        }
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            Self::Text(b) => ArrayBuilder::finish(b),
+            Self::Integer(b) => ArrayBuilder::finish(b),
+            Self::Float(b) => ArrayBuilder::finish(b),
+            Self::Boolean(b) => ArrayBuilder::finish(b),
+            Self::Date(b) => ArrayBuilder::finish(b),
+            Self::Timestamp(b) => ArrayBuilder::finish(b),
+            Self::TextList(b) => ArrayBuilder::finish(b),
+        }
+    }
+}
+
+/// Reads a whole number from a JSON number or numeric string
+fn integer(value: &Value) -> Option<i64> {
+    if let Some(i) = value.as_i64() {
+        return Some(i);
+    }
+    number(value)
+        .filter(|f| f.fract() == 0.0 && f.abs() < 9.0e15)
+        .map(|f| f as i64)
+}
+
+fn boolean(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "y" => Some(true),
+            "false" | "no" | "n" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Days since the Unix epoch
+fn date(value: &Value) -> Option<i32> {
+    let date = parse_timestamp(value.as_str()?)?.date_naive();
+    let days = date.signed_duration_since(NaiveDate::from_ymd_opt(1970, 1, 1)?);
+    i32::try_from(days.num_days()).ok()
+}
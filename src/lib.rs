@@ -32,8 +32,25 @@
 //! - Type-safe API with strongly-typed requests and responses
 //! - Async/await support using Tokio
 //! - Complete endpoint coverage for all 6 major Repliers API endpoints
-//! - Custom error types with detailed error information
-//! - Environment-based configuration for API keys
+//! - Structured error types carrying HTTP status, request context and the decoded error body
+//! - Automatic retries with exponential backoff for transient failures
+//! - Optional client-side rate limiting shared across cloned clients
+//! - Pluggable transport with record/replay cassettes for offline testing
+//! - `RepliersApi` trait over every endpoint, with an in-memory implementation for tests
+//! - Auto-paginating async streams over search results
+//! - Streaming CSV and GeoJSON export with configurable column mapping
+//! - Gzip-capable NDJSON export and import with resumable appends
+//! - Incremental synchronization driven by update timestamps and the deleted-listings feed
+//! - Snapshot diffing that reports new, removed, repriced and re-statused listings
+//! - Saved searches that alert on new and changed matches
+//! - Watchlists that poll specific listings and report their changes
+//! - Embedded SQLite listing store for offline queries (`store` feature)
+//! - Signed webhook receiver for pushed listing events (`webhooks` feature)
+//! - In-process fake API server with fault injection for integration tests (`testing` feature)
+//! - Typed Parquet export for analytics tools (`parquet` feature)
+//! - RESO Data Dictionary mapping of listings with a report of unmapped fields
+//! - `repliers` command-line tool for searches and exports (`cli` feature)
+//! - Layered configuration with named profiles, from a TOML file, the environment and overrides
 //!
 //! # Examples
 //!
@@ -53,15 +70,37 @@
 //! ```
 
 // Re-export main types
-pub use client::RepliersClient;
-pub use error::RepliersError;
+pub use api::{InMemoryApi, RepliersApi};
+pub use client::{RepliersClient, RepliersClientBuilder};
+pub use error::{ApiErrorContext, ConfigError, RepliersError};
+pub use pagination::SearchStreamOptions;
+pub use rate_limit::{RateLimit, RateLimitBudget, RateLimiter};
+pub use retry::RetryPolicy;
 
 // Module declarations
+pub mod api;
 pub mod client;
 pub mod config;
+pub mod diff;
 pub mod endpoints;
 pub mod error;
+pub mod export;
 pub mod models;
+pub mod pagination;
+pub mod rate_limit;
+pub mod reso;
+pub mod retry;
+pub mod saved_search;
+mod storage;
+#[cfg(feature = "store")]
+pub mod store;
+pub mod sync;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod transport;
+pub mod watchlist;
+#[cfg(feature = "webhooks")]
+pub mod webhooks;
 
 // Re-export commonly used types
 pub use models::*;
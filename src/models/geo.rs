@@ -0,0 +1,358 @@
+//! Geospatial search filters
+//!
+//! Map-based searches are expressed either as a point and radius (the `lat`, `long` and
+//! `radius` parameters) or as one or more polygons (the `map` parameter). Polygons are
+//! serialized using GeoJSON coordinate order, `[longitude, latitude]`.
+
+use crate::RepliersError;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// Mean Earth radius used for distance calculations
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+/// A geographic point in decimal degrees
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoPoint {
+    /// Latitude
+    pub latitude: f64,
+
+    /// Longitude
+    pub longitude: f64,
+}
+
+impl GeoPoint {
+    /// Creates a point from a latitude and longitude
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Great-circle distance to another point in kilometers
+    pub fn distance_km(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let dlat = lat2 - lat1;
+        let dlng = (other.longitude - self.longitude).to_radians();
+
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlng / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
+
+/// A rectangular area bounded by its south-west and north-east corners
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoundingBox {
+    /// South-west corner (minimum latitude and longitude)
+    pub south_west: GeoPoint,
+
+    /// North-east corner (maximum latitude and longitude)
+    pub north_east: GeoPoint,
+}
+
+impl BoundingBox {
+    /// Creates a bounding box from its corners
+    pub fn new(south_west: GeoPoint, north_east: GeoPoint) -> Self {
+        Self {
+            south_west,
+            north_east,
+        }
+    }
+
+    /// Converts the box into an equivalent polygon
+    pub fn to_polygon(&self) -> Polygon {
+        let (sw, ne) = (self.south_west, self.north_east);
+        Polygon::new(vec![
+            sw,
+            GeoPoint::new(sw.latitude, ne.longitude),
+            ne,
+            GeoPoint::new(ne.latitude, sw.longitude),
+        ])
+    }
+
+    /// Returns true if the point lies within the box
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        point.latitude >= self.south_west.latitude
+            && point.latitude <= self.north_east.latitude
+            && point.longitude >= self.south_west.longitude
+            && point.longitude <= self.north_east.longitude
+    }
+}
+
+/// A polygon with an exterior ring and optional holes
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Polygon {
+    /// Outer boundary
+    pub exterior: Vec<GeoPoint>,
+
+    /// Areas excluded from the polygon
+    pub holes: Vec<Vec<GeoPoint>>,
+}
+
+impl Polygon {
+    /// Creates a polygon from its exterior ring
+    ///
+    /// The ring does not need to be closed; it is closed on serialization.
+    pub fn new(exterior: Vec<GeoPoint>) -> Self {
+        Self {
+            exterior,
+            holes: Vec::new(),
+        }
+    }
+
+    /// Adds a hole to the polygon
+    pub fn with_hole(mut self, hole: Vec<GeoPoint>) -> Self {
+        self.holes.push(hole);
+        self
+    }
+
+    /// Returns true if the point lies inside the exterior ring and outside every hole
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        ring_contains(&self.exterior, point) && !self.holes.iter().any(|h| ring_contains(h, point))
+    }
+
+    fn to_coordinates(&self) -> Vec<Vec<[f64; 2]>> {
+        std::iter::once(&self.exterior)
+            .chain(self.holes.iter())
+            .map(|ring| close_ring(ring))
+            .collect()
+    }
+
+    fn from_coordinates(value: &Value) -> Result<Self, String> {
+        let mut rings = value
+            .as_array()
+            .ok_or("polygon coordinates must be an array of rings")?
+            .iter()
+            .map(ring_from_coordinates);
+
+        let exterior = rings.next().ok_or("polygon has no exterior ring")??;
+        let holes = rings.collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { exterior, holes })
+    }
+}
+
+/// One or more polygons serialized to the API's `map` parameter
+///
+/// A single polygon is sent as GeoJSON `Polygon` coordinates and several polygons as
+/// `MultiPolygon` coordinates. A filter without polygons places no restriction and is
+/// left out of search requests.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MapFilter {
+    /// Polygons to search within
+    pub polygons: Vec<Polygon>,
+}
+
+impl MapFilter {
+    /// Creates a filter for a single polygon
+    pub fn polygon(polygon: Polygon) -> Self {
+        Self {
+            polygons: vec![polygon],
+        }
+    }
+
+    /// Builds a filter from a GeoJSON geometry, feature or feature collection
+    ///
+    /// Accepts `Polygon` and `MultiPolygon` geometries; features with other geometry
+    /// types are rejected.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RepliersError::InvalidRequest` if the GeoJSON is not a polygon shape or
+    /// contains no polygons.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use repliers_beta::models::geo::MapFilter;
+    ///
+    /// let geojson = serde_json::json!({
+    ///     "type": "Polygon",
+    ///     "coordinates": [[[-79.40, 43.64], [-79.37, 43.64], [-79.37, 43.66], [-79.40, 43.66]]]
+    /// });
+    ///
+    /// let filter = MapFilter::from_geojson(&geojson).unwrap();
+    /// assert_eq!(filter.polygons.len(), 1);
+    /// ```
+    pub fn from_geojson(geojson: &Value) -> Result<Self, RepliersError> {
+        let filter = Self::from_geojson_value(geojson)?;
+        if filter.is_empty() {
+            return Err(RepliersError::InvalidRequest(
+                "GeoJSON: contains no polygons".to_string(),
+            ));
+        }
+        Ok(filter)
+    }
+
+    fn from_geojson_value(geojson: &Value) -> Result<Self, RepliersError> {
+        let invalid = |msg: String| RepliersError::InvalidRequest(format!("GeoJSON: {}", msg));
+
+        match geojson.get("type").and_then(Value::as_str) {
+            Some("Polygon") => {
+                let coords = geojson.get("coordinates").unwrap_or(&Value::Null);
+                Ok(Self::polygon(
+                    Polygon::from_coordinates(coords).map_err(invalid)?,
+                ))
+            }
+            Some("MultiPolygon") => {
+                let polygons = geojson
+                    .get("coordinates")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| invalid("MultiPolygon coordinates must be an array".into()))?
+                    .iter()
+                    .map(Polygon::from_coordinates)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(invalid)?;
+                Ok(Self { polygons })
+            }
+            Some("Feature") => {
+                Self::from_geojson_value(geojson.get("geometry").unwrap_or(&Value::Null))
+            }
+            Some("FeatureCollection") => {
+                let mut polygons = Vec::new();
+                for feature in geojson
+                    .get("features")
+                    .and_then(Value::as_array)
+                    .ok_or_else(|| invalid("FeatureCollection has no features".into()))?
+                {
+                    polygons.extend(Self::from_geojson_value(feature)?.polygons);
+                }
+                Ok(Self { polygons })
+            }
+            Some(other) => Err(invalid(format!("unsupported geometry type '{}'", other))),
+            None => Err(invalid("missing 'type'".into())),
+        }
+    }
+
+    /// Returns true if the filter has no polygons
+    pub fn is_empty(&self) -> bool {
+        self.polygons.is_empty()
+    }
+
+    /// Returns true if the point lies within any of the polygons
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        self.polygons.iter().any(|p| p.contains(point))
+    }
+}
+
+impl From<Polygon> for MapFilter {
+    fn from(polygon: Polygon) -> Self {
+        Self::polygon(polygon)
+    }
+}
+
+impl From<BoundingBox> for MapFilter {
+    fn from(bbox: BoundingBox) -> Self {
+        Self::polygon(bbox.to_polygon())
+    }
+}
+
+impl Serialize for MapFilter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.polygons.as_slice() {
+            [single] => single.to_coordinates().serialize(serializer),
+            many => many
+                .iter()
+                .map(Polygon::to_coordinates)
+                .collect::<Vec<_>>()
+                .serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MapFilter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = match Value::deserialize(deserializer)? {
+            // Query-string style: the coordinates encoded as a JSON string
+            Value::String(s) => serde_json::from_str(&s).map_err(D::Error::custom)?,
+            other => other,
+        };
+
+        // Polygon coordinates nest three levels deep before reaching a number,
+        // MultiPolygon coordinates four
+        let is_multi = value
+            .get(0)
+            .and_then(|v| v.get(0))
+            .and_then(|v| v.get(0))
+            .is_some_and(Value::is_array);
+
+        let polygons = if is_multi {
+            value
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(Polygon::from_coordinates)
+                .collect::<Result<Vec<_>, _>>()
+        } else {
+            Polygon::from_coordinates(&value).map(|p| vec![p])
+        };
+
+        polygons
+            .map(|polygons| Self { polygons })
+            .map_err(D::Error::custom)
+    }
+}
+
+/// A typed geospatial filter for a listing search
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoFilter {
+    /// Listings within `radius_km` of a point
+    Radius {
+        /// Center of the search circle
+        center: GeoPoint,
+        /// Radius in kilometers
+        radius_km: f64,
+    },
+
+    /// Listings within a rectangular area
+    BoundingBox(BoundingBox),
+
+    /// Listings within one or more polygons
+    Map(MapFilter),
+}
+
+fn close_ring(ring: &[GeoPoint]) -> Vec<[f64; 2]> {
+    let mut coords: Vec<[f64; 2]> = ring.iter().map(|p| [p.longitude, p.latitude]).collect();
+    if let (Some(first), Some(last)) = (coords.first().copied(), coords.last()) {
+        if first != *last {
+            coords.push(first);
+        }
+    }
+    coords
+}
+
+fn ring_from_coordinates(value: &Value) -> Result<Vec<GeoPoint>, String> {
+    value
+        .as_array()
+        .ok_or("ring must be an array of positions")?
+        .iter()
+        .map(|position| {
+            let lng = position.get(0).and_then(Value::as_f64);
+            let lat = position.get(1).and_then(Value::as_f64);
+            match (lat, lng) {
+                (Some(lat), Some(lng)) => Ok(GeoPoint::new(lat, lng)),
+                _ => Err(format!("invalid position {}", position)),
+            }
+        })
+        .collect()
+}
+
+/// Even-odd ray casting test
+fn ring_contains(ring: &[GeoPoint], point: &GeoPoint) -> bool {
+    let mut inside = false;
+    let mut j = ring.len().wrapping_sub(1);
+    for i in 0..ring.len() {
+        let (a, b) = (ring[i], ring[j]);
+        if (a.latitude > point.latitude) != (b.latitude > point.latitude)
+            && point.longitude
+                < (b.longitude - a.longitude) * (point.latitude - a.latitude)
+                    / (b.latitude - a.latitude)
+                    + a.longitude
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
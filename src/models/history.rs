@@ -1,5 +1,6 @@
 //! Address history and deleted listing models
 
+use super::listing::Address;
 use serde::{Deserialize, Serialize};
 
 /// Query parameters for address history
@@ -34,7 +35,7 @@ pub struct AddressHistoryQuery {
 }
 
 /// Response containing address history
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddressHistoryResponse {
     /// Historical listing entries for the address
     pub history: Vec<HistoryEntry>,
@@ -44,7 +45,7 @@ pub struct AddressHistoryResponse {
 }
 
 /// A single entry in the address history
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
     /// MLS number for this listing
     pub mls_number: String,
@@ -69,7 +70,6 @@ pub struct HistoryEntry {
 
     /// Number of bedrooms
     pub bedrooms: Option<u32>,
-
     // Note: Additional fields available from the API that could be added:
     // - bathrooms: Number of bathrooms
     // - square_footage: Property size
@@ -110,7 +110,7 @@ pub struct DeletedListingsQuery {
 }
 
 /// Response containing deleted listings
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeletedListingsResponse {
     /// Deleted listings
     pub listings: Vec<DeletedListing>,
@@ -131,60 +131,12 @@ pub struct DeletedListingsResponse {
 }
 
 /// Address structure for deleted listings
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DeletedListingAddress {
-    /// Area/county
-    pub area: Option<String>,
-
-    /// City
-    pub city: Option<String>,
-
-    /// Country
-    pub country: Option<String>,
-
-    /// District
-    pub district: Option<String>,
-
-    /// Major intersection
-    #[serde(rename = "majorIntersection")]
-    pub major_intersection: Option<String>,
-
-    /// Neighborhood
-    pub neighborhood: Option<String>,
-
-    /// Street direction (N, S, E, W, etc.)
-    #[serde(rename = "streetDirection")]
-    pub street_direction: Option<String>,
-
-    /// Street name
-    #[serde(rename = "streetName")]
-    pub street_name: Option<String>,
-
-    /// Street number
-    #[serde(rename = "streetNumber")]
-    pub street_number: Option<String>,
-
-    /// Street suffix (Road, Drive, etc.)
-    #[serde(rename = "streetSuffix")]
-    pub street_suffix: Option<String>,
-
-    /// Unit number
-    #[serde(rename = "unitNumber")]
-    pub unit_number: Option<String>,
-
-    /// ZIP code
-    pub zip: Option<String>,
-
-    /// State
-    pub state: Option<String>,
-
-    /// Community code
-    #[serde(rename = "communityCode")]
-    pub community_code: Option<String>,
-}
+///
+/// Deleted listings share the address shape of regular listings.
+pub type DeletedListingAddress = Address;
 
 /// Timestamp information for deleted listings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DeletedListingTimestamps {
     /// When the listing was last updated before deletion
     #[serde(rename = "listingUpdated")]
@@ -192,7 +144,7 @@ pub struct DeletedListingTimestamps {
 }
 
 /// A deleted listing entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DeletedListing {
     /// Board ID
     #[serde(rename = "boardId")]
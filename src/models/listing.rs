@@ -1,6 +1,9 @@
 //! Listing data models
 
+use super::serde_helpers::{opt_f64, opt_string, opt_string_vec, opt_u32, opt_vec_or_map};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Status of a listing
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -15,26 +18,1094 @@ pub enum ListingStatus {
 
 /// A property listing from the MLS
 ///
-/// Note: This is a minimal struct containing only the MLS number. In practice, the Repliers API
-/// returns comprehensive listing data that varies by MLS board. Additional fields can include:
-/// - address: Full property address structure
-/// - city, state, postal_code: Location details
-/// - list_price, sold_price: Pricing information
-/// - bedrooms, bathrooms: Property details
-/// - square_footage: Living area size
-/// - property_type, property_sub_type: Type categorization
-/// - status: Active, Sold, Expired, etc.
-/// - list_date, sold_date: Important dates
-/// - description, remarks: Property descriptions
-/// - images: Photo URLs and metadata
-/// - agent, office: Listing agent information
-/// - features: Property features (pool, garage, etc.)
-///
-/// For flexibility, most endpoints return `serde_json::Value` to handle varying field availability.
-/// A fully typed struct would need extensive `Option<T>` fields to handle all MLS board variations.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Field availability varies by MLS board, so every field other than the MLS number is
+/// optional. Numeric fields accept both JSON numbers and numeric strings. Any fields not
+/// modelled here are preserved in [`extra`](Self::extra), so a listing survives a
+/// deserialize/serialize round trip without losing board-specific data.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Listing {
-    /// MLS number (unique identifier)
-    #[serde(rename = "mlsNumber")]
+    /// MLS number (unique identifier within a board)
     pub mls_number: String,
+
+    /// Board ID the listing belongs to
+    #[serde(
+        default,
+        deserialize_with = "opt_u32",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub board_id: Option<u32>,
+
+    /// Resource name (e.g. "Property:1234")
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub resource: Option<String>,
+
+    /// Availability status ("A" for available, "U" for unavailable)
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub status: Option<String>,
+
+    /// Most recent status change code (e.g. "New", "Sld", "Pc", "Sc")
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_status: Option<String>,
+
+    /// Property class (e.g. "ResidentialProperty", "CondoProperty")
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub class: Option<String>,
+
+    /// Transaction type ("Sale" or "Lease")
+    #[serde(
+        rename = "type",
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub listing_type: Option<String>,
+
+    /// Current asking price
+    #[serde(
+        default,
+        deserialize_with = "opt_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub list_price: Option<f64>,
+
+    /// Original asking price
+    #[serde(
+        default,
+        deserialize_with = "opt_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub original_price: Option<f64>,
+
+    /// Date the property was listed
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub list_date: Option<String>,
+
+    /// Final sale price (if sold)
+    #[serde(
+        default,
+        deserialize_with = "opt_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sold_price: Option<f64>,
+
+    /// Date the property sold
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sold_date: Option<String>,
+
+    /// Number of days the listing has been on the market
+    #[serde(
+        default,
+        deserialize_with = "opt_u32",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub days_on_market: Option<u32>,
+
+    /// Occupancy (e.g. "Vacant", "Owner")
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub occupancy: Option<String>,
+
+    /// When the listing was last updated
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub updated_on: Option<String>,
+
+    /// Property address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+
+    /// Map coordinates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map: Option<MapCoordinates>,
+
+    /// Property details (bedrooms, bathrooms, size, style, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<ListingDetails>,
+
+    /// Image file names, relative to the Repliers CDN
+    #[serde(
+        default,
+        deserialize_with = "opt_string_vec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub images: Option<Vec<String>>,
+
+    /// Number of photos available
+    #[serde(
+        default,
+        deserialize_with = "opt_u32",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub photo_count: Option<u32>,
+
+    /// Listing brokerage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub office: Option<Office>,
+
+    /// Listing agents
+    #[serde(
+        default,
+        deserialize_with = "opt_vec_or_map",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub agents: Option<Vec<Agent>>,
+
+    /// Room-by-room breakdown
+    #[serde(
+        default,
+        deserialize_with = "opt_vec_or_map",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub rooms: Option<Vec<Room>>,
+
+    /// Condominium information (condo listings only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condominium: Option<Condominium>,
+
+    /// Property taxes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub taxes: Option<Taxes>,
+
+    /// Lifecycle timestamps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<ListingTimestamps>,
+
+    /// Board-specific fields not modelled above
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Listing {
+    /// Returns the `(latitude, longitude)` pair if both coordinates are present
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        let map = self.map.as_ref()?;
+        Some((map.latitude?, map.longitude?))
+    }
+
+    /// Returns the number of bedrooms, if known
+    pub fn bedrooms(&self) -> Option<u32> {
+        self.details.as_ref()?.num_bedrooms
+    }
+
+    /// Returns the number of bathrooms, if known
+    pub fn bathrooms(&self) -> Option<u32> {
+        self.details.as_ref()?.num_bathrooms
+    }
+
+    /// Returns the city, if known
+    pub fn city(&self) -> Option<&str> {
+        self.address.as_ref()?.city.as_deref()
+    }
+
+    /// Returns a single-line street address such as "123 Main St, Unit 4"
+    pub fn street_address(&self) -> Option<String> {
+        self.address.as_ref()?.street_line()
+    }
+
+    /// Returns when the listing was last modified
+    ///
+    /// Prefers `timestamps.listingUpdated` and falls back to `updatedOn`.
+    pub fn last_updated(&self) -> Option<DateTime<Utc>> {
+        self.timestamps
+            .as_ref()
+            .and_then(|t| t.listing_updated.as_deref())
+            .and_then(parse_timestamp)
+            .or_else(|| self.updated_on.as_deref().and_then(parse_timestamp))
+    }
+}
+
+/// Expanded view of a single listing returned by `GET /listings/{mlsNumber}`
+///
+/// Contains every field of a [`Listing`] (accessible directly through `Deref`) plus
+/// comparable listings and the property's MLS history.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListingDetail {
+    /// The listing itself
+    #[serde(flatten)]
+    pub listing: Listing,
+
+    /// Comparable listings selected by the API
+    #[serde(
+        default,
+        deserialize_with = "opt_vec_or_map",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub comparables: Option<Vec<Listing>>,
+
+    /// Previous MLS listings of the same property
+    #[serde(
+        default,
+        deserialize_with = "opt_vec_or_map",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub history: Option<Vec<ListingHistoryEntry>>,
+}
+
+impl std::ops::Deref for ListingDetail {
+    type Target = Listing;
+
+    fn deref(&self) -> &Listing {
+        &self.listing
+    }
+}
+
+impl ListingDetail {
+    /// Returns the comparable listings, or an empty slice if none were returned
+    pub fn comparables(&self) -> &[Listing] {
+        self.comparables.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the history entries, or an empty slice if none were returned
+    pub fn history(&self) -> &[ListingHistoryEntry] {
+        self.history.as_deref().unwrap_or_default()
+    }
+}
+
+/// A previous MLS listing of the same property, embedded in a [`ListingDetail`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingHistoryEntry {
+    /// MLS number of the historical listing
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mls_number: Option<String>,
+
+    /// Transaction type ("Sale" or "Lease")
+    #[serde(
+        rename = "type",
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub listing_type: Option<String>,
+
+    /// Asking price
+    #[serde(
+        default,
+        deserialize_with = "opt_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub list_price: Option<f64>,
+
+    /// Date listed
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub list_date: Option<String>,
+
+    /// Final status code (e.g. "Sld", "Exp", "Ter")
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_status: Option<String>,
+
+    /// Sale price (if sold)
+    #[serde(
+        default,
+        deserialize_with = "opt_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sold_price: Option<f64>,
+
+    /// Sale date (if sold)
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sold_date: Option<String>,
+
+    /// Image file names
+    #[serde(
+        default,
+        deserialize_with = "opt_string_vec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub images: Option<Vec<String>>,
+
+    /// Listing brokerage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub office: Option<Office>,
+
+    /// Lifecycle timestamps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<ListingTimestamps>,
+
+    /// Board-specific fields not modelled above
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Parses an API timestamp, accepting RFC 3339 date-times and plain `YYYY-MM-DD` dates
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Some(dt.and_utc());
+    }
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+}
+
+/// Structured property address
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Address {
+    /// Area/county
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub area: Option<String>,
+
+    /// City
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub city: Option<String>,
+
+    /// Country
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub country: Option<String>,
+
+    /// District
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub district: Option<String>,
+
+    /// Major intersection
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub major_intersection: Option<String>,
+
+    /// Neighborhood
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub neighborhood: Option<String>,
+
+    /// Street direction prefix (e.g. "N" in "N Main St")
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub street_direction_prefix: Option<String>,
+
+    /// Street direction (N, S, E, W, etc.)
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub street_direction: Option<String>,
+
+    /// Street name
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub street_name: Option<String>,
+
+    /// Street number
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub street_number: Option<String>,
+
+    /// Street suffix (Road, Drive, etc.)
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub street_suffix: Option<String>,
+
+    /// Unit number
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub unit_number: Option<String>,
+
+    /// ZIP/postal code
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub zip: Option<String>,
+
+    /// State/province
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub state: Option<String>,
+
+    /// Community code
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub community_code: Option<String>,
+}
+
+impl Address {
+    /// Joins the street components into a single line (e.g. "123 N Main St, Unit 4")
+    ///
+    /// Returns `None` if neither a street number nor a street name is present.
+    pub fn street_line(&self) -> Option<String> {
+        let parts: Vec<&str> = [
+            &self.street_number,
+            &self.street_direction_prefix,
+            &self.street_name,
+            &self.street_suffix,
+            &self.street_direction,
+        ]
+        .iter()
+        .filter_map(|p| p.as_deref())
+        .filter(|p| !p.is_empty())
+        .collect();
+
+        if self.street_number.is_none() && self.street_name.is_none() {
+            return None;
+        }
+
+        let mut line = parts.join(" ");
+        if let Some(unit) = self.unit_number.as_deref().filter(|u| !u.is_empty()) {
+            line.push_str(", Unit ");
+            line.push_str(unit);
+        }
+        Some(line)
+    }
+}
+
+/// Geographic coordinates of a listing
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapCoordinates {
+    /// Latitude in decimal degrees
+    #[serde(
+        default,
+        deserialize_with = "opt_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub latitude: Option<f64>,
+
+    /// Longitude in decimal degrees
+    #[serde(
+        default,
+        deserialize_with = "opt_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub longitude: Option<f64>,
+
+    /// WKT point (e.g. "POINT (-79.38 43.65)")
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub point: Option<String>,
+}
+
+/// Physical details of a property
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingDetails {
+    /// Number of bedrooms
+    #[serde(
+        default,
+        deserialize_with = "opt_u32",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub num_bedrooms: Option<u32>,
+
+    /// Additional bedrooms (e.g. basement bedrooms)
+    #[serde(
+        default,
+        deserialize_with = "opt_u32",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub num_bedrooms_plus: Option<u32>,
+
+    /// Number of bathrooms
+    #[serde(
+        default,
+        deserialize_with = "opt_u32",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub num_bathrooms: Option<u32>,
+
+    /// Additional bathrooms (e.g. half baths)
+    #[serde(
+        default,
+        deserialize_with = "opt_u32",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub num_bathrooms_plus: Option<u32>,
+
+    /// Number of rooms
+    #[serde(
+        default,
+        deserialize_with = "opt_u32",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub num_rooms: Option<u32>,
+
+    /// Living area in square feet; often a range such as "1500-2000"
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sqft: Option<String>,
+
+    /// Architectural style (e.g. "2-Storey", "Apartment")
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub style: Option<String>,
+
+    /// Property type (e.g. "Detached", "Condo Apt")
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub property_type: Option<String>,
+
+    /// Year built; some boards report an age range such as "31-50"
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub year_built: Option<String>,
+
+    /// Public remarks
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub description: Option<String>,
+
+    /// Additional remarks (inclusions, exclusions, ...)
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub extras: Option<String>,
+
+    /// Garage type
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub garage: Option<String>,
+
+    /// Number of garage spaces
+    #[serde(
+        default,
+        deserialize_with = "opt_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub num_garage_spaces: Option<f64>,
+
+    /// Number of parking spaces
+    #[serde(
+        default,
+        deserialize_with = "opt_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub num_parking_spaces: Option<f64>,
+
+    /// Primary basement description
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub basement1: Option<String>,
+
+    /// Secondary basement description
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub basement2: Option<String>,
+
+    /// Heating type
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub heating: Option<String>,
+
+    /// Air conditioning type
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub air_conditioning: Option<String>,
+
+    /// Swimming pool type
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub swimming_pool: Option<String>,
+
+    /// Virtual tour link
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub virtual_tour_url: Option<String>,
+
+    /// Board-specific detail fields not modelled above
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Listing brokerage
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Office {
+    /// Brokerage name
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub brokerage_name: Option<String>,
+
+    /// Board-specific office fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Listing agent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Agent {
+    /// Repliers agent ID
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub agent_id: Option<String>,
+
+    /// Agent ID assigned by the board
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub board_agent_id: Option<String>,
+
+    /// Office ID
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub office_id: Option<String>,
+
+    /// Full name
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub name: Option<String>,
+
+    /// Position/title
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub position: Option<String>,
+
+    /// Email address
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub email: Option<String>,
+
+    /// Phone numbers
+    #[serde(
+        default,
+        deserialize_with = "opt_string_vec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub phones: Option<Vec<String>>,
+
+    /// Website
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub website: Option<String>,
+
+    /// Board-specific agent fields (photo, brokerage, social links, ...)
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// A single room
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Room {
+    /// Room name (e.g. "Kitchen")
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub description: Option<String>,
+
+    /// Floor level
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub level: Option<String>,
+
+    /// Length (units vary by board)
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub length: Option<String>,
+
+    /// Width (units vary by board)
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub width: Option<String>,
+
+    /// Primary features
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub features: Option<String>,
+
+    /// Secondary features
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub features2: Option<String>,
+
+    /// Tertiary features
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub features3: Option<String>,
+}
+
+/// Condominium information
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Condominium {
+    /// Building amenities (the API spells this field "ammenities")
+    #[serde(
+        rename = "ammenities",
+        default,
+        deserialize_with = "opt_string_vec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub amenities: Option<Vec<String>>,
+
+    /// Condo corporation name/abbreviation
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub condo_corp: Option<String>,
+
+    /// Condo corporation number
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub condo_corp_num: Option<String>,
+
+    /// Unit exposure (e.g. "S", "NE")
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub exposure: Option<String>,
+
+    /// Locker type
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub locker: Option<String>,
+
+    /// Parking type
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub parking_type: Option<String>,
+
+    /// Pet policy
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub pets: Option<String>,
+
+    /// Property management company
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub property_mgr: Option<String>,
+
+    /// Floor/storey of the unit
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub stories: Option<String>,
+
+    /// Monthly fees and what they include
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fees: Option<CondoFees>,
+
+    /// Board-specific condominium fields
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Condominium fees
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CondoFees {
+    /// Monthly maintenance fee
+    #[serde(
+        default,
+        deserialize_with = "opt_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub maintenance: Option<f64>,
+
+    /// Inclusion flags (e.g. "heatIncl": "Y")
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Property taxes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Taxes {
+    /// Annual tax amount
+    #[serde(
+        default,
+        deserialize_with = "opt_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub annual_amount: Option<f64>,
+
+    /// Assessment year
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub assessment_year: Option<String>,
+}
+
+/// Lifecycle timestamps of a listing
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingTimestamps {
+    /// When the IDX feed last updated the listing
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub idx_updated: Option<String>,
+
+    /// When the listing was last updated
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub listing_updated: Option<String>,
+
+    /// When the photos were last updated
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub photos_updated: Option<String>,
+
+    /// When the listing was entered into the MLS
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub listing_entry_date: Option<String>,
+
+    /// When a conditional sale expires
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub conditional_expiry_date: Option<String>,
+
+    /// When the listing was terminated
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub terminated_date: Option<String>,
+
+    /// When the listing was suspended
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub suspended_date: Option<String>,
+
+    /// When the sale closed
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub closed_date: Option<String>,
+
+    /// When the listing became unavailable
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub unavailable_date: Option<String>,
+
+    /// When the listing agreement expires
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expiry_date: Option<String>,
+
+    /// Possession date
+    #[serde(
+        default,
+        deserialize_with = "opt_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub possession_date: Option<String>,
 }
@@ -1,11 +1,14 @@
 //! Data models for Repliers API requests and responses
 
+pub mod geo;
 pub mod history;
 pub mod listing;
 pub mod nlp;
 pub mod search;
+mod serde_helpers;
 
 // Re-export commonly used types
+pub use geo::*;
 pub use history::*;
 pub use listing::*;
 pub use nlp::*;
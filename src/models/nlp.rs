@@ -15,7 +15,7 @@ pub struct NLPSearchRequest {
 }
 
 /// Response from AI search containing structured parameters
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NLPSearchResponse {
     /// Constructed API URL for the search
     pub url: String,
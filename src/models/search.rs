@@ -1,6 +1,10 @@
 //! Search request and response models
 
+use super::geo::{BoundingBox, GeoFilter, GeoPoint, MapFilter, Polygon};
+use super::listing::{parse_timestamp, Listing};
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 
 /// Request parameters for listing search
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -29,6 +33,154 @@ pub struct ListingSearchRequest {
     #[serde(skip_serializing_if = "Option::is_none", rename = "propertyType")]
     pub property_type: Option<Vec<String>>,
 
+    /// Minimum number of bedrooms
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minBeds")]
+    pub min_bedrooms: Option<u32>,
+
+    /// Maximum number of bedrooms
+    #[serde(skip_serializing_if = "Option::is_none", rename = "maxBeds")]
+    pub max_bedrooms: Option<u32>,
+
+    /// Minimum number of bathrooms
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minBaths")]
+    pub min_bathrooms: Option<u32>,
+
+    /// Maximum number of bathrooms
+    #[serde(skip_serializing_if = "Option::is_none", rename = "maxBaths")]
+    pub max_bathrooms: Option<u32>,
+
+    /// Minimum living area in square feet
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minSqft")]
+    pub min_sqft: Option<u32>,
+
+    /// Maximum living area in square feet
+    #[serde(skip_serializing_if = "Option::is_none", rename = "maxSqft")]
+    pub max_sqft: Option<u32>,
+
+    /// Geographic area (region/county)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub area: Option<String>,
+
+    /// Neighborhood name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub neighborhood: Option<String>,
+
+    /// Property class (e.g. ["ResidentialProperty", "CondoProperty"])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class: Option<Vec<String>>,
+
+    /// Transaction type (e.g. ["Sale"] or ["Lease"])
+    #[serde(skip_serializing_if = "Option::is_none", rename = "type")]
+    pub listing_type: Option<Vec<String>>,
+
+    /// Architectural style / property sub-type (e.g. ["2-Storey", "Bungalow"])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<Vec<String>>,
+
+    /// Last status code (e.g. ["Sld", "Sc", "Pc"])
+    #[serde(skip_serializing_if = "Option::is_none", rename = "lastStatus")]
+    pub last_status: Option<Vec<String>>,
+
+    /// Minimum sold price
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minSoldPrice")]
+    pub min_sold_price: Option<f64>,
+
+    /// Maximum sold price
+    #[serde(skip_serializing_if = "Option::is_none", rename = "maxSoldPrice")]
+    pub max_sold_price: Option<f64>,
+
+    /// Earliest listing date (YYYY-MM-DD)
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minListDate")]
+    pub min_list_date: Option<String>,
+
+    /// Latest listing date (YYYY-MM-DD)
+    #[serde(skip_serializing_if = "Option::is_none", rename = "maxListDate")]
+    pub max_list_date: Option<String>,
+
+    /// Earliest sold date (YYYY-MM-DD)
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minSoldDate")]
+    pub min_sold_date: Option<String>,
+
+    /// Latest sold date (YYYY-MM-DD)
+    #[serde(skip_serializing_if = "Option::is_none", rename = "maxSoldDate")]
+    pub max_sold_date: Option<String>,
+
+    /// Earliest last-updated date (YYYY-MM-DD)
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minUpdatedOn")]
+    pub min_updated_on: Option<String>,
+
+    /// Latest last-updated date (YYYY-MM-DD)
+    #[serde(skip_serializing_if = "Option::is_none", rename = "maxUpdatedOn")]
+    pub max_updated_on: Option<String>,
+
+    /// Minimum number of days on market
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minDaysOnMarket")]
+    pub min_days_on_market: Option<u32>,
+
+    /// Maximum number of days on market
+    #[serde(skip_serializing_if = "Option::is_none", rename = "maxDaysOnMarket")]
+    pub max_days_on_market: Option<u32>,
+
+    /// Minimum number of garage spaces
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minGarageSpaces")]
+    pub min_garage_spaces: Option<u32>,
+
+    /// Minimum number of parking spaces
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minParkingSpaces")]
+    pub min_parking_spaces: Option<u32>,
+
+    /// Basement types (e.g. ["Finished", "Walk-Out"])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub basement: Option<Vec<String>>,
+
+    /// Free-text keyword search (e.g. "pool", "waterfront")
+    #[serde(skip_serializing_if = "Option::is_none", rename = "search")]
+    pub keywords: Option<String>,
+
+    /// Fields the keyword search is applied to (e.g. "details.description")
+    #[serde(skip_serializing_if = "Option::is_none", rename = "searchFields")]
+    pub search_fields: Option<String>,
+
+    /// Listing agent name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+
+    /// Listing brokerage name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brokerage: Option<String>,
+
+    /// Listing office ID
+    #[serde(skip_serializing_if = "Option::is_none", rename = "officeId")]
+    pub office_id: Option<String>,
+
+    /// Board ID for multi-MLS accounts
+    #[serde(skip_serializing_if = "Option::is_none", rename = "boardId")]
+    pub board_id: Option<String>,
+
+    /// Latitude of the center of a radius search
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f64>,
+
+    /// Longitude of the center of a radius search
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long: Option<f64>,
+
+    /// Radius in kilometers for a radius search
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f64>,
+
+    /// Polygons to search within; an empty filter is not sent
+    #[serde(skip_serializing_if = "is_unset_map")]
+    pub map: Option<MapFilter>,
+
+    /// Sort order (e.g. "listPriceAsc", "updatedOnDesc")
+    #[serde(skip_serializing_if = "Option::is_none", rename = "sortBy")]
+    pub sort_by: Option<String>,
+
+    /// Comma-separated list of fields to return (e.g. "mlsNumber,listPrice")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<String>,
+
     /// Page number for pagination
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page: Option<u32>,
@@ -36,31 +188,16 @@ pub struct ListingSearchRequest {
     /// Number of results per page
     #[serde(skip_serializing_if = "Option::is_none", rename = "resultsPerPage")]
     pub results_per_page: Option<u32>,
-
-    // Note: The Repliers API supports many additional search parameters that can be added:
-    // - bathrooms, min_bathrooms, max_bathrooms: Bathroom count filters
-    // - min_bedrooms, max_bedrooms: More granular bedroom filtering
-    // - min_sqft, max_sqft: Square footage range
-    // - area: Geographic area filter
-    // - neighborhood: Neighborhood-specific search
-    // - property_sub_type: More specific property categorization
-    // - listing_date, days_on_market: Time-based filters
-    // - features: Specific property features (pool, garage, etc.)
-    //
-    // These can be added as needed based on use case requirements.
-    // Refer to https://docs.repliers.io/reference/search-listings for complete list.
 }
 
 /// Response from listing search
-#[derive(Debug, Clone, Deserialize)]
-pub struct ListingSearchResponse {
+///
+/// Listings are typed as [`Listing`] by default. The raw search methods return
+/// [`RawListingSearchResponse`], which keeps each listing as untyped JSON.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListingSearchResponse<L = Listing> {
     /// Array of listing results
-    ///
-    /// Currently uses `serde_json::Value` for flexibility, as listing structures
-    /// can vary based on MLS board and available data. A fully typed `Listing`
-    /// struct could be implemented for stricter type safety, but would need to
-    /// handle optional fields for varying data availability.
-    pub listings: Vec<serde_json::Value>,
+    pub listings: Vec<L>,
 
     /// Current page number
     pub page: u32,
@@ -104,11 +241,17 @@ pub struct SimilarListingsRequest {
     pub sort_by: Option<String>,
 }
 
+/// Listing search response with listings left as untyped JSON
+pub type RawListingSearchResponse = ListingSearchResponse<serde_json::Value>;
+
 /// Response from similar listings search
-#[derive(Debug, Clone, Deserialize)]
-pub struct SimilarListingsResponse {
+///
+/// Listings are typed as [`Listing`] by default; see [`RawSimilarListingsResponse`]
+/// for the untyped variant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarListingsResponse<L = Listing> {
     /// Similar listings found
-    pub similar: Vec<serde_json::Value>,
+    pub similar: Vec<L>,
 
     /// Current page
     pub page: u32,
@@ -125,107 +268,694 @@ pub struct SimilarListingsResponse {
     pub count: u32,
 }
 
+/// Similar listings response with listings left as untyped JSON
+pub type RawSimilarListingsResponse = SimilarListingsResponse<serde_json::Value>;
+
 impl ListingSearchRequest {
     /// Creates a new builder for constructing a search request
     pub fn builder() -> ListingSearchRequestBuilder {
         ListingSearchRequestBuilder::default()
     }
+
+    /// Applies a geospatial filter, replacing any previous one
+    ///
+    /// Radius filters set `lat`, `long` and `radius`; bounding boxes and polygons set `map`.
+    pub fn set_geo_filter(&mut self, filter: GeoFilter) {
+        self.lat = None;
+        self.long = None;
+        self.radius = None;
+        self.map = None;
+
+        match filter {
+            GeoFilter::Radius { center, radius_km } => {
+                self.lat = Some(center.latitude);
+                self.long = Some(center.longitude);
+                self.radius = Some(radius_km);
+            }
+            GeoFilter::BoundingBox(bbox) => self.map = Some(bbox.into()),
+            GeoFilter::Map(map) => self.map = Some(map),
+        }
+    }
+
+    /// Returns the geospatial filter currently applied, if any
+    pub fn geo_filter(&self) -> Option<GeoFilter> {
+        if let Some(map) = self.map.as_ref().filter(|map| !map.is_empty()) {
+            return Some(GeoFilter::Map(map.clone()));
+        }
+        match (self.lat, self.long, self.radius) {
+            (Some(lat), Some(long), Some(radius_km)) => Some(GeoFilter::Radius {
+                center: GeoPoint::new(lat, long),
+                radius_km,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if `listing` satisfies every filter in this request
+    ///
+    /// This mirrors the API's filtering closely enough for offline use (local stores,
+    /// fakes and tests). String comparisons are case-insensitive, and a listing that
+    /// lacks the field a filter targets does not match. `keywords` are matched against
+    /// the description and extras, and `agent`/`brokerage` as substrings.
+    /// Pagination, sorting and `fields` are ignored.
+    pub fn matches(&self, listing: &Listing) -> bool {
+        let details = listing.details.as_ref();
+        let address = listing.address.as_ref();
+
+        if let Some(board_id) = self
+            .board_id
+            .as_deref()
+            .and_then(|b| b.trim().parse::<u32>().ok())
+        {
+            if listing.board_id != Some(board_id) {
+                return false;
+            }
+        }
+        if !matches_text(&self.city, listing.city())
+            || !matches_text(&self.area, address.and_then(|a| a.area.as_deref()))
+            || !matches_text(
+                &self.neighborhood,
+                address.and_then(|a| a.neighborhood.as_deref()),
+            )
+        {
+            return false;
+        }
+        if let Some(statuses) = self.status.as_ref().filter(|s| !s.is_empty()) {
+            if !statuses.iter().any(|s| matches_status(s, listing)) {
+                return false;
+            }
+        }
+        if !matches_any(&self.last_status, listing.last_status.as_deref())
+            || !matches_any(&self.class, listing.class.as_deref())
+            || !matches_any(&self.listing_type, listing.listing_type.as_deref())
+            || !matches_any(&self.style, details.and_then(|d| d.style.as_deref()))
+            || !matches_any(
+                &self.property_type,
+                details.and_then(|d| d.property_type.as_deref()),
+            )
+        {
+            return false;
+        }
+        if let Some(basements) = self.basement.as_ref().filter(|b| !b.is_empty()) {
+            let found = details
+                .map(|d| [d.basement1.as_deref(), d.basement2.as_deref()])
+                .unwrap_or_default();
+            if !found
+                .iter()
+                .flatten()
+                .any(|b| basements.iter().any(|w| w.eq_ignore_ascii_case(b)))
+            {
+                return false;
+            }
+        }
+
+        if !in_range(listing.list_price, self.min_price, self.max_price)
+            || !in_range(listing.sold_price, self.min_sold_price, self.max_sold_price)
+            || !in_range(listing.bedrooms(), self.min_bedrooms, self.max_bedrooms)
+            || !in_range(listing.bathrooms(), self.min_bathrooms, self.max_bathrooms)
+            || !in_range(
+                listing.days_on_market,
+                self.min_days_on_market,
+                self.max_days_on_market,
+            )
+        {
+            return false;
+        }
+        if self.bedrooms.is_some() && listing.bedrooms() != self.bedrooms {
+            return false;
+        }
+        let sqft = details.and_then(|d| d.sqft.as_deref()).and_then(parse_sqft);
+        if !in_range(
+            sqft,
+            self.min_sqft.map(f64::from),
+            self.max_sqft.map(f64::from),
+        ) {
+            return false;
+        }
+        let garage = details.and_then(|d| d.num_garage_spaces);
+        let parking = details.and_then(|d| d.num_parking_spaces);
+        if !in_range(garage, self.min_garage_spaces.map(f64::from), None)
+            || !in_range(parking, self.min_parking_spaces.map(f64::from), None)
+        {
+            return false;
+        }
+
+        if !in_date_range(
+            listing.list_date.as_deref(),
+            &self.min_list_date,
+            &self.max_list_date,
+        ) || !in_date_range(
+            listing.sold_date.as_deref(),
+            &self.min_sold_date,
+            &self.max_sold_date,
+        ) {
+            return false;
+        }
+        if self.min_updated_on.is_some() || self.max_updated_on.is_some() {
+            let updated = listing.last_updated().map(|dt| dt.date_naive());
+            if !in_range(
+                updated,
+                parse_date(&self.min_updated_on),
+                parse_date(&self.max_updated_on),
+            ) {
+                return false;
+            }
+        }
+
+        if let Some(keywords) = self
+            .keywords
+            .as_deref()
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+        {
+            let haystack = details
+                .map(|d| {
+                    [d.description.as_deref(), d.extras.as_deref()]
+                        .iter()
+                        .flatten()
+                        .map(|s| s.to_lowercase())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .unwrap_or_default();
+            if !keywords
+                .to_lowercase()
+                .split_whitespace()
+                .all(|word| haystack.contains(word))
+            {
+                return false;
+            }
+        }
+        if let Some(agent) = self.agent.as_deref() {
+            let agent = agent.to_lowercase();
+            let found = listing.agents.iter().flatten().any(|a| {
+                a.name
+                    .as_deref()
+                    .is_some_and(|n| n.to_lowercase().contains(&agent))
+            });
+            if !found {
+                return false;
+            }
+        }
+        if let Some(brokerage) = self.brokerage.as_deref() {
+            let found = listing
+                .office
+                .as_ref()
+                .and_then(|o| o.brokerage_name.as_deref())
+                .is_some_and(|n| n.to_lowercase().contains(&brokerage.to_lowercase()));
+            if !found {
+                return false;
+            }
+        }
+        if let Some(office_id) = self.office_id.as_deref() {
+            let found = listing
+                .agents
+                .iter()
+                .flatten()
+                .any(|a| a.office_id.as_deref() == Some(office_id));
+            if !found {
+                return false;
+            }
+        }
+
+        if let Some(filter) = self.geo_filter() {
+            let Some((lat, long)) = listing.coordinates() else {
+                return false;
+            };
+            let point = GeoPoint::new(lat, long);
+            let inside = match filter {
+                GeoFilter::Radius { center, radius_km } => center.distance_km(&point) <= radius_km,
+                GeoFilter::BoundingBox(bbox) => bbox.contains(&point),
+                GeoFilter::Map(map) => map.contains(&point),
+            };
+            if !inside {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Runs this search over an in-memory set of listings
+    ///
+    /// Filters with [`matches`](Self::matches), orders with
+    /// [`sort_listings`](Self::sort_listings) and returns the requested page, defaulting
+    /// to page 1 with 100 results per page.
+    pub fn search_in(&self, listings: impl IntoIterator<Item = Listing>) -> ListingSearchResponse {
+        let mut listings: Vec<Listing> = listings
+            .into_iter()
+            .filter(|listing| self.matches(listing))
+            .collect();
+        self.sort_listings(&mut listings);
+
+        let page_size = self.results_per_page.unwrap_or(100).max(1);
+        let page = self.page.unwrap_or(1).max(1);
+        let count = listings.len() as u32;
+        let listings = listings
+            .into_iter()
+            .skip((page as usize - 1).saturating_mul(page_size as usize))
+            .take(page_size as usize)
+            .collect();
+
+        ListingSearchResponse {
+            listings,
+            page,
+            num_pages: count.div_ceil(page_size),
+            page_size,
+            count,
+        }
+    }
+
+    /// Sorts `listings` in place according to `sort_by`
+    ///
+    /// Supports the `listPrice`, `soldPrice`, `listDate`, `soldDate`, `updatedOn` and
+    /// `daysOnMarket` keys with an `Asc`/`Desc` suffix. Listings missing the sort key
+    /// are placed last; unknown or absent sort orders leave the slice untouched.
+    pub fn sort_listings(&self, listings: &mut [Listing]) {
+        let Some(sort_by) = self.sort_by.as_deref() else {
+            return;
+        };
+        let (key, descending) = if let Some(key) = sort_by.strip_suffix("Desc") {
+            (key, true)
+        } else {
+            (sort_by.strip_suffix("Asc").unwrap_or(sort_by), false)
+        };
+
+        let key_fn: fn(&Listing) -> Option<f64> = match key {
+            "listPrice" => |l| l.list_price,
+            "soldPrice" => |l| l.sold_price,
+            "daysOnMarket" => |l| l.days_on_market.map(f64::from),
+            "listDate" => |l| date_key(l.list_date.as_deref()),
+            "soldDate" => |l| date_key(l.sold_date.as_deref()),
+            "updatedOn" => |l| l.last_updated().map(|dt| dt.timestamp() as f64),
+            _ => return,
+        };
+
+        listings.sort_by(|a, b| match (key_fn(a), key_fn(b)) {
+            (Some(a), Some(b)) if descending => b.total_cmp(&a),
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+    }
 }
 
 /// Builder for constructing a ListingSearchRequest
 #[derive(Debug, Default)]
 pub struct ListingSearchRequestBuilder {
-    city: Option<String>,
-    status: Option<Vec<String>>,
-    min_price: Option<f64>,
-    max_price: Option<f64>,
-    bedrooms: Option<u32>,
-    property_type: Option<Vec<String>>,
-    page: Option<u32>,
-    results_per_page: Option<u32>,
+    request: ListingSearchRequest,
 }
 
 impl ListingSearchRequestBuilder {
     /// Sets the city filter
     pub fn city(mut self, city: impl Into<String>) -> Self {
-        self.city = Some(city.into());
+        self.request.city = Some(city.into());
         self
     }
 
     /// Sets the status filter (e.g., ["Active", "Sold"])
     pub fn status(mut self, status: Vec<String>) -> Self {
-        self.status = Some(status);
+        self.request.status = Some(status);
         self
     }
 
     /// Adds a single status to the filter
     pub fn add_status(mut self, status: impl Into<String>) -> Self {
-        self.status.get_or_insert_with(Vec::new).push(status.into());
+        self.request
+            .status
+            .get_or_insert_with(Vec::new)
+            .push(status.into());
         self
     }
 
     /// Sets the minimum price filter
     pub fn min_price(mut self, min_price: f64) -> Self {
-        self.min_price = Some(min_price);
+        self.request.min_price = Some(min_price);
         self
     }
 
     /// Sets the maximum price filter
     pub fn max_price(mut self, max_price: f64) -> Self {
-        self.max_price = Some(max_price);
+        self.request.max_price = Some(max_price);
         self
     }
 
     /// Sets a price range filter
     pub fn price_range(mut self, min: f64, max: f64) -> Self {
-        self.min_price = Some(min);
-        self.max_price = Some(max);
+        self.request.min_price = Some(min);
+        self.request.max_price = Some(max);
         self
     }
 
     /// Sets the number of bedrooms filter
     pub fn bedrooms(mut self, bedrooms: u32) -> Self {
-        self.bedrooms = Some(bedrooms);
+        self.request.bedrooms = Some(bedrooms);
         self
     }
 
     /// Sets the property type filter (e.g., ["Condo", "Detached"])
     pub fn property_type(mut self, property_type: Vec<String>) -> Self {
-        self.property_type = Some(property_type);
+        self.request.property_type = Some(property_type);
         self
     }
 
     /// Adds a single property type to the filter
     pub fn add_property_type(mut self, property_type: impl Into<String>) -> Self {
-        self.property_type
+        self.request
+            .property_type
             .get_or_insert_with(Vec::new)
             .push(property_type.into());
         self
     }
 
+    /// Sets the minimum number of bedrooms
+    pub fn min_bedrooms(mut self, min: u32) -> Self {
+        self.request.min_bedrooms = Some(min);
+        self
+    }
+
+    /// Sets the maximum number of bedrooms
+    pub fn max_bedrooms(mut self, max: u32) -> Self {
+        self.request.max_bedrooms = Some(max);
+        self
+    }
+
+    /// Sets a bedroom range filter
+    pub fn bedroom_range(mut self, min: u32, max: u32) -> Self {
+        self.request.min_bedrooms = Some(min);
+        self.request.max_bedrooms = Some(max);
+        self
+    }
+
+    /// Sets the minimum number of bathrooms
+    pub fn min_bathrooms(mut self, min: u32) -> Self {
+        self.request.min_bathrooms = Some(min);
+        self
+    }
+
+    /// Sets the maximum number of bathrooms
+    pub fn max_bathrooms(mut self, max: u32) -> Self {
+        self.request.max_bathrooms = Some(max);
+        self
+    }
+
+    /// Sets a bathroom range filter
+    pub fn bathroom_range(mut self, min: u32, max: u32) -> Self {
+        self.request.min_bathrooms = Some(min);
+        self.request.max_bathrooms = Some(max);
+        self
+    }
+
+    /// Sets a square footage range filter
+    pub fn sqft_range(mut self, min: u32, max: u32) -> Self {
+        self.request.min_sqft = Some(min);
+        self.request.max_sqft = Some(max);
+        self
+    }
+
+    /// Sets the minimum square footage
+    pub fn min_sqft(mut self, min: u32) -> Self {
+        self.request.min_sqft = Some(min);
+        self
+    }
+
+    /// Sets the maximum square footage
+    pub fn max_sqft(mut self, max: u32) -> Self {
+        self.request.max_sqft = Some(max);
+        self
+    }
+
+    /// Sets the area filter
+    pub fn area(mut self, area: impl Into<String>) -> Self {
+        self.request.area = Some(area.into());
+        self
+    }
+
+    /// Sets the neighborhood filter
+    pub fn neighborhood(mut self, neighborhood: impl Into<String>) -> Self {
+        self.request.neighborhood = Some(neighborhood.into());
+        self
+    }
+
+    /// Adds a property class to the filter (e.g. "CondoProperty")
+    pub fn add_class(mut self, class: impl Into<String>) -> Self {
+        self.request
+            .class
+            .get_or_insert_with(Vec::new)
+            .push(class.into());
+        self
+    }
+
+    /// Adds a transaction type to the filter ("Sale" or "Lease")
+    pub fn add_listing_type(mut self, listing_type: impl Into<String>) -> Self {
+        self.request
+            .listing_type
+            .get_or_insert_with(Vec::new)
+            .push(listing_type.into());
+        self
+    }
+
+    /// Adds a style / property sub-type to the filter
+    pub fn add_style(mut self, style: impl Into<String>) -> Self {
+        self.request
+            .style
+            .get_or_insert_with(Vec::new)
+            .push(style.into());
+        self
+    }
+
+    /// Adds a last-status code to the filter (e.g. "Sld")
+    pub fn add_last_status(mut self, last_status: impl Into<String>) -> Self {
+        self.request
+            .last_status
+            .get_or_insert_with(Vec::new)
+            .push(last_status.into());
+        self
+    }
+
+    /// Sets a sold price range filter
+    pub fn sold_price_range(mut self, min: f64, max: f64) -> Self {
+        self.request.min_sold_price = Some(min);
+        self.request.max_sold_price = Some(max);
+        self
+    }
+
+    /// Sets a listing date range filter (YYYY-MM-DD)
+    pub fn list_date_range(mut self, min: impl Into<String>, max: impl Into<String>) -> Self {
+        self.request.min_list_date = Some(min.into());
+        self.request.max_list_date = Some(max.into());
+        self
+    }
+
+    /// Sets a sold date range filter (YYYY-MM-DD)
+    pub fn sold_date_range(mut self, min: impl Into<String>, max: impl Into<String>) -> Self {
+        self.request.min_sold_date = Some(min.into());
+        self.request.max_sold_date = Some(max.into());
+        self
+    }
+
+    /// Only returns listings updated on or after this date (YYYY-MM-DD)
+    pub fn min_updated_on(mut self, date: impl Into<String>) -> Self {
+        self.request.min_updated_on = Some(date.into());
+        self
+    }
+
+    /// Only returns listings updated on or before this date (YYYY-MM-DD)
+    pub fn max_updated_on(mut self, date: impl Into<String>) -> Self {
+        self.request.max_updated_on = Some(date.into());
+        self
+    }
+
+    /// Sets a days-on-market range filter
+    pub fn days_on_market_range(mut self, min: u32, max: u32) -> Self {
+        self.request.min_days_on_market = Some(min);
+        self.request.max_days_on_market = Some(max);
+        self
+    }
+
+    /// Sets the minimum number of garage spaces
+    pub fn min_garage_spaces(mut self, min: u32) -> Self {
+        self.request.min_garage_spaces = Some(min);
+        self
+    }
+
+    /// Sets the minimum number of parking spaces
+    pub fn min_parking_spaces(mut self, min: u32) -> Self {
+        self.request.min_parking_spaces = Some(min);
+        self
+    }
+
+    /// Adds a basement type to the filter
+    pub fn add_basement(mut self, basement: impl Into<String>) -> Self {
+        self.request
+            .basement
+            .get_or_insert_with(Vec::new)
+            .push(basement.into());
+        self
+    }
+
+    /// Sets a free-text keyword search
+    pub fn keywords(mut self, keywords: impl Into<String>) -> Self {
+        self.request.keywords = Some(keywords.into());
+        self
+    }
+
+    /// Restricts the keyword search to the given fields
+    pub fn search_fields(mut self, fields: impl Into<String>) -> Self {
+        self.request.search_fields = Some(fields.into());
+        self
+    }
+
+    /// Sets the listing agent filter
+    pub fn agent(mut self, agent: impl Into<String>) -> Self {
+        self.request.agent = Some(agent.into());
+        self
+    }
+
+    /// Sets the listing brokerage filter
+    pub fn brokerage(mut self, brokerage: impl Into<String>) -> Self {
+        self.request.brokerage = Some(brokerage.into());
+        self
+    }
+
+    /// Sets the listing office filter
+    pub fn office_id(mut self, office_id: impl Into<String>) -> Self {
+        self.request.office_id = Some(office_id.into());
+        self
+    }
+
+    /// Sets the board ID for multi-MLS accounts
+    pub fn board_id(mut self, board_id: impl Into<String>) -> Self {
+        self.request.board_id = Some(board_id.into());
+        self
+    }
+
+    /// Applies a geospatial filter
+    pub fn geo(mut self, filter: GeoFilter) -> Self {
+        self.request.set_geo_filter(filter);
+        self
+    }
+
+    /// Restricts results to listings within `radius_km` of a point
+    pub fn within_radius(self, center: GeoPoint, radius_km: f64) -> Self {
+        self.geo(GeoFilter::Radius { center, radius_km })
+    }
+
+    /// Restricts results to listings within a bounding box
+    pub fn within_bounds(self, bbox: BoundingBox) -> Self {
+        self.geo(GeoFilter::BoundingBox(bbox))
+    }
+
+    /// Restricts results to listings within a polygon
+    pub fn within_polygon(self, polygon: Polygon) -> Self {
+        self.geo(GeoFilter::Map(MapFilter::polygon(polygon)))
+    }
+
+    /// Restricts results to listings within the polygons of a GeoJSON object
+    ///
+    /// # Errors
+    ///
+    /// Returns `RepliersError::InvalidRequest` if the GeoJSON is not a polygon shape.
+    pub fn within_geojson(self, geojson: &serde_json::Value) -> Result<Self, crate::RepliersError> {
+        Ok(self.geo(GeoFilter::Map(MapFilter::from_geojson(geojson)?)))
+    }
+
+    /// Sets the sort order (e.g. "listPriceAsc")
+    pub fn sort_by(mut self, sort_by: impl Into<String>) -> Self {
+        self.request.sort_by = Some(sort_by.into());
+        self
+    }
+
+    /// Limits the fields returned for each listing
+    pub fn fields(mut self, fields: impl Into<String>) -> Self {
+        self.request.fields = Some(fields.into());
+        self
+    }
+
     /// Sets the page number for pagination
     pub fn page(mut self, page: u32) -> Self {
-        self.page = Some(page);
+        self.request.page = Some(page);
         self
     }
 
     /// Sets the number of results per page
     pub fn results_per_page(mut self, results_per_page: u32) -> Self {
-        self.results_per_page = Some(results_per_page);
+        self.request.results_per_page = Some(results_per_page);
         self
     }
 
     /// Builds the ListingSearchRequest
     pub fn build(self) -> ListingSearchRequest {
-        ListingSearchRequest {
-            city: self.city,
-            status: self.status,
-            min_price: self.min_price,
-            max_price: self.max_price,
-            bedrooms: self.bedrooms,
-            property_type: self.property_type,
-            page: self.page,
-            results_per_page: self.results_per_page,
-        }
+        self.request
+    }
+}
+
+fn is_unset_map(map: &Option<MapFilter>) -> bool {
+    map.as_ref().is_none_or(MapFilter::is_empty)
+}
+
+fn matches_text(wanted: &Option<String>, actual: Option<&str>) -> bool {
+    match wanted {
+        Some(wanted) => actual.is_some_and(|a| a.trim().eq_ignore_ascii_case(wanted.trim())),
+        None => true,
+    }
+}
+
+fn matches_any(wanted: &Option<Vec<String>>, actual: Option<&str>) -> bool {
+    match wanted.as_ref().filter(|w| !w.is_empty()) {
+        Some(wanted) => actual.is_some_and(|a| {
+            wanted
+                .iter()
+                .any(|w| w.trim().eq_ignore_ascii_case(a.trim()))
+        }),
+        None => true,
     }
 }
+
+/// Matches the friendly status names accepted by the API as well as raw status codes
+fn matches_status(wanted: &str, listing: &Listing) -> bool {
+    let status = listing.status.as_deref().unwrap_or_default();
+    let last_status = listing.last_status.as_deref().unwrap_or_default();
+    match wanted.trim().to_ascii_lowercase().as_str() {
+        "a" | "active" => status.eq_ignore_ascii_case("A"),
+        "u" | "unavailable" | "inactive" => status.eq_ignore_ascii_case("U"),
+        "sold" => last_status.eq_ignore_ascii_case("Sld"),
+        "leased" => last_status.eq_ignore_ascii_case("Lsd"),
+        other => status.eq_ignore_ascii_case(other) || last_status.eq_ignore_ascii_case(other),
+    }
+}
+
+fn in_range<T: PartialOrd>(value: Option<T>, min: Option<T>, max: Option<T>) -> bool {
+    if min.is_none() && max.is_none() {
+        return true;
+    }
+    let Some(value) = value else {
+        return false;
+    };
+    min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+}
+
+fn in_date_range(value: Option<&str>, min: &Option<String>, max: &Option<String>) -> bool {
+    let date = value.and_then(parse_timestamp).map(|dt| dt.date_naive());
+    in_range(date, parse_date(min), parse_date(max))
+}
+
+fn parse_date(value: &Option<String>) -> Option<NaiveDate> {
+    value
+        .as_deref()
+        .and_then(parse_timestamp)
+        .map(|dt| dt.date_naive())
+}
+
+fn date_key(value: Option<&str>) -> Option<f64> {
+    value
+        .and_then(parse_timestamp)
+        .map(|dt| dt.timestamp() as f64)
+}
+
+/// Parses square footage values such as "1500" or ranges like "1500-1999" (lower bound)
+fn parse_sqft(value: &str) -> Option<f64> {
+    let lower = value.split('-').next()?.trim().replace(',', "");
+    lower.parse().ok()
+}
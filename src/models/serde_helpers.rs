@@ -0,0 +1,99 @@
+//! Lenient deserializers for listing fields
+//!
+//! MLS boards disagree on whether numeric fields are sent as JSON numbers or strings
+//! (e.g. `"listPrice": "649900.00"`), and frequently send empty strings for missing
+//! values. These helpers accept either form and map blanks to `None`.
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Deserializes a number or numeric string into `Option<f64>`
+pub(crate) fn opt_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<Value>::deserialize(deserializer)?.and_then(|v| value_as_f64(&v)))
+}
+
+/// Deserializes a number or numeric string into `Option<u32>`
+///
+/// Fractional values are truncated, since some boards report counts like `2.0`.
+pub(crate) fn opt_u32<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<Value>::deserialize(deserializer)?
+        .and_then(|v| value_as_f64(&v))
+        .filter(|n| *n >= 0.0 && *n <= u32::MAX as f64)
+        .map(|n| n as u32))
+}
+
+/// Deserializes a string, number or boolean into `Option<String>`
+pub(crate) fn opt_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(s)) if s.trim().is_empty() => None,
+        Some(Value::String(s)) => Some(s),
+        Some(Value::Number(n)) => Some(n.to_string()),
+        Some(Value::Bool(b)) => Some(b.to_string()),
+        _ => None,
+    })
+}
+
+/// Deserializes either an array or an object of values into `Option<Vec<T>>`
+///
+/// Some boards send collections such as rooms keyed by index (`{"1": {...}}`).
+pub(crate) fn opt_vec_or_map<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let values = match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Array(items)) => items,
+        Some(Value::Object(map)) => {
+            // serde_json maps iterate in string order ("1", "10", "2"); put numeric keys
+            // back in numeric order, ahead of any others
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by_cached_key(|(key, _)| key.parse::<u64>().map_err(|_| key.clone()));
+            entries.into_iter().map(|(_, v)| v).collect()
+        }
+        _ => return Ok(None),
+    };
+
+    values
+        .into_iter()
+        .map(|v| serde_json::from_value(v).map_err(serde::de::Error::custom))
+        .collect::<Result<Vec<T>, _>>()
+        .map(Some)
+}
+
+/// Deserializes a list of strings, tolerating a single string or stray non-string items
+pub(crate) fn opt_string_vec<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Array(items)) => Some(
+            items
+                .into_iter()
+                .filter_map(|v| match v {
+                    Value::String(s) => Some(s),
+                    Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        Some(Value::String(s)) if !s.trim().is_empty() => Some(vec![s]),
+        _ => None,
+    })
+}
+
+fn value_as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().replace(',', "").parse::<f64>().ok(),
+        _ => None,
+    }
+}
@@ -0,0 +1,294 @@
+//! Auto-paginating search streams
+//!
+//! [`RepliersClient::search_listings_stream`] walks every page of a listing search lazily,
+//! yielding one [`Listing`] at a time. Pages are only requested as the stream is polled,
+//! and optionally prefetched a few at a time.
+//!
+//! [`RepliersClient::deleted_listings_stream`] does the same for the deleted-listings
+//! feed. [`search_stream`] and [`deleted_stream`] work over any [`RepliersApi`]
+//! implementation, so code written against the trait can page through results from an
+//! [`InMemoryApi`] in tests.
+//!
+//! [`InMemoryApi`]: crate::InMemoryApi
+
+use crate::api::RepliersApi;
+use crate::models::{DeletedListing, DeletedListingsQuery, Listing, ListingSearchRequest};
+use crate::{RepliersClient, RepliersError};
+use futures::stream::{self, BoxStream, StreamExt, TryStreamExt};
+
+/// Options controlling how a search stream walks the result pages
+#[derive(Debug, Clone)]
+pub struct SearchStreamOptions {
+    /// Results per page; defaults to the request's `results_per_page`
+    pub page_size: Option<u32>,
+
+    /// Stop after yielding this many listings
+    pub max_results: Option<usize>,
+
+    /// Number of pages requested concurrently ahead of the consumer (minimum 1)
+    pub prefetch: usize,
+
+    /// Page to start from, e.g. to resume an interrupted export; defaults to the
+    /// request's `page`, or 1
+    pub start_page: Option<u32>,
+}
+
+impl Default for SearchStreamOptions {
+    fn default() -> Self {
+        Self {
+            page_size: None,
+            max_results: None,
+            prefetch: 1,
+            start_page: None,
+        }
+    }
+}
+
+impl RepliersClient {
+    /// Streams every listing matching a search, fetching pages on demand
+    ///
+    /// Equivalent to [`search_listings_stream_with`](Self::search_listings_stream_with)
+    /// with default options.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use repliers_beta::{RepliersClient, ListingSearchRequest};
+    /// use futures::TryStreamExt;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = RepliersClient::new("api_key".to_string());
+    /// let request = ListingSearchRequest::builder().city("Toronto").build();
+    ///
+    /// let mut listings = client.search_listings_stream(request);
+    /// while let Some(listing) = listings.try_next().await? {
+    ///     println!("{}", listing.mls_number);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn search_listings_stream(
+        &self,
+        request: ListingSearchRequest,
+    ) -> BoxStream<'static, Result<Listing, RepliersError>> {
+        self.search_listings_stream_with(request, SearchStreamOptions::default())
+    }
+
+    /// Streams every listing matching a search using the given options
+    ///
+    /// The first page is requested when the stream is first polled; it determines how
+    /// many pages remain. The stream ends after the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use repliers_beta::{RepliersClient, ListingSearchRequest};
+    /// use futures::TryStreamExt;
+    /// use repliers_beta::pagination::SearchStreamOptions;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = RepliersClient::new("api_key".to_string());
+    /// let request = ListingSearchRequest::builder().city("Toronto").build();
+    ///
+    /// let options = SearchStreamOptions {
+    ///     page_size: Some(100),
+    ///     max_results: Some(1_000),
+    ///     prefetch: 3,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let listings: Vec<_> = client
+    ///     .search_listings_stream_with(request, options)
+    ///     .try_collect()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn search_listings_stream_with(
+        &self,
+        request: ListingSearchRequest,
+        options: SearchStreamOptions,
+    ) -> BoxStream<'static, Result<Listing, RepliersError>> {
+        search_stream(self.clone(), request, options)
+    }
+
+    /// Streams every deleted listing matching a query, fetching pages on demand
+    ///
+    /// Starts at the query's `page` (default 1) and requests `results_per_page` listings
+    /// at a time until the last page. The stream ends after the first error.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use repliers_beta::{DeletedListingsQuery, RepliersClient};
+    /// use futures::TryStreamExt;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = RepliersClient::new("api_key".to_string());
+    /// let query = DeletedListingsQuery {
+    ///     min_updated_on: Some("2025-01-01".to_string()),
+    ///     results_per_page: Some(100),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let mut deleted = client.deleted_listings_stream(query);
+    /// while let Some(listing) = deleted.try_next().await? {
+    ///     println!("{}", listing.mls_number);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deleted_listings_stream(
+        &self,
+        query: DeletedListingsQuery,
+    ) -> BoxStream<'static, Result<DeletedListing, RepliersError>> {
+        deleted_stream(self.clone(), query)
+    }
+}
+
+/// Streams every listing matching a search through any [`RepliersApi`]
+///
+/// Behaves like [`RepliersClient::search_listings_stream_with`]. Pass the API by value
+/// for a `'static` stream (clients and `Arc`s are cheap to clone) or by reference to
+/// borrow it.
+///
+/// # Examples
+///
+/// ```
+/// use futures::TryStreamExt;
+/// use repliers_beta::pagination::{search_stream, SearchStreamOptions};
+/// use repliers_beta::{InMemoryApi, Listing, ListingSearchRequest};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let api = InMemoryApi::new();
+/// api.insert_listings((1..=25).map(|i| {
+///     serde_json::from_value::<Listing>(serde_json::json!({"mlsNumber": format!("X{}", i)}))
+///         .unwrap()
+/// }));
+///
+/// let options = SearchStreamOptions {
+///     page_size: Some(10),
+///     ..Default::default()
+/// };
+/// let listings: Vec<Listing> = search_stream(&api, ListingSearchRequest::default(), options)
+///     .try_collect()
+///     .await?;
+/// assert_eq!(listings.len(), 25);
+/// # Ok(())
+/// # }
+/// ```
+pub fn search_stream<'a, A>(
+    api: A,
+    request: ListingSearchRequest,
+    options: SearchStreamOptions,
+) -> BoxStream<'a, Result<Listing, RepliersError>>
+where
+    A: RepliersApi + Clone + 'a,
+{
+    let start_page = options.start_page.or(request.page).unwrap_or(1).max(1);
+    let page_size = options.page_size.or(request.results_per_page);
+    let prefetch = options.prefetch.max(1);
+    let max_results = options.max_results;
+
+    let fetch_page = move |page: u32| {
+        let api = api.clone();
+        let mut request = request.clone();
+        request.page = Some(page);
+        request.results_per_page = page_size;
+        async move { api.search_listings(request).await }
+    };
+
+    let first_page = fetch_page(start_page);
+    let listings = stream::once(first_page)
+        .map(move |result| match result {
+            Err(e) => stream::iter([Err(e)]).boxed(),
+            Ok(first) => {
+                let last_page = last_page_needed(
+                    start_page,
+                    first.num_pages,
+                    first.page_size.max(first.listings.len() as u32),
+                    max_results,
+                );
+
+                let rest = stream::iter(start_page.saturating_add(1)..=last_page)
+                    .map(fetch_page.clone())
+                    .buffered(prefetch)
+                    .flat_map(|result| match result {
+                        Ok(page) => stream::iter(page.listings.into_iter().map(Ok)).boxed(),
+                        Err(e) => stream::iter([Err(e)]).boxed(),
+                    });
+
+                stream::iter(first.listings.into_iter().map(Ok))
+                    .chain(rest)
+                    .boxed()
+            }
+        })
+        .flatten()
+        .scan(false, |failed, item| {
+            if *failed {
+                return futures::future::ready(None);
+            }
+            *failed = item.is_err();
+            futures::future::ready(Some(item))
+        });
+
+    match max_results {
+        Some(max) => listings.take(max).boxed(),
+        None => listings.boxed(),
+    }
+}
+
+/// Streams every deleted listing matching a query through any [`RepliersApi`]
+///
+/// Behaves like [`RepliersClient::deleted_listings_stream`].
+pub fn deleted_stream<'a, A>(
+    api: A,
+    query: DeletedListingsQuery,
+) -> BoxStream<'a, Result<DeletedListing, RepliersError>>
+where
+    A: RepliersApi + Clone + 'a,
+{
+    stream::try_unfold(Some(query), move |query| {
+        let api = api.clone();
+        async move {
+            let Some(query) = query else {
+                return Ok::<_, RepliersError>(None);
+            };
+            let page = query.page.unwrap_or(1).max(1);
+            let response = api
+                .get_deleted_listings(DeletedListingsQuery {
+                    page: Some(page),
+                    ..query.clone()
+                })
+                .await?;
+            let next = (page < response.num_pages).then(|| DeletedListingsQuery {
+                page: Some(page + 1),
+                ..query
+            });
+            Ok(Some((
+                stream::iter(response.listings.into_iter().map(Ok)),
+                next,
+            )))
+        }
+    })
+    .try_flatten()
+    .boxed()
+}
+
+/// Computes the last page worth requesting, given the result cap
+fn last_page_needed(
+    start_page: u32,
+    num_pages: u32,
+    page_size: u32,
+    max_results: Option<usize>,
+) -> u32 {
+    match max_results {
+        Some(max) if page_size > 0 => {
+            let pages = (max as u64).div_ceil(page_size as u64).max(1);
+            let capped = (start_page as u64 + pages - 1).min(num_pages as u64);
+            capped as u32
+        }
+        _ => num_pages,
+    }
+}
@@ -0,0 +1,267 @@
+//! Client-side rate limiting
+//!
+//! A token-bucket limiter that can be attached to a [`RepliersClient`](crate::RepliersClient).
+//! The bucket lives behind an `Arc`, so every clone of the client draws from the same
+//! budget. When the API reports its own quota through `X-RateLimit-*` headers, the limiter
+//! tightens its budget to match.
+
+use reqwest::header::HeaderMap;
+use std::num::NonZeroU32;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Header names checked for the remaining request quota
+const REMAINING_HEADERS: [&str; 2] = ["x-ratelimit-remaining", "ratelimit-remaining"];
+
+/// Header names checked for the time until the quota resets
+const RESET_HEADERS: [&str; 2] = ["x-ratelimit-reset", "ratelimit-reset"];
+
+/// Reset values above this are treated as Unix timestamps rather than seconds
+const EPOCH_THRESHOLD: u64 = 1_000_000_000;
+
+/// Rate at which requests may be sent
+///
+/// # Examples
+///
+/// ```no_run
+/// use repliers_beta::{RateLimit, RepliersClient};
+/// use std::num::NonZeroU32;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let per_minute = NonZeroU32::new(120).unwrap();
+/// let client = RepliersClient::builder()
+///     .api_key("your_api_key")
+///     .rate_limit(RateLimit::per_minute(per_minute).burst(10))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// Tokens added to the bucket per second; must be positive, or the limiter never
+    /// refills once the burst is spent
+    pub refill_per_second: f64,
+
+    /// Maximum number of requests that may be sent back-to-back
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Allows `requests` per second, with a burst of the same size
+    pub fn per_second(requests: NonZeroU32) -> Self {
+        Self {
+            refill_per_second: requests.get() as f64,
+            burst: requests.get(),
+        }
+    }
+
+    /// Allows `requests` per minute, with a burst of the same size
+    pub fn per_minute(requests: NonZeroU32) -> Self {
+        Self {
+            refill_per_second: requests.get() as f64 / 60.0,
+            burst: requests.get(),
+        }
+    }
+
+    /// Sets the maximum burst size (minimum 1)
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+}
+
+/// Snapshot of a limiter's current budget
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitBudget {
+    /// Requests that can be sent immediately
+    pub available: u32,
+
+    /// Maximum size of the bucket
+    pub capacity: u32,
+
+    /// Remaining quota last reported by the API, if any
+    pub server_remaining: Option<u64>,
+
+    /// Time until the API quota resets, if reported
+    pub server_reset_in: Option<Duration>,
+}
+
+/// Token-bucket rate limiter shared between client clones
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    state: Arc<Mutex<BucketState>>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+    server_remaining: Option<u64>,
+    server_reset: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter with a full bucket
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            state: Arc::new(Mutex::new(BucketState {
+                tokens: limit.burst as f64,
+                last_refill: Instant::now(),
+                server_remaining: None,
+                server_reset: None,
+            })),
+        }
+    }
+
+    /// Returns the configured rate
+    pub fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    /// Waits until a request may be sent and consumes one token
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.lock();
+                let now = Instant::now();
+                self.refill(&mut state, now);
+
+                match state.blocked_until(now) {
+                    Some(until) => until - now,
+                    None if state.tokens >= 1.0 => {
+                        state.take();
+                        return;
+                    }
+                    None => self.time_until_token(&state),
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Consumes one token if available without waiting
+    ///
+    /// Returns false if the request should be delayed.
+    pub fn try_acquire(&self) -> bool {
+        let mut state = self.lock();
+        let now = Instant::now();
+        self.refill(&mut state, now);
+
+        if state.blocked_until(now).is_none() && state.tokens >= 1.0 {
+            state.take();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the current budget
+    pub fn budget(&self) -> RateLimitBudget {
+        let mut state = self.lock();
+        let now = Instant::now();
+        self.refill(&mut state, now);
+
+        RateLimitBudget {
+            available: state.tokens.floor() as u32,
+            capacity: self.limit.burst,
+            server_remaining: state.server_remaining,
+            server_reset_in: state
+                .server_reset
+                .map(|reset| reset.saturating_duration_since(now)),
+        }
+    }
+
+    /// Adapts the budget to rate-limit headers reported by the API
+    ///
+    /// Recognizes `X-RateLimit-Remaining`/`X-RateLimit-Reset` and their unprefixed
+    /// `RateLimit-*` equivalents. The reset value may be given in seconds or as a Unix
+    /// timestamp. Responses without these headers leave the budget unchanged.
+    pub fn update_from_headers(&self, headers: &HeaderMap) {
+        let remaining = header_u64(headers, &REMAINING_HEADERS);
+        let reset = header_u64(headers, &RESET_HEADERS).map(reset_duration);
+        if remaining.is_none() && reset.is_none() {
+            return;
+        }
+
+        let mut state = self.lock();
+        let now = Instant::now();
+        self.refill(&mut state, now);
+
+        if let Some(remaining) = remaining {
+            state.server_remaining = Some(remaining);
+            state.tokens = state.tokens.min(remaining as f64);
+        }
+        if let Some(reset) = reset {
+            state.server_reset = Some(now + reset);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BucketState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn refill(&self, state: &mut BucketState, now: Instant) {
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens =
+            (state.tokens + elapsed * self.limit.refill_per_second).min(self.limit.burst as f64);
+        state.last_refill = now;
+
+        if state.server_reset.is_some_and(|reset| reset <= now) {
+            state.server_remaining = None;
+            state.server_reset = None;
+        }
+    }
+
+    fn time_until_token(&self, state: &BucketState) -> Duration {
+        if self.limit.refill_per_second <= 0.0 {
+            return Duration::from_secs(1);
+        }
+        let missing = 1.0 - state.tokens;
+        Duration::from_secs_f64(missing / self.limit.refill_per_second)
+    }
+}
+
+impl BucketState {
+    /// Returns when the server quota resets, if it is currently exhausted
+    fn blocked_until(&self, now: Instant) -> Option<Instant> {
+        match (self.server_remaining, self.server_reset) {
+            (Some(0), Some(reset)) if reset > now => Some(reset),
+            _ => None,
+        }
+    }
+
+    fn take(&mut self) {
+        self.tokens -= 1.0;
+        if let Some(remaining) = self.server_remaining.as_mut() {
+            *remaining = remaining.saturating_sub(1);
+        }
+    }
+}
+
+fn header_u64(headers: &HeaderMap, names: &[&str]) -> Option<u64> {
+    names.iter().find_map(|name| {
+        headers
+            .get(*name)?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| *v >= 0.0)
+            .map(|v| v.ceil() as u64)
+    })
+}
+
+fn reset_duration(value: u64) -> Duration {
+    if value >= EPOCH_THRESHOLD {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Duration::from_secs(value.saturating_sub(now))
+    } else {
+        Duration::from_secs(value)
+    }
+}
@@ -0,0 +1,1670 @@
+//! RESO Data Dictionary mapping
+//!
+//! Converts [`Listing`]s into RESO Data Dictionary `Property` records ([`ResoProperty`])
+//! with [`to_reso`], and back with [`from_reso`]. Field names follow the RESO Web API
+//! (`ListPrice`, `BedroomsTotal`, `StandardStatus`, `UnparsedAddress`...), and Repliers
+//! status codes, classes and property types are translated to the RESO lookup values
+//! ([`StandardStatus`], [`PropertyType`], `PropertySubType`).
+//!
+//! Neither model is a superset of the other. Every conversion returns a
+//! [`MappingReport`] listing the fields that were dropped, either because the target
+//! has no equivalent, because a lookup value is not recognized or because a value could
+//! not be converted. Board-specific fields in [`Listing::extra`] and unknown RESO fields
+//! in [`ResoProperty::extra`] are always reported.
+//!
+//! # Examples
+//!
+//! ```
+//! use repliers_beta::reso::{self, PropertyType, StandardStatus};
+//! use repliers_beta::Listing;
+//!
+//! let listing: Listing = serde_json::from_str(
+//!     r#"{
+//!         "mlsNumber": "C1234567",
+//!         "status": "U",
+//!         "lastStatus": "Sld",
+//!         "class": "CondoProperty",
+//!         "type": "Sale",
+//!         "listPrice": 649900,
+//!         "soldPrice": 655000,
+//!         "address": {"streetNumber": "10", "streetName": "Bay", "streetSuffix": "St", "city": "Toronto"},
+//!         "details": {"numBedrooms": 2, "numBedroomsPlus": 1, "propertyType": "Condo Apartment"},
+//!         "boardRegion": "GTA"
+//!     }"#,
+//! )
+//! .unwrap();
+//!
+//! let mapped = reso::to_reso(&listing);
+//! let property = &mapped.record;
+//! assert_eq!(property.standard_status, Some(StandardStatus::Closed));
+//! assert_eq!(property.property_type, Some(PropertyType::Residential));
+//! assert_eq!(property.property_sub_type.as_deref(), Some("Condominium"));
+//! assert_eq!(property.bedrooms_total, Some(3));
+//! assert_eq!(property.unparsed_address.as_deref(), Some("10 Bay St"));
+//!
+//! // The board-specific field has no RESO equivalent
+//! assert_eq!(mapped.report.unmapped[0].field, "boardRegion");
+//!
+//! let back = reso::from_reso(property).record;
+//! assert_eq!(back.last_status.as_deref(), Some("Sld"));
+//! assert_eq!(back.details.unwrap().property_type.as_deref(), Some("Condo Apartment"));
+//! ```
+
+use crate::models::{
+    parse_timestamp, Address, Agent, CondoFees, Condominium, Listing, ListingDetails,
+    ListingTimestamps, MapCoordinates, Office, Room, Taxes,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Base URL of the Repliers image CDN; listing images are paths relative to it
+pub const IMAGE_CDN: &str = "https://cdn.repliers.io/";
+
+/// RESO `StandardStatus` lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StandardStatus {
+    /// On the market and accepting offers
+    Active,
+
+    /// An offer was accepted, but the listing still takes backup offers
+    ActiveUnderContract,
+
+    /// The listing agreement was cancelled before it expired
+    Canceled,
+
+    /// The sale or lease has closed
+    Closed,
+
+    /// Not yet on the market, but about to be
+    ComingSoon,
+
+    /// Removed from the MLS, usually because it was entered in error
+    Delete,
+
+    /// The listing agreement ran out
+    Expired,
+
+    /// Temporarily off the market
+    Hold,
+
+    /// Not yet complete enough to publish
+    Incomplete,
+
+    /// An offer was accepted and the sale is waiting to close
+    Pending,
+
+    /// Taken off the market by the seller while the agreement is still in effect
+    Withdrawn,
+}
+
+impl StandardStatus {
+    /// Derives the standard status from a Repliers `status` (`A`/`U`) and `lastStatus`
+    /// code
+    ///
+    /// Returns `None` for unknown codes.
+    pub fn from_repliers(status: Option<&str>, last_status: Option<&str>) -> Option<Self> {
+        let active = match status.map(str::trim) {
+            Some(s) if s.eq_ignore_ascii_case("A") => Some(true),
+            Some(s) if s.eq_ignore_ascii_case("U") => Some(false),
+            _ => None,
+        };
+        let last_status = last_status.map(|s| s.trim().to_ascii_lowercase());
+        match (last_status.as_deref(), active) {
+            (Some("sld" | "lsd"), _) => Some(Self::Closed),
+            (Some("sc" | "sce" | "lc" | "lce"), _) => Some(Self::ActiveUnderContract),
+            (Some("exp"), _) => Some(Self::Expired),
+            (Some("ter"), _) => Some(Self::Canceled),
+            (Some("sus"), _) => Some(Self::Hold),
+            (Some("cs"), _) => Some(Self::ComingSoon),
+            (Some("new" | "pc" | "ext" | "dft"), Some(false)) => Some(Self::Withdrawn),
+            (Some("new" | "pc" | "ext" | "dft"), _) => Some(Self::Active),
+            (None, Some(true)) => Some(Self::Active),
+            _ => None,
+        }
+    }
+
+    /// Returns the Repliers `(status, lastStatus)` codes for this status
+    ///
+    /// `lease` selects the lease codes (`Lc`, `Lsd`) over the sale codes. Returns `None`
+    /// for `Delete` and `Incomplete`, which Repliers does not publish.
+    pub fn to_repliers(self, lease: bool) -> Option<(&'static str, &'static str)> {
+        match self {
+            Self::Active => Some(("A", "New")),
+            Self::ActiveUnderContract | Self::Pending => {
+                Some(("A", if lease { "Lc" } else { "Sc" }))
+            }
+            Self::ComingSoon => Some(("A", "Cs")),
+            Self::Closed => Some(("U", if lease { "Lsd" } else { "Sld" })),
+            Self::Expired => Some(("U", "Exp")),
+            Self::Canceled | Self::Withdrawn => Some(("U", "Ter")),
+            Self::Hold => Some(("U", "Sus")),
+            Self::Delete | Self::Incomplete => None,
+        }
+    }
+}
+
+/// RESO `PropertyType` lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PropertyType {
+    /// A business for sale, with or without its premises
+    BusinessOpportunity,
+
+    /// Commercial space for lease
+    CommercialLease,
+
+    /// Commercial property for sale
+    CommercialSale,
+
+    /// Farm or ranch land
+    Farm,
+
+    /// Vacant land
+    Land,
+
+    /// A manufactured home on a leased lot in a park
+    ManufacturedInPark,
+
+    /// A home for sale: house, townhouse or condominium
+    Residential,
+
+    /// A multi-unit building bought to rent out
+    ResidentialIncome,
+
+    /// A home for lease
+    ResidentialLease,
+}
+
+impl PropertyType {
+    /// Derives the property type from a Repliers `class`, `type` (`Sale`/`Lease`) and
+    /// `details.propertyType`
+    ///
+    /// Returns `None` for unknown classes.
+    pub fn from_repliers(
+        class: Option<&str>,
+        listing_type: Option<&str>,
+        property_type: Option<&str>,
+    ) -> Option<Self> {
+        let lease = listing_type.is_some_and(|t| t.trim().eq_ignore_ascii_case("Lease"));
+        let class = class.map(|c| c.trim().to_ascii_lowercase());
+        let property_type = property_type.map(|p| p.trim().to_ascii_lowercase());
+        match (class.as_deref(), lease) {
+            (Some("commercialproperty"), true) => Some(Self::CommercialLease),
+            (Some("commercialproperty"), false) => Some(Self::CommercialSale),
+            (Some("residentialproperty" | "condoproperty") | None, true) => {
+                Some(Self::ResidentialLease)
+            }
+            (Some("residentialproperty" | "condoproperty") | None, false) => {
+                match property_type.as_deref() {
+                    Some("vacant land") => Some(Self::Land),
+                    Some("farm") => Some(Self::Farm),
+                    Some("duplex" | "triplex" | "fourplex" | "multiplex") => {
+                        Some(Self::ResidentialIncome)
+                    }
+                    _ if class.is_none() && listing_type.is_none() => None,
+                    _ => Some(Self::Residential),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the Repliers `(class, type)` for this property type
+    ///
+    /// Residential types map to `ResidentialProperty`; [`from_reso`] switches to
+    /// `CondoProperty` for condominium sub-types.
+    pub fn to_repliers(self) -> (&'static str, &'static str) {
+        match self {
+            Self::CommercialLease => ("CommercialProperty", "Lease"),
+            Self::CommercialSale | Self::BusinessOpportunity => ("CommercialProperty", "Sale"),
+            Self::ResidentialLease => ("ResidentialProperty", "Lease"),
+            Self::Residential
+            | Self::ResidentialIncome
+            | Self::Farm
+            | Self::Land
+            | Self::ManufacturedInPark => ("ResidentialProperty", "Sale"),
+        }
+    }
+}
+
+/// Repliers `details.propertyType` values, their RESO `PropertySubType` and whether the
+/// property is attached
+///
+/// When several Repliers values share a sub-type, the first is used for the reverse
+/// mapping.
+const SUB_TYPES: &[(&str, &str, Option<bool>)] = &[
+    ("Detached", "SingleFamilyResidence", Some(false)),
+    ("Semi-Detached", "SingleFamilyResidence", Some(true)),
+    ("Link", "SingleFamilyResidence", Some(true)),
+    ("Att/Row/Townhouse", "Townhouse", Some(true)),
+    ("Condo Townhouse", "Townhouse", Some(true)),
+    ("Condo Apartment", "Condominium", Some(true)),
+    ("Comm Element Condo", "Condominium", Some(true)),
+    ("Leasehold Condo", "Condominium", Some(true)),
+    ("Co-op Apartment", "StockCooperative", Some(true)),
+    ("Co-Ownership Apartment", "OwnYourOwn", Some(true)),
+    ("Duplex", "Duplex", None),
+    ("Triplex", "Triplex", None),
+    ("Fourplex", "Quadruplex", None),
+    ("Multiplex", "MultiFamily", None),
+    ("Cottage", "Cabin", Some(false)),
+    ("Mobile/Trailer", "MobileHome", None),
+    ("Vacant Land", "UnimprovedLand", None),
+    ("Farm", "Farm", None),
+    ("Store W/Apt/Office", "MixedUse", None),
+    ("Office", "Office", None),
+    ("Industrial", "Industrial", None),
+    ("Commercial/Retail", "Retail", None),
+];
+
+/// RESO sub-types that Repliers files under `CondoProperty`
+const CONDO_SUB_TYPES: &[&str] = &["Condominium", "StockCooperative", "OwnYourOwn"];
+
+/// Why a field was left out of a conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UnmappedReason {
+    /// The target model has no equivalent field
+    NoEquivalent,
+
+    /// The value is not in the lookup table for the target field
+    UnknownLookup,
+
+    /// The value could not be converted (e.g. an unparseable date)
+    InvalidValue,
+}
+
+/// A source field that was not carried over
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnmappedField {
+    /// Field name in the source model: a dotted Repliers path (`details.extras`) or a
+    /// RESO field name
+    pub field: String,
+
+    /// The value that was dropped
+    pub value: Value,
+
+    /// Why it was dropped
+    pub reason: UnmappedReason,
+}
+
+/// Fields that could not be mapped, in source order
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MappingReport {
+    /// Every field that was dropped, with its value and the reason
+    pub unmapped: Vec<UnmappedField>,
+}
+
+impl MappingReport {
+    /// Returns true if every field was mapped
+    pub fn is_complete(&self) -> bool {
+        self.unmapped.is_empty()
+    }
+
+    /// Records a dropped field; `None` and null values are ignored
+    fn add(&mut self, field: impl Into<String>, value: impl Serialize, reason: UnmappedReason) {
+        let value = serde_json::to_value(value).unwrap_or(Value::Null);
+        if !value.is_null() {
+            self.unmapped.push(UnmappedField {
+                field: field.into(),
+                value,
+                reason,
+            });
+        }
+    }
+
+    fn extra(&mut self, prefix: &str, extra: &HashMap<String, Value>) {
+        let mut keys: Vec<&String> = extra.keys().collect();
+        keys.sort();
+        for key in keys {
+            self.add(
+                format!("{}{}", prefix, key),
+                &extra[key],
+                UnmappedReason::NoEquivalent,
+            );
+        }
+    }
+}
+
+/// A converted record with the report of what was left out
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mapped<T> {
+    /// The converted record
+    pub record: T,
+
+    /// The source fields the record could not hold
+    pub report: MappingReport,
+}
+
+/// A RESO Data Dictionary `Property` record
+///
+/// Covers the RESO fields that have a Repliers equivalent; other fields of a RESO
+/// payload are kept in [`extra`](Self::extra). Field docs name the Repliers source.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResoProperty {
+    /// `mlsNumber`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listing_key: Option<String>,
+
+    /// `mlsNumber`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listing_id: Option<String>,
+
+    /// `boardId`
+    #[serde(
+        default,
+        rename = "OriginatingSystemID",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub originating_system_id: Option<String>,
+
+    /// Derived from `status` and `lastStatus`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub standard_status: Option<StandardStatus>,
+
+    /// `lastStatus`, the board's own status code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mls_status: Option<String>,
+
+    /// Derived from `class`, `type` and `details.propertyType`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property_type: Option<PropertyType>,
+
+    /// `details.propertyType`, as a RESO lookup value (e.g. `SingleFamilyResidence`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property_sub_type: Option<String>,
+
+    /// Derived from `details.propertyType` (e.g. true for `Semi-Detached`)
+    #[serde(
+        default,
+        rename = "PropertyAttachedYN",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub property_attached_yn: Option<bool>,
+
+    /// `listPrice`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_price: Option<f64>,
+
+    /// `originalPrice`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_list_price: Option<f64>,
+
+    /// `soldPrice`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub close_price: Option<f64>,
+
+    /// `listDate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listing_contract_date: Option<NaiveDate>,
+
+    /// `soldDate`, or `timestamps.closedDate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub close_date: Option<NaiveDate>,
+
+    /// `timestamps.expiryDate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_date: Option<NaiveDate>,
+
+    /// `timestamps.terminatedDate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancellation_date: Option<NaiveDate>,
+
+    /// `timestamps.unavailableDate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub off_market_date: Option<NaiveDate>,
+
+    /// `timestamps.listingEntryDate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_entry_timestamp: Option<DateTime<Utc>>,
+
+    /// `timestamps.listingUpdated`, or `updatedOn`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modification_timestamp: Option<DateTime<Utc>>,
+
+    /// `timestamps.photosUpdated`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub photos_change_timestamp: Option<DateTime<Utc>>,
+
+    /// `daysOnMarket`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days_on_market: Option<u32>,
+
+    /// `occupancy` (`Owner`, `Tenant` or `Vacant`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub occupant_type: Option<String>,
+
+    /// The street address on one line, from the `address` components
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unparsed_address: Option<String>,
+
+    /// `address.streetNumber`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_number: Option<String>,
+
+    /// `address.streetDirectionPrefix`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_dir_prefix: Option<String>,
+
+    /// `address.streetName`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_name: Option<String>,
+
+    /// `address.streetSuffix`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_suffix: Option<String>,
+
+    /// `address.streetDirection`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_dir_suffix: Option<String>,
+
+    /// `address.unitNumber`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit_number: Option<String>,
+
+    /// `address.city`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+
+    /// `address.state`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_or_province: Option<String>,
+
+    /// `address.zip`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+
+    /// `address.country`, as an ISO code (`CA`, `US`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+
+    /// `address.area`
+    #[serde(
+        default,
+        rename = "MLSAreaMajor",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mls_area_major: Option<String>,
+
+    /// `address.district`
+    #[serde(
+        default,
+        rename = "MLSAreaMinor",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub mls_area_minor: Option<String>,
+
+    /// `address.neighborhood`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subdivision_name: Option<String>,
+
+    /// `address.majorIntersection`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cross_street: Option<String>,
+
+    /// `map.latitude`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+
+    /// `map.longitude`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+
+    /// `details.numBedrooms` plus `details.numBedroomsPlus`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bedrooms_total: Option<u32>,
+
+    /// `details.numBathrooms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bathrooms_total_integer: Option<u32>,
+
+    /// `details.numRooms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rooms_total: Option<u32>,
+
+    /// `details.sqft`, when it is a single number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub living_area: Option<f64>,
+
+    /// `SquareFeet` whenever [`living_area`](Self::living_area) is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub living_area_units: Option<String>,
+
+    /// `details.sqft`, when it is a range such as `1400-1599`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub living_area_range: Option<String>,
+
+    /// `details.yearBuilt`, when it is a year
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year_built: Option<u32>,
+
+    /// `details.yearBuilt`, when it is an age range such as `6-15`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year_built_details: Option<String>,
+
+    /// `details.style`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub architectural_style: Option<Vec<String>>,
+
+    /// `details.description`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_remarks: Option<String>,
+
+    /// `details.extras`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inclusions: Option<String>,
+
+    /// Derived from `details.garage` (false for `None`)
+    #[serde(default, rename = "GarageYN", skip_serializing_if = "Option::is_none")]
+    pub garage_yn: Option<bool>,
+
+    /// Derived from `details.garage` (`Attached`, `Built-In` or `Detached`)
+    #[serde(
+        default,
+        rename = "AttachedGarageYN",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub attached_garage_yn: Option<bool>,
+
+    /// `details.numGarageSpaces`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub garage_spaces: Option<f64>,
+
+    /// `details.numParkingSpaces`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parking_total: Option<f64>,
+
+    /// `details.basement1` and `details.basement2`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub basement: Option<Vec<String>>,
+
+    /// `details.heating`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heating: Option<Vec<String>>,
+
+    /// `details.airConditioning`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cooling: Option<Vec<String>>,
+
+    /// `details.swimmingPool`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool_features: Option<Vec<String>>,
+
+    /// `details.virtualTourUrl`
+    #[serde(
+        default,
+        rename = "VirtualTourURLUnbranded",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub virtual_tour_url_unbranded: Option<String>,
+
+    /// `office.brokerageName`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_office_name: Option<String>,
+
+    /// `officeId` of the first agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_office_key: Option<String>,
+
+    /// `agentId` of the first agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_agent_key: Option<String>,
+
+    /// `boardAgentId` of the first agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_agent_mls_id: Option<String>,
+
+    /// `name` of the first agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_agent_full_name: Option<String>,
+
+    /// `email` of the first agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_agent_email: Option<String>,
+
+    /// First phone number of the first agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_agent_direct_phone: Option<String>,
+
+    /// `website` of the first agent
+    #[serde(
+        default,
+        rename = "ListAgentURL",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub list_agent_url: Option<String>,
+
+    /// `officeId` of the second agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub co_list_office_key: Option<String>,
+
+    /// `agentId` of the second agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub co_list_agent_key: Option<String>,
+
+    /// `boardAgentId` of the second agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub co_list_agent_mls_id: Option<String>,
+
+    /// `name` of the second agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub co_list_agent_full_name: Option<String>,
+
+    /// `email` of the second agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub co_list_agent_email: Option<String>,
+
+    /// First phone number of the second agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub co_list_agent_direct_phone: Option<String>,
+
+    /// `website` of the second agent
+    #[serde(
+        default,
+        rename = "CoListAgentURL",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub co_list_agent_url: Option<String>,
+
+    /// `condominium.fees.maintenance`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub association_fee: Option<f64>,
+
+    /// `Monthly` whenever [`association_fee`](Self::association_fee) is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub association_fee_frequency: Option<String>,
+
+    /// `condominium.condoCorp`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub association_name: Option<String>,
+
+    /// `condominium.ammenities`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub association_amenities: Option<Vec<String>>,
+
+    /// `condominium.pets`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pets_allowed: Option<Vec<String>>,
+
+    /// `taxes.annualAmount`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tax_annual_amount: Option<f64>,
+
+    /// `taxes.assessmentYear`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tax_year: Option<u32>,
+
+    /// `photoCount`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub photos_count: Option<u32>,
+
+    /// `images`, as absolute URLs on the [`IMAGE_CDN`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<Vec<ResoMedia>>,
+
+    /// `rooms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rooms: Option<Vec<ResoRoom>>,
+
+    /// RESO fields not modelled above
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// A RESO `Media` record (a listing photo)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResoMedia {
+    /// Absolute photo URL
+    #[serde(rename = "MediaURL")]
+    pub media_url: String,
+
+    /// Position of the photo, from 0
+    #[serde(default)]
+    pub order: u32,
+
+    /// `Photo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_category: Option<String>,
+}
+
+/// A RESO `PropertyRooms` record
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResoRoom {
+    /// `description` (e.g. `Kitchen`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_type: Option<String>,
+
+    /// `level`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_level: Option<String>,
+
+    /// `length`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_length: Option<f64>,
+
+    /// `width`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_width: Option<f64>,
+
+    /// `features`, `features2` and `features3`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_features: Option<Vec<String>>,
+}
+
+impl From<&Listing> for ResoProperty {
+    fn from(listing: &Listing) -> Self {
+        to_reso(listing).record
+    }
+}
+
+/// Converts a listing into a RESO `Property` record
+pub fn to_reso(listing: &Listing) -> Mapped<ResoProperty> {
+    use UnmappedReason::*;
+
+    let mut report = MappingReport::default();
+    let Listing {
+        mls_number,
+        board_id,
+        resource,
+        status,
+        last_status,
+        class,
+        listing_type,
+        list_price,
+        original_price,
+        list_date,
+        sold_price,
+        sold_date,
+        days_on_market,
+        occupancy,
+        updated_on,
+        address,
+        map,
+        details,
+        images,
+        photo_count,
+        office,
+        agents,
+        rooms,
+        condominium,
+        taxes,
+        timestamps,
+        extra,
+    } = listing;
+
+    let mut property = ResoProperty {
+        listing_key: Some(mls_number.clone()),
+        listing_id: Some(mls_number.clone()),
+        originating_system_id: board_id.map(|id| id.to_string()),
+        mls_status: last_status.clone(),
+        list_price: *list_price,
+        original_list_price: *original_price,
+        close_price: *sold_price,
+        days_on_market: *days_on_market,
+        photos_count: *photo_count,
+        ..Default::default()
+    };
+    report.add("resource", resource, NoEquivalent);
+
+    property.standard_status =
+        StandardStatus::from_repliers(status.as_deref(), last_status.as_deref());
+    if property.standard_status.is_none() {
+        match last_status {
+            Some(code) => report.add("lastStatus", code, UnknownLookup),
+            None => report.add("status", status, UnknownLookup),
+        }
+    }
+
+    let property_type_code = details.as_ref().and_then(|d| d.property_type.as_deref());
+    property.property_type = PropertyType::from_repliers(
+        class.as_deref(),
+        listing_type.as_deref(),
+        property_type_code,
+    );
+    if property.property_type.is_none() {
+        report.add("class", class, UnknownLookup);
+    }
+
+    property.listing_contract_date = date(&mut report, "listDate", list_date);
+    property.close_date = date(&mut report, "soldDate", sold_date);
+    property.occupant_type = occupancy.as_deref().and_then(|o| {
+        let occupant = ["Owner", "Tenant", "Vacant"]
+            .into_iter()
+            .find(|v| v.eq_ignore_ascii_case(o.trim()));
+        if occupant.is_none() {
+            report.add("occupancy", o, UnknownLookup);
+        }
+        occupant.map(str::to_string)
+    });
+
+    if let Some(timestamps) = timestamps {
+        let ListingTimestamps {
+            idx_updated,
+            listing_updated,
+            photos_updated,
+            listing_entry_date,
+            conditional_expiry_date,
+            terminated_date,
+            suspended_date,
+            closed_date,
+            unavailable_date,
+            expiry_date,
+            possession_date,
+        } = timestamps;
+        property.modification_timestamp =
+            timestamp(&mut report, "timestamps.listingUpdated", listing_updated);
+        property.photos_change_timestamp =
+            timestamp(&mut report, "timestamps.photosUpdated", photos_updated);
+        property.original_entry_timestamp = timestamp(
+            &mut report,
+            "timestamps.listingEntryDate",
+            listing_entry_date,
+        );
+        property.expiration_date = date(&mut report, "timestamps.expiryDate", expiry_date);
+        property.cancellation_date =
+            date(&mut report, "timestamps.terminatedDate", terminated_date);
+        property.off_market_date =
+            date(&mut report, "timestamps.unavailableDate", unavailable_date);
+        if property.close_date.is_none() {
+            property.close_date = date(&mut report, "timestamps.closedDate", closed_date);
+        }
+        report.add("timestamps.idxUpdated", idx_updated, NoEquivalent);
+        report.add(
+            "timestamps.conditionalExpiryDate",
+            conditional_expiry_date,
+            NoEquivalent,
+        );
+        report.add("timestamps.suspendedDate", suspended_date, NoEquivalent);
+        report.add("timestamps.possessionDate", possession_date, NoEquivalent);
+    }
+    if property.modification_timestamp.is_none() {
+        property.modification_timestamp = timestamp(&mut report, "updatedOn", updated_on);
+    }
+
+    if let Some(address) = address {
+        property.unparsed_address = address
+            .street_line()
+            .map(|line| line.replace(", Unit ", " #"));
+        let Address {
+            area,
+            city,
+            country,
+            district,
+            major_intersection,
+            neighborhood,
+            street_direction_prefix,
+            street_direction,
+            street_name,
+            street_number,
+            street_suffix,
+            unit_number,
+            zip,
+            state,
+            community_code,
+        } = address;
+        property.street_number = street_number.clone();
+        property.street_dir_prefix = street_direction_prefix.clone();
+        property.street_name = street_name.clone();
+        property.street_suffix = street_suffix.clone();
+        property.street_dir_suffix = street_direction.clone();
+        property.unit_number = unit_number.clone();
+        property.city = city.clone();
+        property.state_or_province = state.clone();
+        property.postal_code = zip.clone();
+        property.mls_area_major = area.clone();
+        property.mls_area_minor = district.clone();
+        property.subdivision_name = neighborhood.clone();
+        property.cross_street = major_intersection.clone();
+        property.country = country.as_deref().and_then(|c| {
+            let code = country_code(c);
+            if code.is_none() {
+                report.add("address.country", c, UnknownLookup);
+            }
+            code.map(str::to_string)
+        });
+        report.add("address.communityCode", community_code, NoEquivalent);
+    }
+
+    if let Some(MapCoordinates {
+        latitude,
+        longitude,
+        point: _,
+    }) = map
+    {
+        property.latitude = *latitude;
+        property.longitude = *longitude;
+    }
+
+    if let Some(details) = details {
+        map_details(&mut property, &mut report, details);
+    }
+
+    if let Some(images) = images {
+        property.media = Some(
+            images
+                .iter()
+                .zip(0..)
+                .map(|(image, order)| ResoMedia {
+                    media_url: image_url(image),
+                    order,
+                    media_category: Some("Photo".to_string()),
+                })
+                .collect(),
+        );
+    }
+
+    if let Some(Office {
+        brokerage_name,
+        extra,
+    }) = office
+    {
+        property.list_office_name = brokerage_name.clone();
+        report.extra("office.", extra);
+    }
+
+    for (index, agent) in agents.iter().flatten().enumerate() {
+        map_agent(&mut property, &mut report, index, agent);
+    }
+
+    if let Some(rooms) = rooms {
+        property.rooms = Some(
+            rooms
+                .iter()
+                .enumerate()
+                .map(|(index, room)| map_room(&mut report, index, room))
+                .collect(),
+        );
+    }
+
+    if let Some(condominium) = condominium {
+        let Condominium {
+            amenities,
+            condo_corp,
+            condo_corp_num,
+            exposure,
+            locker,
+            parking_type,
+            pets,
+            property_mgr,
+            stories,
+            fees,
+            extra,
+        } = condominium;
+        property.association_amenities = amenities.clone();
+        property.association_name = condo_corp.clone();
+        property.pets_allowed = pets.as_ref().map(|p| vec![p.clone()]);
+        if let Some(CondoFees { maintenance, extra }) = fees {
+            property.association_fee = *maintenance;
+            property.association_fee_frequency = maintenance.map(|_| "Monthly".to_string());
+            report.extra("condominium.fees.", extra);
+        }
+        report.add("condominium.condoCorpNum", condo_corp_num, NoEquivalent);
+        report.add("condominium.exposure", exposure, NoEquivalent);
+        report.add("condominium.locker", locker, NoEquivalent);
+        report.add("condominium.parkingType", parking_type, NoEquivalent);
+        report.add("condominium.propertyMgr", property_mgr, NoEquivalent);
+        report.add("condominium.stories", stories, NoEquivalent);
+        report.extra("condominium.", extra);
+    }
+
+    if let Some(Taxes {
+        annual_amount,
+        assessment_year,
+    }) = taxes
+    {
+        property.tax_annual_amount = *annual_amount;
+        property.tax_year = assessment_year.as_deref().and_then(|y| {
+            let year = y.trim().parse().ok();
+            if year.is_none() {
+                report.add("taxes.assessmentYear", y, InvalidValue);
+            }
+            year
+        });
+    }
+
+    report.extra("", extra);
+
+    Mapped {
+        record: property,
+        report,
+    }
+}
+
+fn map_details(property: &mut ResoProperty, report: &mut MappingReport, details: &ListingDetails) {
+    use UnmappedReason::*;
+
+    let ListingDetails {
+        num_bedrooms,
+        num_bedrooms_plus,
+        num_bathrooms,
+        num_bathrooms_plus,
+        num_rooms,
+        sqft,
+        style,
+        property_type,
+        year_built,
+        description,
+        extras,
+        garage,
+        num_garage_spaces,
+        num_parking_spaces,
+        basement1,
+        basement2,
+        heating,
+        air_conditioning,
+        swimming_pool,
+        virtual_tour_url,
+        extra,
+    } = details;
+
+    property.bedrooms_total = match (num_bedrooms, num_bedrooms_plus) {
+        (None, None) => None,
+        (above, below) => Some(above.unwrap_or(0) + below.unwrap_or(0)),
+    };
+    property.bathrooms_total_integer = *num_bathrooms;
+    property.rooms_total = *num_rooms;
+    property.garage_spaces = *num_garage_spaces;
+    property.parking_total = *num_parking_spaces;
+    property.architectural_style = style.as_ref().map(|s| vec![s.clone()]);
+    property.public_remarks = description.clone();
+    property.inclusions = extras.clone();
+    property.virtual_tour_url_unbranded = virtual_tour_url.clone();
+    property.heating = heating.as_ref().map(|h| vec![h.clone()]);
+    property.cooling = air_conditioning.as_ref().map(|c| vec![c.clone()]);
+    property.pool_features = swimming_pool
+        .as_ref()
+        .filter(|p| !p.trim().eq_ignore_ascii_case("None"))
+        .map(|p| vec![p.clone()]);
+    let basement: Vec<String> = [basement1, basement2]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+    property.basement = (!basement.is_empty()).then_some(basement);
+    report.add("details.numBathroomsPlus", num_bathrooms_plus, NoEquivalent);
+
+    if let Some(code) = property_type {
+        match SUB_TYPES
+            .iter()
+            .find(|(repliers, _, _)| repliers.eq_ignore_ascii_case(code.trim()))
+        {
+            Some((_, sub_type, attached)) => {
+                property.property_sub_type = Some(sub_type.to_string());
+                property.property_attached_yn = *attached;
+            }
+            None => report.add("details.propertyType", code, UnknownLookup),
+        }
+    }
+
+    if let Some(sqft) = sqft {
+        let value = sqft.trim().replace(',', "");
+        if let Ok(area) = value.parse::<f64>() {
+            property.living_area = Some(area);
+            property.living_area_units = Some("SquareFeet".to_string());
+        } else if is_range(&value) {
+            property.living_area_range = Some(value);
+        } else {
+            report.add("details.sqft", sqft, InvalidValue);
+        }
+    }
+
+    if let Some(year) = year_built {
+        let value = year.trim();
+        match value.parse::<u32>() {
+            Ok(year) if (1600..=2200).contains(&year) => property.year_built = Some(year),
+            _ if !value.is_empty() => property.year_built_details = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    if let Some(garage) = garage {
+        match garage.trim().to_ascii_lowercase().as_str() {
+            "none" => property.garage_yn = Some(false),
+            "attached" | "built-in" => {
+                property.garage_yn = Some(true);
+                property.attached_garage_yn = Some(true);
+            }
+            "detached" => {
+                property.garage_yn = Some(true);
+                property.attached_garage_yn = Some(false);
+            }
+            _ => report.add("details.garage", garage, UnknownLookup),
+        }
+    }
+
+    report.extra("details.", extra);
+}
+
+fn map_agent(property: &mut ResoProperty, report: &mut MappingReport, index: usize, agent: &Agent) {
+    use UnmappedReason::*;
+
+    let Agent {
+        agent_id,
+        board_agent_id,
+        office_id,
+        name,
+        position,
+        email,
+        phones,
+        website,
+        extra,
+    } = agent;
+    let prefix = format!("agents.{}.", index);
+    let phone = phones.as_ref().and_then(|p| p.first()).cloned();
+    let fields = match index {
+        0 => Some((
+            &mut property.list_agent_key,
+            &mut property.list_agent_mls_id,
+            &mut property.list_office_key,
+            &mut property.list_agent_full_name,
+            &mut property.list_agent_email,
+            &mut property.list_agent_direct_phone,
+            &mut property.list_agent_url,
+        )),
+        1 => Some((
+            &mut property.co_list_agent_key,
+            &mut property.co_list_agent_mls_id,
+            &mut property.co_list_office_key,
+            &mut property.co_list_agent_full_name,
+            &mut property.co_list_agent_email,
+            &mut property.co_list_agent_direct_phone,
+            &mut property.co_list_agent_url,
+        )),
+        _ => None,
+    };
+    let Some((key, mls_id, office, full_name, agent_email, direct_phone, url)) = fields else {
+        report.add(format!("agents.{}", index), agent, NoEquivalent);
+        return;
+    };
+    *key = agent_id.clone();
+    *mls_id = board_agent_id.clone();
+    *office = office_id.clone();
+    *full_name = name.clone();
+    *agent_email = email.clone();
+    *direct_phone = phone;
+    *url = website.clone();
+
+    report.add(format!("{}position", prefix), position, NoEquivalent);
+    for (n, other) in phones.iter().flatten().enumerate().skip(1) {
+        report.add(format!("{}phones.{}", prefix, n), other, NoEquivalent);
+    }
+    report.extra(&prefix, extra);
+}
+
+fn map_room(report: &mut MappingReport, index: usize, room: &Room) -> ResoRoom {
+    let Room {
+        description,
+        level,
+        length,
+        width,
+        features,
+        features2,
+        features3,
+    } = room;
+    let mut dimension = |field: &str, value: &Option<String>| {
+        let value = value.as_deref()?;
+        let parsed = value.trim().parse::<f64>().ok();
+        if parsed.is_none() {
+            report.add(
+                format!("rooms.{}.{}", index, field),
+                value,
+                UnmappedReason::InvalidValue,
+            );
+        }
+        parsed
+    };
+    let room_length = dimension("length", length);
+    let room_width = dimension("width", width);
+    let room_features: Vec<String> = [features, features2, features3]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+    ResoRoom {
+        room_type: description.clone(),
+        room_level: level.clone(),
+        room_length,
+        room_width,
+        room_features: (!room_features.is_empty()).then_some(room_features),
+    }
+}
+
+/// Converts a RESO `Property` record into a listing
+///
+/// Values that RESO normalizes are mapped back to the first matching Repliers code, so
+/// a listing that goes through [`to_reso`] and back may use different (equivalent)
+/// codes, and fields such as the split of `numBedrooms`/`numBedroomsPlus` are lost.
+pub fn from_reso(property: &ResoProperty) -> Mapped<Listing> {
+    use UnmappedReason::*;
+
+    let mut report = MappingReport::default();
+    let ResoProperty {
+        listing_key,
+        listing_id,
+        originating_system_id,
+        standard_status,
+        mls_status,
+        property_type,
+        property_sub_type,
+        property_attached_yn,
+        list_price,
+        original_list_price,
+        close_price,
+        listing_contract_date,
+        close_date,
+        expiration_date,
+        cancellation_date,
+        off_market_date,
+        original_entry_timestamp,
+        modification_timestamp,
+        photos_change_timestamp,
+        days_on_market,
+        occupant_type,
+        unparsed_address,
+        street_number,
+        street_dir_prefix,
+        street_name,
+        street_suffix,
+        street_dir_suffix,
+        unit_number,
+        city,
+        state_or_province,
+        postal_code,
+        country,
+        mls_area_major,
+        mls_area_minor,
+        subdivision_name,
+        cross_street,
+        latitude,
+        longitude,
+        bedrooms_total,
+        bathrooms_total_integer,
+        rooms_total,
+        living_area,
+        living_area_units,
+        living_area_range,
+        year_built,
+        year_built_details,
+        architectural_style,
+        public_remarks,
+        inclusions,
+        garage_yn,
+        attached_garage_yn,
+        garage_spaces,
+        parking_total,
+        basement,
+        heating,
+        cooling,
+        pool_features,
+        virtual_tour_url_unbranded,
+        list_office_name,
+        list_office_key,
+        list_agent_key,
+        list_agent_mls_id,
+        list_agent_full_name,
+        list_agent_email,
+        list_agent_direct_phone,
+        list_agent_url,
+        co_list_office_key,
+        co_list_agent_key,
+        co_list_agent_mls_id,
+        co_list_agent_full_name,
+        co_list_agent_email,
+        co_list_agent_direct_phone,
+        co_list_agent_url,
+        association_fee,
+        association_fee_frequency,
+        association_name,
+        association_amenities,
+        pets_allowed,
+        tax_annual_amount,
+        tax_year,
+        photos_count,
+        media,
+        rooms,
+        extra,
+    } = property;
+
+    let mut listing = Listing {
+        mls_number: listing_id
+            .clone()
+            .or_else(|| listing_key.clone())
+            .unwrap_or_default(),
+        list_price: *list_price,
+        original_price: *original_list_price,
+        sold_price: *close_price,
+        days_on_market: *days_on_market,
+        occupancy: occupant_type.clone(),
+        photo_count: *photos_count,
+        list_date: listing_contract_date.map(format_date),
+        sold_date: close_date.map(format_date),
+        updated_on: modification_timestamp.map(format_timestamp),
+        ..Default::default()
+    };
+    if listing.mls_number.is_empty() {
+        report.add("ListingId", Value::String(String::new()), InvalidValue);
+    }
+
+    listing.board_id = originating_system_id.as_deref().and_then(|id| {
+        let board_id = id.trim().parse().ok();
+        if board_id.is_none() {
+            report.add("OriginatingSystemID", id, InvalidValue);
+        }
+        board_id
+    });
+
+    let sub_type = property_sub_type.as_deref().and_then(|sub_type| {
+        let found = SUB_TYPES
+            .iter()
+            .filter(|(_, reso, _)| reso.eq_ignore_ascii_case(sub_type.trim()))
+            .find(|(_, _, attached)| {
+                property_attached_yn.is_none() || *attached == *property_attached_yn
+            })
+            .or_else(|| {
+                SUB_TYPES
+                    .iter()
+                    .find(|(_, reso, _)| reso.eq_ignore_ascii_case(sub_type.trim()))
+            });
+        if found.is_none() {
+            report.add("PropertySubType", sub_type, UnknownLookup);
+        }
+        found
+    });
+
+    let lease = matches!(
+        property_type,
+        Some(PropertyType::ResidentialLease | PropertyType::CommercialLease)
+    );
+    if let Some(property_type) = property_type {
+        let (class, listing_type) = property_type.to_repliers();
+        let condo = sub_type.is_some_and(|(_, reso, _)| CONDO_SUB_TYPES.contains(reso));
+        listing.class = Some(if condo && class == "ResidentialProperty" {
+            "CondoProperty".to_string()
+        } else {
+            class.to_string()
+        });
+        listing.listing_type = Some(listing_type.to_string());
+    }
+
+    if let Some(standard_status) = standard_status {
+        match standard_status.to_repliers(lease) {
+            Some((status, last_status)) => {
+                listing.status = Some(status.to_string());
+                listing.last_status = Some(mls_status.clone().unwrap_or(last_status.to_string()));
+            }
+            None => {
+                report.add("StandardStatus", standard_status, UnknownLookup);
+                listing.last_status = mls_status.clone();
+            }
+        }
+    } else {
+        listing.last_status = mls_status.clone();
+    }
+
+    let timestamps = ListingTimestamps {
+        listing_updated: modification_timestamp.map(format_timestamp),
+        photos_updated: photos_change_timestamp.map(format_timestamp),
+        listing_entry_date: original_entry_timestamp.map(format_timestamp),
+        expiry_date: expiration_date.map(format_date),
+        terminated_date: cancellation_date.map(format_date),
+        unavailable_date: off_market_date.map(format_date),
+        closed_date: close_date.map(format_date),
+        ..Default::default()
+    };
+    listing.timestamps = (timestamps != ListingTimestamps::default()).then_some(timestamps);
+
+    let address = Address {
+        area: mls_area_major.clone(),
+        city: city.clone(),
+        country: country
+            .as_deref()
+            .map(|c| country_name(c).unwrap_or(c).to_string()),
+        district: mls_area_minor.clone(),
+        major_intersection: cross_street.clone(),
+        neighborhood: subdivision_name.clone(),
+        street_direction_prefix: street_dir_prefix.clone(),
+        street_direction: street_dir_suffix.clone(),
+        street_name: street_name.clone(),
+        street_number: street_number.clone(),
+        street_suffix: street_suffix.clone(),
+        unit_number: unit_number.clone(),
+        zip: postal_code.clone(),
+        state: state_or_province.clone(),
+        community_code: None,
+    };
+    if address.street_number.is_none() && address.street_name.is_none() {
+        report.add("UnparsedAddress", unparsed_address, NoEquivalent);
+    }
+    listing.address = (address != Address::default()).then_some(address);
+
+    if latitude.is_some() || longitude.is_some() {
+        listing.map = Some(MapCoordinates {
+            latitude: *latitude,
+            longitude: *longitude,
+            point: None,
+        });
+    }
+
+    let sqft = match (living_area_range, living_area) {
+        (Some(range), _) => Some(range.clone()),
+        (None, Some(area)) => match living_area_units.as_deref().map(str::trim) {
+            None | Some("SquareFeet") => Some(format!("{}", area.round())),
+            Some("SquareMeters") => Some(format!("{}", (area * 10.763_910_4).round())),
+            Some(units) => {
+                report.add("LivingAreaUnits", units, UnknownLookup);
+                None
+            }
+        },
+        (None, None) => None,
+    };
+    let garage = match (garage_yn, attached_garage_yn) {
+        (Some(false), _) => Some("None"),
+        (_, Some(true)) => Some("Attached"),
+        (_, Some(false)) => Some("Detached"),
+        _ => None,
+    };
+    let mut basement = basement.iter().flatten();
+    let details = ListingDetails {
+        num_bedrooms: *bedrooms_total,
+        num_bathrooms: *bathrooms_total_integer,
+        num_rooms: *rooms_total,
+        sqft,
+        style: join(architectural_style),
+        property_type: sub_type.map(|(repliers, _, _)| repliers.to_string()),
+        year_built: year_built
+            .map(|y| y.to_string())
+            .or_else(|| year_built_details.clone()),
+        description: public_remarks.clone(),
+        extras: inclusions.clone(),
+        garage: garage.map(str::to_string),
+        num_garage_spaces: *garage_spaces,
+        num_parking_spaces: *parking_total,
+        basement1: basement.next().cloned(),
+        basement2: basement.next().cloned(),
+        heating: join(heating),
+        air_conditioning: join(cooling),
+        swimming_pool: join(pool_features),
+        virtual_tour_url: virtual_tour_url_unbranded.clone(),
+        ..Default::default()
+    };
+    for (n, extra_basement) in basement.enumerate() {
+        report.add(format!("Basement.{}", n + 2), extra_basement, NoEquivalent);
+    }
+    listing.details = (details != ListingDetails::default()).then_some(details);
+
+    listing.office = list_office_name.as_ref().map(|name| Office {
+        brokerage_name: Some(name.clone()),
+        ..Default::default()
+    });
+
+    let agents: Vec<Agent> = [
+        (
+            list_agent_key,
+            list_agent_mls_id,
+            list_office_key,
+            list_agent_full_name,
+            list_agent_email,
+            list_agent_direct_phone,
+            list_agent_url,
+        ),
+        (
+            co_list_agent_key,
+            co_list_agent_mls_id,
+            co_list_office_key,
+            co_list_agent_full_name,
+            co_list_agent_email,
+            co_list_agent_direct_phone,
+            co_list_agent_url,
+        ),
+    ]
+    .into_iter()
+    .map(|(key, mls_id, office, name, email, phone, url)| Agent {
+        agent_id: key.clone(),
+        board_agent_id: mls_id.clone(),
+        office_id: office.clone(),
+        name: name.clone(),
+        email: email.clone(),
+        phones: phone.as_ref().map(|p| vec![p.clone()]),
+        website: url.clone(),
+        ..Default::default()
+    })
+    .filter(|agent| *agent != Agent::default())
+    .collect();
+    listing.agents = (!agents.is_empty()).then_some(agents);
+
+    if association_fee.is_some()
+        || association_name.is_some()
+        || association_amenities.is_some()
+        || pets_allowed.is_some()
+    {
+        if let Some(frequency) = association_fee_frequency
+            .as_deref()
+            .filter(|f| !f.trim().eq_ignore_ascii_case("Monthly"))
+        {
+            report.add("AssociationFeeFrequency", frequency, UnknownLookup);
+        }
+        listing.condominium = Some(Condominium {
+            amenities: association_amenities.clone(),
+            condo_corp: association_name.clone(),
+            pets: join(pets_allowed),
+            fees: association_fee.map(|fee| CondoFees {
+                maintenance: Some(fee),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+    }
+
+    if tax_annual_amount.is_some() || tax_year.is_some() {
+        listing.taxes = Some(Taxes {
+            annual_amount: *tax_annual_amount,
+            assessment_year: tax_year.map(|y| y.to_string()),
+        });
+    }
+
+    listing.images = media.as_ref().map(|media| {
+        let mut media: Vec<&ResoMedia> = media.iter().collect();
+        media.sort_by_key(|m| m.order);
+        media
+            .into_iter()
+            .map(|m| {
+                m.media_url
+                    .strip_prefix(IMAGE_CDN)
+                    .unwrap_or(&m.media_url)
+                    .to_string()
+            })
+            .collect()
+    });
+
+    listing.rooms = rooms.as_ref().map(|rooms| {
+        rooms
+            .iter()
+            .map(|room| {
+                let mut features = room.room_features.iter().flatten().cloned();
+                Room {
+                    description: room.room_type.clone(),
+                    level: room.room_level.clone(),
+                    length: room.room_length.map(|l| l.to_string()),
+                    width: room.room_width.map(|w| w.to_string()),
+                    features: features.next(),
+                    features2: features.next(),
+                    features3: features.next(),
+                }
+            })
+            .collect()
+    });
+
+    report.extra("", extra);
+
+    Mapped {
+        record: listing,
+        report,
+    }
+}
+
+fn date(report: &mut MappingReport, field: &str, value: &Option<String>) -> Option<NaiveDate> {
+    timestamp(report, field, value).map(|dt| dt.date_naive())
+}
+
+fn timestamp(
+    report: &mut MappingReport,
+    field: &str,
+    value: &Option<String>,
+) -> Option<DateTime<Utc>> {
+    let value = value.as_deref()?;
+    let parsed = parse_timestamp(value);
+    if parsed.is_none() {
+        report.add(field, value, UnmappedReason::InvalidValue);
+    }
+    parsed
+}
+
+/// Formats a date the way the API sends `listDate` and `soldDate`
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%dT00:00:00.000Z").to_string()
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+fn is_range(value: &str) -> bool {
+    value.split_once('-').is_some_and(|(low, high)| {
+        low.trim().parse::<f64>().is_ok() && high.trim().parse::<f64>().is_ok()
+    })
+}
+
+fn join(values: &Option<Vec<String>>) -> Option<String> {
+    values
+        .as_ref()
+        .filter(|v| !v.is_empty())
+        .map(|v| v.join(", "))
+}
+
+fn image_url(image: &str) -> String {
+    if image.starts_with("http://") || image.starts_with("https://") {
+        image.to_string()
+    } else {
+        format!("{}{}", IMAGE_CDN, image.trim_start_matches('/'))
+    }
+}
+
+fn country_code(country: &str) -> Option<&'static str> {
+    match country.trim().to_ascii_lowercase().as_str() {
+        "canada" | "ca" | "can" => Some("CA"),
+        "united states" | "usa" | "us" => Some("US"),
+        _ => None,
+    }
+}
+
+fn country_name(code: &str) -> Option<&'static str> {
+    match code.trim().to_ascii_uppercase().as_str() {
+        "CA" => Some("Canada"),
+        "US" => Some("United States"),
+        _ => None,
+    }
+}
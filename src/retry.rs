@@ -0,0 +1,214 @@
+//! Retry policy for transient API failures
+//!
+//! Requests that fail with a retryable HTTP status (429 and most 5xx responses by default)
+//! or a transient transport error are retried with exponential backoff. A `Retry-After`
+//! header on the response takes precedence over the computed backoff.
+
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+/// Configures how the client retries failed requests
+///
+/// Only idempotent GET requests are retried by default. The POST `/listings` search is
+/// read-only and can be opted in with [`retry_search`](Self::retry_search); the
+/// `/nlp` endpoint is never retried.
+///
+/// # Examples
+///
+/// ```no_run
+/// use repliers_beta::{RepliersClient, RetryPolicy};
+/// use std::time::Duration;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let policy = RetryPolicy::default()
+///     .max_attempts(5)
+///     .base_delay(Duration::from_millis(250))
+///     .retry_search(true);
+///
+/// let client = RepliersClient::builder()
+///     .api_key("your_api_key")
+///     .retry_policy(policy)
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+
+    /// Delay before the first retry; later retries double it
+    pub base_delay: Duration,
+
+    /// Upper bound for any single delay, including `Retry-After` values
+    pub max_delay: Duration,
+
+    /// Whether to randomize computed backoff delays
+    pub jitter: bool,
+
+    /// HTTP status codes that trigger a retry
+    pub retry_statuses: Vec<StatusCode>,
+
+    /// Retry requests that timed out
+    pub retry_on_timeout: bool,
+
+    /// Retry requests that failed to connect
+    pub retry_on_connect: bool,
+
+    /// Retry requests that failed while being sent (e.g. connection reset)
+    pub retry_on_request: bool,
+
+    /// Honour the `Retry-After` header on retryable responses
+    pub respect_retry_after: bool,
+
+    /// Retry the POST `/listings` search
+    pub retry_search: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retry_statuses: vec![
+                StatusCode::REQUEST_TIMEOUT,
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_on_timeout: true,
+            retry_on_connect: true,
+            retry_on_request: false,
+            respect_retry_after: true,
+            retry_search: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the total number of attempts, including the first one (minimum 1)
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry; later retries double it
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Sets the upper bound for any single delay, including `Retry-After` values
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Enables or disables random jitter on computed backoff delays
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Replaces the set of HTTP status codes that trigger a retry
+    pub fn retry_statuses(mut self, statuses: Vec<StatusCode>) -> Self {
+        self.retry_statuses = statuses;
+        self
+    }
+
+    /// Retries requests that timed out
+    pub fn retry_on_timeout(mut self, retry: bool) -> Self {
+        self.retry_on_timeout = retry;
+        self
+    }
+
+    /// Retries requests that failed to connect
+    pub fn retry_on_connect(mut self, retry: bool) -> Self {
+        self.retry_on_connect = retry;
+        self
+    }
+
+    /// Retries requests that failed while being sent (e.g. connection reset)
+    pub fn retry_on_request(mut self, retry: bool) -> Self {
+        self.retry_on_request = retry;
+        self
+    }
+
+    /// Honours the `Retry-After` header on retryable responses
+    pub fn respect_retry_after(mut self, respect: bool) -> Self {
+        self.respect_retry_after = respect;
+        self
+    }
+
+    /// Opts the POST `/listings` search into retries
+    pub fn retry_search(mut self, retry: bool) -> Self {
+        self.retry_search = retry;
+        self
+    }
+
+    /// Returns whether a response with this status should be retried
+    pub fn is_retryable_status(&self, status: StatusCode) -> bool {
+        self.retry_statuses.contains(&status)
+    }
+
+    /// Returns whether a transport error should be retried
+    pub fn is_retryable_error(&self, error: &reqwest::Error) -> bool {
+        (self.retry_on_timeout && error.is_timeout())
+            || (self.retry_on_connect && error.is_connect())
+            || (self.retry_on_request && error.is_request())
+    }
+
+    /// Computes the delay before the given retry (1 = first retry)
+    ///
+    /// Uses the `Retry-After` header when present and enabled, otherwise exponential
+    /// backoff. The result never exceeds the configured maximum delay.
+    pub fn delay_for(&self, retry: u32, headers: Option<&HeaderMap>) -> Duration {
+        if self.respect_retry_after {
+            if let Some(delay) = headers.and_then(parse_retry_after) {
+                return delay.min(self.max_delay);
+            }
+        }
+
+        let exponent = retry.saturating_sub(1).min(31);
+        let backoff = self
+            .base_delay
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_delay);
+
+        if self.jitter && !backoff.is_zero() {
+            // Equal jitter: keep half of the delay, randomize the other half
+            let half = backoff / 2;
+            let spread = rand::thread_rng().gen_range(0..=half.as_millis() as u64);
+            half + Duration::from_millis(spread)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Parses a `Retry-After` header given either in seconds or as an HTTP date
+pub fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delta = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(delta.to_std().unwrap_or(Duration::ZERO))
+}
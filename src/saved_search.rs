@@ -0,0 +1,674 @@
+//! Saved searches with new-match alerts
+//!
+//! A [`SavedSearch`] names a [`ListingSearchRequest`] and says who owns it and where its
+//! alerts go. Searches live in a [`SavedSearchRegistry`], a JSON file that also remembers
+//! the listings each search returned last time.
+//!
+//! [`SavedSearchRunner`] executes every registered search, compares the results with the
+//! previous run and builds a [`SearchDigest`] of the new and changed matches only. Non-empty
+//! digests are delivered through a [`Notifier`] chosen by the search's [`NotifyChannel`]:
+//! stdout, a file, a generic JSON webhook, or any custom notifier registered by name.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use repliers_beta::saved_search::{
+//!     NotifyChannel, SavedSearch, SavedSearchRegistry, SavedSearchRunner,
+//! };
+//! use repliers_beta::{ListingSearchRequest, RepliersClient};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut registry = SavedSearchRegistry::load("saved_searches.json")?;
+//! registry.insert(SavedSearch {
+//!     name: "toronto-3bed".to_string(),
+//!     request: ListingSearchRequest::builder()
+//!         .city("Toronto")
+//!         .min_bedrooms(3)
+//!         .max_price(1_200_000.0)
+//!         .build(),
+//!     owner: Some("sam@example.com".to_string()),
+//!     notify: NotifyChannel::Stdout,
+//!     max_results: Some(200),
+//! });
+//!
+//! let mut runner = SavedSearchRunner::new(RepliersClient::from_env()?, registry);
+//! let report = runner.run_all().await?;
+//! println!("{} digest(s) sent", report.digests.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::RepliersApi;
+use crate::diff::{diff_listing, DiffOptions, ListingChange};
+use crate::models::{Listing, ListingSearchRequest};
+use crate::pagination::{search_stream, SearchStreamOptions};
+use crate::storage::{storage_error, write_atomic};
+use crate::{RepliersClient, RepliersError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Where a saved search's alerts are delivered
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum NotifyChannel {
+    /// Print digests to standard output
+    #[default]
+    Stdout,
+
+    /// Append text digests to a file
+    File {
+        /// File to append to
+        path: PathBuf,
+    },
+
+    /// POST digests as JSON to a URL
+    Webhook {
+        /// Endpoint receiving the digest
+        url: String,
+    },
+
+    /// Use a notifier registered on the runner under this name
+    Custom {
+        /// Name passed to [`SavedSearchRunner::register_notifier`]
+        name: String,
+    },
+}
+
+/// A named set of search criteria
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearch {
+    /// Unique name of the search
+    pub name: String,
+
+    /// Criteria to run; pagination is handled by the runner
+    pub request: ListingSearchRequest,
+
+    /// Who the search belongs to (e.g. a buyer or agent email)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+
+    /// Where alerts are delivered
+    #[serde(default)]
+    pub notify: NotifyChannel,
+
+    /// Upper bound on the listings fetched per run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_results: Option<usize>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct RegistryFile {
+    #[serde(default)]
+    searches: Vec<SavedSearch>,
+
+    #[serde(default)]
+    seen: BTreeMap<String, SeenState>,
+}
+
+/// Listings a search returned on its last successful run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SeenState {
+    last_run: Option<DateTime<Utc>>,
+    listings: BTreeMap<String, Listing>,
+}
+
+/// Persistent collection of saved searches and their seen listings
+#[derive(Debug, Clone, Default)]
+pub struct SavedSearchRegistry {
+    searches: BTreeMap<String, SavedSearch>,
+    seen: BTreeMap<String, SeenState>,
+    path: Option<PathBuf>,
+}
+
+impl SavedSearchRegistry {
+    /// Creates an empty registry kept in memory only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a registry from a JSON file, starting empty if it does not exist
+    ///
+    /// [`save`](Self::save) writes back to the same file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file exists but cannot be read or parsed.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, RepliersError> {
+        let path = path.into();
+        let file: RegistryFile = match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                serde_json::from_str(&content).map_err(|e| storage_error(path.display(), e))?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => RegistryFile::default(),
+            Err(e) => return Err(storage_error(path.display(), e)),
+        };
+        Ok(Self {
+            searches: file
+                .searches
+                .into_iter()
+                .map(|search| (search.name.clone(), search))
+                .collect(),
+            seen: file.seen,
+            path: Some(path),
+        })
+    }
+
+    /// Writes the registry to its file, if it was loaded from one
+    ///
+    /// The file is written to a temporary path and renamed over the original.
+    pub fn save(&self) -> Result<(), RepliersError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let file = RegistryFile {
+            searches: self.searches.values().cloned().collect(),
+            seen: self.seen.clone(),
+        };
+        let content =
+            serde_json::to_string_pretty(&file).map_err(|e| storage_error(path.display(), e))?;
+        write_atomic(path, content)
+    }
+
+    /// Adds or replaces a search, returning the previous one with the same name
+    ///
+    /// Replacing a search keeps its seen listings.
+    pub fn insert(&mut self, search: SavedSearch) -> Option<SavedSearch> {
+        self.searches.insert(search.name.clone(), search)
+    }
+
+    /// Removes a search and forgets its seen listings
+    pub fn remove(&mut self, name: &str) -> Option<SavedSearch> {
+        self.seen.remove(name);
+        self.searches.remove(name)
+    }
+
+    /// Returns the search with the given name
+    pub fn get(&self, name: &str) -> Option<&SavedSearch> {
+        self.searches.get(name)
+    }
+
+    /// Iterates over the searches in name order
+    pub fn iter(&self) -> impl Iterator<Item = &SavedSearch> {
+        self.searches.values()
+    }
+
+    /// Returns the number of saved searches
+    pub fn len(&self) -> usize {
+        self.searches.len()
+    }
+
+    /// Returns `true` if no searches are saved
+    pub fn is_empty(&self) -> bool {
+        self.searches.is_empty()
+    }
+
+    /// Returns when a search last ran successfully
+    pub fn last_run(&self, name: &str) -> Option<DateTime<Utc>> {
+        self.seen.get(name)?.last_run
+    }
+
+    /// Forgets the listings a search has seen, so its next run reports every match as new
+    pub fn reset_seen(&mut self, name: &str) {
+        self.seen.remove(name);
+    }
+}
+
+/// A listing that matched before and has since changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChangedMatch {
+    /// The listing as just returned
+    pub listing: Listing,
+
+    /// What changed since the previous run
+    pub changes: Vec<ListingChange>,
+}
+
+/// New and changed matches of one saved search run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchDigest {
+    /// Name of the saved search
+    pub search: String,
+
+    /// Owner of the saved search
+    pub owner: Option<String>,
+
+    /// When the search ran
+    pub run_at: DateTime<Utc>,
+
+    /// Total listings matching in this run
+    pub total_matches: usize,
+
+    /// Listings not returned by the previous run
+    pub new_matches: Vec<Listing>,
+
+    /// Listings returned before whose price or status changed
+    pub changed_matches: Vec<ChangedMatch>,
+}
+
+impl SearchDigest {
+    /// Returns `true` if there is nothing to report
+    pub fn is_empty(&self) -> bool {
+        self.new_matches.is_empty() && self.changed_matches.is_empty()
+    }
+
+    /// Renders the digest as plain text
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "Saved search \"{}\" - {} new, {} changed ({} total matches, {})",
+            self.search,
+            self.new_matches.len(),
+            self.changed_matches.len(),
+            self.total_matches,
+            self.run_at.format("%Y-%m-%d %H:%M UTC")
+        );
+        if !self.new_matches.is_empty() {
+            let _ = writeln!(out, "\nNew listings:");
+            for listing in &self.new_matches {
+                let _ = writeln!(out, "  - {}", describe(listing));
+            }
+        }
+        if !self.changed_matches.is_empty() {
+            let _ = writeln!(out, "\nChanged listings:");
+            for changed in &self.changed_matches {
+                let _ = writeln!(out, "  - {}", describe(&changed.listing));
+                for change in &changed.changes {
+                    let _ = writeln!(out, "      {}", describe_change(change));
+                }
+            }
+        }
+        out
+    }
+
+    /// Renders the digest as a small HTML fragment, e.g. for an email body
+    pub fn render_html(&self) -> String {
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "<h2>Saved search &ldquo;{}&rdquo;</h2>\n<p>{} new, {} changed ({} total matches)</p>\n",
+            escape_html(&self.search),
+            self.new_matches.len(),
+            self.changed_matches.len(),
+            self.total_matches
+        );
+        if !self.new_matches.is_empty() {
+            out.push_str("<h3>New listings</h3>\n<ul>\n");
+            for listing in &self.new_matches {
+                let _ = writeln!(out, "<li>{}</li>", escape_html(&describe(listing)));
+            }
+            out.push_str("</ul>\n");
+        }
+        if !self.changed_matches.is_empty() {
+            out.push_str("<h3>Changed listings</h3>\n<ul>\n");
+            for changed in &self.changed_matches {
+                let _ = write!(out, "<li>{}<ul>", escape_html(&describe(&changed.listing)));
+                for change in &changed.changes {
+                    let _ = write!(out, "<li>{}</li>", escape_html(&describe_change(change)));
+                }
+                out.push_str("</ul></li>\n");
+            }
+            out.push_str("</ul>\n");
+        }
+        out
+    }
+}
+
+/// Delivers saved search digests
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Delivers one non-empty digest
+    async fn notify(&self, digest: &SearchDigest) -> Result<(), RepliersError>;
+}
+
+/// Prints text digests to standard output
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutNotifier;
+
+#[async_trait]
+impl Notifier for StdoutNotifier {
+    async fn notify(&self, digest: &SearchDigest) -> Result<(), RepliersError> {
+        println!("{}", digest.render_text());
+        Ok(())
+    }
+}
+
+/// Appends text digests to a file
+#[derive(Debug, Clone)]
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    /// Creates a notifier appending to the given file (created if missing)
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl Notifier for FileNotifier {
+    async fn notify(&self, digest: &SearchDigest) -> Result<(), RepliersError> {
+        use tokio::io::AsyncWriteExt;
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| storage_error(self.path.display(), e))?;
+        let text = format!("{}\n", digest.render_text());
+        file.write_all(text.as_bytes())
+            .await
+            .map_err(|e| storage_error(self.path.display(), e))
+    }
+}
+
+/// POSTs digests as JSON to a URL
+///
+/// The body is the serialized [`SearchDigest`] plus `text` and `html` renderings.
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    /// Creates a notifier posting to the given URL
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::with_client(reqwest::Client::new(), url)
+    }
+
+    /// Creates a notifier using an existing HTTP client
+    pub fn with_client(client: reqwest::Client, url: impl Into<String>) -> Self {
+        Self {
+            client,
+            url: url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, digest: &SearchDigest) -> Result<(), RepliersError> {
+        #[derive(Serialize)]
+        struct Payload<'a> {
+            #[serde(flatten)]
+            digest: &'a SearchDigest,
+            text: String,
+            html: String,
+        }
+
+        self.client
+            .post(&self.url)
+            .json(&Payload {
+                digest,
+                text: digest.render_text(),
+                html: digest.render_html(),
+            })
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Outcome of running the saved searches
+#[derive(Debug, Default)]
+pub struct RunReport {
+    /// Digests with new or changed matches (already delivered)
+    pub digests: Vec<SearchDigest>,
+
+    /// Number of searches that ran without anything to report
+    pub unchanged: usize,
+
+    /// Searches that failed to run or notify, with the error
+    pub failures: Vec<(String, RepliersError)>,
+}
+
+/// Runs saved searches and delivers digests of new and changed matches
+///
+/// Searches run through any [`RepliersApi`]: a [`RepliersClient`] by default, or an
+/// [`InMemoryApi`](crate::InMemoryApi) in tests.
+pub struct SavedSearchRunner<A = RepliersClient> {
+    api: A,
+    registry: SavedSearchRegistry,
+    notifiers: HashMap<String, Arc<dyn Notifier>>,
+    diff: DiffOptions,
+    page_size: u32,
+}
+
+impl<A: RepliersApi> SavedSearchRunner<A> {
+    /// Creates a runner over a registry
+    ///
+    /// Only price and status changes count as changed matches by default; see
+    /// [`diff_options`](Self::diff_options).
+    pub fn new(api: A, registry: SavedSearchRegistry) -> Self {
+        Self {
+            api,
+            registry,
+            notifiers: HashMap::new(),
+            diff: DiffOptions::default().field_changes(false),
+            page_size: 100,
+        }
+    }
+
+    /// Registers a notifier for [`NotifyChannel::Custom`] searches with this name
+    pub fn register_notifier(
+        &mut self,
+        name: impl Into<String>,
+        notifier: impl Notifier + 'static,
+    ) {
+        self.notifiers.insert(name.into(), Arc::new(notifier));
+    }
+
+    /// Replaces the options deciding what counts as a changed match
+    pub fn diff_options(mut self, options: DiffOptions) -> Self {
+        self.diff = options;
+        self
+    }
+
+    /// Sets the results requested per page (default 100)
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Returns the registry
+    pub fn registry(&self) -> &SavedSearchRegistry {
+        &self.registry
+    }
+
+    /// Returns the registry for adding or removing searches
+    pub fn registry_mut(&mut self) -> &mut SavedSearchRegistry {
+        &mut self.registry
+    }
+
+    /// Consumes the runner, returning the registry
+    pub fn into_registry(self) -> SavedSearchRegistry {
+        self.registry
+    }
+
+    /// Runs every saved search, notifies and saves the registry
+    ///
+    /// A search's seen listings are updated only after its digest is delivered, so a
+    /// failed notification is retried on the next run.
+    ///
+    /// # Errors
+    ///
+    /// Individual search failures are collected in the report; an error is returned
+    /// only if the registry cannot be saved.
+    pub async fn run_all(&mut self) -> Result<RunReport, RepliersError> {
+        let mut report = RunReport::default();
+        let searches: Vec<SavedSearch> = self.registry.iter().cloned().collect();
+
+        for search in searches {
+            match self.run_search(&search).await {
+                Ok(Some(digest)) => report.digests.push(digest),
+                Ok(None) => report.unchanged += 1,
+                Err(e) => report.failures.push((search.name.clone(), e)),
+            }
+        }
+
+        self.registry.save()?;
+        Ok(report)
+    }
+
+    /// Runs one saved search by name and notifies if anything is new or changed
+    ///
+    /// Does not save the registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::InvalidRequest`] for unknown names, or the search or
+    /// notification error.
+    pub async fn run(&mut self, name: &str) -> Result<Option<SearchDigest>, RepliersError> {
+        let search = self.registry.get(name).cloned().ok_or_else(|| {
+            RepliersError::InvalidRequest(format!("Unknown saved search: {}", name))
+        })?;
+        self.run_search(&search).await
+    }
+
+    async fn run_search(
+        &mut self,
+        search: &SavedSearch,
+    ) -> Result<Option<SearchDigest>, RepliersError> {
+        let options = SearchStreamOptions {
+            page_size: Some(self.page_size),
+            max_results: search.max_results,
+            ..Default::default()
+        };
+        let listings: Vec<Listing> = search_stream(&self.api, search.request.clone(), options)
+            .try_collect()
+            .await?;
+
+        let run_at = Utc::now();
+        let previous = self.registry.seen.get(&search.name);
+        let mut digest = SearchDigest {
+            search: search.name.clone(),
+            owner: search.owner.clone(),
+            run_at,
+            total_matches: listings.len(),
+            new_matches: Vec::new(),
+            changed_matches: Vec::new(),
+        };
+        for listing in &listings {
+            match previous.and_then(|seen| seen.listings.get(&listing.mls_number)) {
+                None => digest.new_matches.push(listing.clone()),
+                Some(old) => {
+                    let changes = diff_listing(old, listing, &self.diff);
+                    if !changes.is_empty() {
+                        digest.changed_matches.push(ChangedMatch {
+                            listing: listing.clone(),
+                            changes,
+                        });
+                    }
+                }
+            }
+        }
+
+        if !digest.is_empty() {
+            self.notifier_for(&search.notify)?.notify(&digest).await?;
+        }
+
+        self.registry.seen.insert(
+            search.name.clone(),
+            SeenState {
+                last_run: Some(run_at),
+                listings: listings
+                    .into_iter()
+                    .map(|listing| (listing.mls_number.clone(), listing))
+                    .collect(),
+            },
+        );
+
+        Ok((!digest.is_empty()).then_some(digest))
+    }
+
+    fn notifier_for(&self, channel: &NotifyChannel) -> Result<Arc<dyn Notifier>, RepliersError> {
+        Ok(match channel {
+            NotifyChannel::Stdout => Arc::new(StdoutNotifier),
+            NotifyChannel::File { path } => Arc::new(FileNotifier::new(path)),
+            NotifyChannel::Webhook { url } => Arc::new(WebhookNotifier::new(url.clone())),
+            NotifyChannel::Custom { name } => {
+                self.notifiers.get(name).cloned().ok_or_else(|| {
+                    RepliersError::InvalidConfig(format!("No notifier registered as {}", name))
+                })?
+            }
+        })
+    }
+}
+
+/// One-line description such as "C1234567 - 123 Main St, Toronto - $899,000"
+fn describe(listing: &Listing) -> String {
+    let mut parts = vec![listing.mls_number.clone()];
+    let address = match (listing.street_address(), listing.city()) {
+        (Some(street), Some(city)) => Some(format!("{}, {}", street, city)),
+        (Some(street), None) => Some(street),
+        (None, Some(city)) => Some(city.to_string()),
+        (None, None) => None,
+    };
+    parts.extend(address);
+    parts.extend(listing.list_price.map(format_price));
+    parts.join(" - ")
+}
+
+fn describe_change(change: &ListingChange) -> String {
+    match change {
+        ListingChange::PriceChanged { old, new, pct, .. } => {
+            let mut text = format!("Price {} -> {}", format_price(*old), format_price(*new));
+            if let Some(pct) = pct {
+                let _ = write!(text, " ({:+.1}%)", pct);
+            }
+            text
+        }
+        ListingChange::StatusChanged {
+            old_status,
+            new_status,
+            old_last_status,
+            new_last_status,
+            ..
+        } => format!(
+            "Status {}/{} -> {}/{}",
+            old_status.as_deref().unwrap_or("?"),
+            old_last_status.as_deref().unwrap_or("?"),
+            new_status.as_deref().unwrap_or("?"),
+            new_last_status.as_deref().unwrap_or("?")
+        ),
+        ListingChange::FieldChanged {
+            field, old, new, ..
+        } => {
+            format!("{}: {} -> {}", field, old, new)
+        }
+        ListingChange::New { .. } => "New listing".to_string(),
+        ListingChange::Removed { .. } => "Removed".to_string(),
+    }
+}
+
+fn format_price(price: f64) -> String {
+    let digits = format!("{:.0}", price.abs());
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{}${}", if price < 0.0 { "-" } else { "" }, grouped)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
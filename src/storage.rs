@@ -0,0 +1,46 @@
+//! File helpers shared by the file-backed stores and exporters
+
+use crate::RepliersError;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+/// Wraps an I/O or serialization error with what was being read or written
+///
+/// `context` is usually a path (`path.display()`) or a short description such as
+/// `"NDJSON export failed"`.
+pub(crate) fn storage_error(context: impl Display, e: impl Display) -> RepliersError {
+    RepliersError::Storage(format!("{}: {}", context, e))
+}
+
+/// Writes `content` to a temporary file next to `path`, then renames it over `path`
+///
+/// A crash part-way therefore never leaves a partially written file behind.
+pub(crate) async fn write_atomic_async(
+    path: &Path,
+    content: impl AsRef<[u8]>,
+) -> Result<(), RepliersError> {
+    let tmp = tmp_path(path);
+    tokio::fs::write(&tmp, content)
+        .await
+        .map_err(|e| storage_error(tmp.display(), e))?;
+    tokio::fs::rename(&tmp, path)
+        .await
+        .map_err(|e| storage_error(path.display(), e))
+}
+
+/// Blocking counterpart of [`write_atomic_async`]
+pub(crate) fn write_atomic(path: &Path, content: impl AsRef<[u8]>) -> Result<(), RepliersError> {
+    let tmp = tmp_path(path);
+    std::fs::write(&tmp, content).map_err(|e| storage_error(tmp.display(), e))?;
+    std::fs::rename(&tmp, path).map_err(|e| storage_error(path.display(), e))
+}
+
+/// The temporary file for `path`: its full file name with `.tmp` appended
+///
+/// Appending rather than replacing the extension keeps `state.json` and `state.toml`
+/// from sharing one temporary file.
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
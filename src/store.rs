@@ -0,0 +1,560 @@
+//! Embedded SQLite listing store
+//!
+//! [`ListingStore`] keeps a local copy of listings in a single SQLite file. It is a
+//! [`SyncSink`] and a [`WatermarkStore`], so a [`SyncEngine`](crate::sync::SyncEngine)
+//! can mirror an MLS into it, and it answers [`ListingSearchRequest`]s offline with the
+//! same response shape as the API.
+//!
+//! Listings are keyed on board ID plus MLS number; listings without a board ID are stored
+//! with a `NULL` board and are removed by a deletion of that MLS number on any board.
+//! Every insert and every change of price or status is appended to a history table, and
+//! deletions are kept in their own table until the listing reappears.
+//!
+//! The schema is versioned with SQLite's `user_version` pragma and migrated automatically
+//! when a store is opened.
+//!
+//! Requires the `store` feature.
+//!
+//! # Examples
+//!
+//! ```
+//! use repliers_beta::store::ListingStore;
+//! use repliers_beta::{Listing, ListingSearchRequest};
+//!
+//! # fn main() -> Result<(), repliers_beta::RepliersError> {
+//! let store = ListingStore::open_in_memory()?;
+//! store.upsert_listing(&Listing {
+//!     mls_number: "C1234567".to_string(),
+//!     list_price: Some(899_000.0),
+//!     ..Default::default()
+//! })?;
+//!
+//! let request = ListingSearchRequest::builder().max_price(900_000.0).build();
+//! let response = store.search(&request)?;
+//! assert_eq!(response.count, 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::models::{
+    parse_timestamp, DeletedListing, Listing, ListingSearchRequest, ListingSearchResponse,
+};
+use crate::storage::storage_error;
+use crate::sync::{SyncEvent, SyncSink, WatermarkStore};
+use crate::RepliersError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+type Result<T> = std::result::Result<T, RepliersError>;
+
+/// `(recorded_at, list_price, status, last_status, deleted)` as read from `listing_history`
+type HistoryRow = (String, Option<f64>, Option<String>, Option<String>, bool);
+
+/// `(data, list_price, status, last_status)` of a stored listing
+type StoredRow = (String, Option<f64>, Option<String>, Option<String>);
+
+/// Schema migrations, applied in order; `user_version` records how many have run
+const MIGRATIONS: &[&str] = &[r#"
+    CREATE TABLE listings (
+        board_id    INTEGER,
+        mls_number  TEXT    NOT NULL,
+        status      TEXT,
+        last_status TEXT,
+        list_price  REAL,
+        city        TEXT COLLATE NOCASE,
+        updated_at  TEXT,
+        data        TEXT    NOT NULL
+    );
+    CREATE UNIQUE INDEX listings_key ON listings (IFNULL(board_id, -1), mls_number);
+    CREATE INDEX listings_city ON listings (city);
+    CREATE INDEX listings_list_price ON listings (list_price);
+    CREATE INDEX listings_updated_at ON listings (updated_at);
+
+    CREATE TABLE deletions (
+        board_id    INTEGER NOT NULL,
+        mls_number  TEXT    NOT NULL,
+        deleted_at  TEXT,
+        data        TEXT    NOT NULL,
+        PRIMARY KEY (board_id, mls_number)
+    );
+
+    CREATE TABLE listing_history (
+        id          INTEGER PRIMARY KEY AUTOINCREMENT,
+        board_id    INTEGER,
+        mls_number  TEXT    NOT NULL,
+        recorded_at TEXT    NOT NULL,
+        list_price  REAL,
+        status      TEXT,
+        last_status TEXT,
+        deleted     INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX listing_history_key ON listing_history (board_id, mls_number);
+
+    CREATE TABLE sync_state (
+        key   TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+"#];
+
+const WATERMARK_KEY: &str = "watermark";
+
+/// Result of writing a listing to the store
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    /// The listing was not in the store before
+    Inserted,
+
+    /// The stored listing was replaced with different data
+    Updated,
+
+    /// The stored listing was identical
+    Unchanged,
+}
+
+/// A recorded price or status change for a listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryRecord {
+    /// When the change happened (the listing's update time when known)
+    pub recorded_at: DateTime<Utc>,
+
+    /// List price after the change
+    pub list_price: Option<f64>,
+
+    /// Status after the change (e.g. "A", "U")
+    pub status: Option<String>,
+
+    /// Last status after the change (e.g. "New", "Sld")
+    pub last_status: Option<String>,
+
+    /// Whether this entry records the listing's deletion
+    pub deleted: bool,
+}
+
+/// SQLite-backed local copy of listings
+///
+/// Cloning is cheap and clones share the same connection, so one store can serve as both
+/// the sink and the watermark store of a sync engine.
+#[derive(Debug, Clone)]
+pub struct ListingStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl ListingStore {
+    /// Opens (or creates) a store at the given path and migrates its schema
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let conn = Connection::open(path).map_err(|e| storage_error(path.display(), e))?;
+        Self::from_connection(conn)
+    }
+
+    /// Opens a store that lives in memory only
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory().map_err(db_error)?)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self> {
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Returns the schema version of the underlying database
+    pub fn schema_version(&self) -> Result<u32> {
+        self.with_conn(|conn| conn.pragma_query_value(None, "user_version", |row| row.get(0)))
+    }
+
+    /// Inserts or replaces a listing, recording price and status changes
+    pub fn upsert_listing(&self, listing: &Listing) -> Result<UpsertOutcome> {
+        let data = to_json(listing)?;
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let outcome = upsert(&tx, listing, &data)?;
+            tx.commit()?;
+            Ok(outcome)
+        })
+    }
+
+    /// Inserts or replaces several listings in a single transaction
+    ///
+    /// Returns the number of listings that were inserted or changed.
+    pub fn upsert_listings<'a>(
+        &self,
+        listings: impl IntoIterator<Item = &'a Listing>,
+    ) -> Result<usize> {
+        let rows = listings
+            .into_iter()
+            .map(|listing| Ok((listing, to_json(listing)?)))
+            .collect::<Result<Vec<_>>>()?;
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let mut changed = 0;
+            for (listing, data) in &rows {
+                if upsert(&tx, listing, data)? != UpsertOutcome::Unchanged {
+                    changed += 1;
+                }
+            }
+            tx.commit()?;
+            Ok(changed)
+        })
+    }
+
+    /// Removes a listing and records its deletion
+    ///
+    /// Returns `true` if the listing was present in the store.
+    pub fn record_deletion(&self, deleted: &DeletedListing) -> Result<bool> {
+        let data = to_json(deleted)?;
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let removed = delete(&tx, deleted, &data)?;
+            tx.commit()?;
+            Ok(removed)
+        })
+    }
+
+    /// Looks up a listing by MLS number
+    ///
+    /// With `board_id` set to `None` the first listing with that MLS number on any board
+    /// is returned.
+    pub fn get_listing(&self, board_id: Option<u32>, mls_number: &str) -> Result<Option<Listing>> {
+        let data: Option<String> = self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT data FROM listings
+                 WHERE mls_number = ?1 AND (?2 IS NULL OR board_id = ?2)
+                 ORDER BY board_id LIMIT 1",
+                params![mls_number, board_id],
+                |row| row.get(0),
+            )
+            .optional()
+        })?;
+        data.as_deref().map(from_json).transpose()
+    }
+
+    /// Returns every stored listing
+    pub fn listings(&self) -> Result<Vec<Listing>> {
+        self.load_listings(
+            "SELECT data FROM listings ORDER BY board_id, mls_number",
+            params![],
+        )
+    }
+
+    /// Returns the number of stored listings
+    pub fn count(&self) -> Result<usize> {
+        self.with_conn(|conn| conn.query_row("SELECT COUNT(*) FROM listings", [], |row| row.get(0)))
+    }
+
+    /// Runs a search against the stored listings
+    ///
+    /// Behaves like [`ListingSearchRequest::search_in`] over the stored listings.
+    pub fn search(&self, request: &ListingSearchRequest) -> Result<ListingSearchResponse> {
+        // Narrow the scan with the indexed columns, then apply the full filter in Rust.
+        // Cities are stored trimmed and compared trimmed, as `matches` does.
+        let city = request.city.as_deref().map(str::trim);
+        let listings = self.load_listings(
+            "SELECT data FROM listings
+             WHERE (?1 IS NULL OR city = ?1)
+               AND (?2 IS NULL OR list_price >= ?2)
+               AND (?3 IS NULL OR list_price <= ?3)
+             ORDER BY board_id, mls_number",
+            params![city, request.min_price, request.max_price],
+        )?;
+        Ok(request.search_in(listings))
+    }
+
+    /// Returns the listings recorded as deleted, most recent first
+    pub fn deleted_listings(&self) -> Result<Vec<DeletedListing>> {
+        let rows: Vec<String> = self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT data FROM deletions ORDER BY deleted_at DESC, board_id, mls_number",
+            )?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect()
+        })?;
+        rows.iter().map(|data| from_json(data)).collect()
+    }
+
+    /// Returns the recorded price and status changes of a listing, oldest first
+    pub fn history(&self, board_id: Option<u32>, mls_number: &str) -> Result<Vec<HistoryRecord>> {
+        let rows: Vec<HistoryRow> = self.with_conn(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT recorded_at, list_price, status, last_status, deleted
+                     FROM listing_history
+                     WHERE mls_number = ?1 AND (?2 IS NULL OR board_id = ?2)
+                     ORDER BY recorded_at, id",
+            )?;
+            let rows = stmt.query_map(params![mls_number, board_id], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            })?;
+            rows.collect()
+        })?;
+
+        rows.into_iter()
+            .map(|(recorded_at, list_price, status, last_status, deleted)| {
+                let recorded_at = parse_timestamp(&recorded_at).ok_or_else(|| {
+                    RepliersError::Storage(format!("Invalid history timestamp: {}", recorded_at))
+                })?;
+                Ok(HistoryRecord {
+                    recorded_at,
+                    list_price,
+                    status,
+                    last_status,
+                    deleted,
+                })
+            })
+            .collect()
+    }
+
+    fn load_listings(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<Listing>> {
+        let rows: Vec<String> = self.with_conn(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt.query_map(params, |row| row.get(0))?;
+            rows.collect()
+        })?;
+        rows.iter().map(|data| from_json(data)).collect()
+    }
+
+    fn with_conn<T>(&self, f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T> {
+        let mut conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut conn).map_err(db_error)
+    }
+}
+
+#[async_trait]
+impl SyncSink for ListingStore {
+    /// Applies the whole batch in one transaction
+    async fn apply(&mut self, events: &[SyncEvent]) -> Result<()> {
+        let rows = events
+            .iter()
+            .map(|event| match event {
+                SyncEvent::Upsert(listing) => to_json(listing),
+                SyncEvent::Delete(deleted) => to_json(deleted),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            for (event, data) in events.iter().zip(&rows) {
+                match event {
+                    SyncEvent::Upsert(listing) => {
+                        upsert(&tx, listing, data)?;
+                    }
+                    SyncEvent::Delete(deleted) => {
+                        delete(&tx, deleted, data)?;
+                    }
+                }
+            }
+            tx.commit()
+        })
+    }
+}
+
+#[async_trait]
+impl WatermarkStore for ListingStore {
+    async fn load(&mut self) -> Result<Option<DateTime<Utc>>> {
+        let value: Option<String> = self.with_conn(|conn| {
+            conn.query_row(
+                "SELECT value FROM sync_state WHERE key = ?1",
+                params![WATERMARK_KEY],
+                |row| row.get(0),
+            )
+            .optional()
+        })?;
+        value
+            .map(|v| {
+                parse_timestamp(&v)
+                    .ok_or_else(|| RepliersError::Storage(format!("Invalid watermark: {}", v)))
+            })
+            .transpose()
+    }
+
+    async fn save(&mut self, watermark: DateTime<Utc>) -> Result<()> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO sync_state (key, value) VALUES (?1, ?2)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![WATERMARK_KEY, watermark.to_rfc3339()],
+            )
+            .map(|_| ())
+        })
+    }
+}
+
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: usize = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(db_error)?;
+    if version > MIGRATIONS.len() {
+        return Err(RepliersError::Storage(format!(
+            "Store schema version {} is newer than supported version {}",
+            version,
+            MIGRATIONS.len()
+        )));
+    }
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction().map_err(db_error)?;
+        tx.execute_batch(sql).map_err(db_error)?;
+        tx.pragma_update(None, "user_version", index + 1)
+            .map_err(db_error)?;
+        tx.commit().map_err(db_error)?;
+    }
+    Ok(())
+}
+
+fn upsert(conn: &Connection, listing: &Listing, data: &str) -> rusqlite::Result<UpsertOutcome> {
+    let board_id = listing.board_id;
+    let previous: Option<StoredRow> = conn
+        .query_row(
+            "SELECT data, list_price, status, last_status FROM listings
+             WHERE board_id IS ?1 AND mls_number = ?2",
+            params![board_id, listing.mls_number],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()?;
+
+    let outcome = match &previous {
+        None => UpsertOutcome::Inserted,
+        Some((previous_data, ..)) if same_json(previous_data, data) => {
+            return Ok(UpsertOutcome::Unchanged)
+        }
+        Some(_) => UpsertOutcome::Updated,
+    };
+
+    let updated_at = listing.last_updated().map(|dt| dt.to_rfc3339());
+    let values = params![
+        board_id,
+        listing.mls_number,
+        listing.status,
+        listing.last_status,
+        listing.list_price,
+        listing.city().map(str::trim),
+        updated_at,
+        data,
+    ];
+    // `board_id` may be NULL, which a plain ON CONFLICT upsert would never match
+    if previous.is_some() {
+        conn.execute(
+            "UPDATE listings SET
+                 status = ?3, last_status = ?4, list_price = ?5, city = ?6,
+                 updated_at = ?7, data = ?8
+             WHERE board_id IS ?1 AND mls_number = ?2",
+            values,
+        )?;
+    } else {
+        conn.execute(
+            "INSERT INTO listings
+                 (board_id, mls_number, status, last_status, list_price, city, updated_at, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            values,
+        )?;
+    }
+    conn.execute(
+        "DELETE FROM deletions WHERE (?1 IS NULL OR board_id = ?1) AND mls_number = ?2",
+        params![board_id, listing.mls_number],
+    )?;
+
+    let changed = match &previous {
+        None => true,
+        Some((_, price, status, last_status)) => {
+            *price != listing.list_price
+                || *status != listing.status
+                || *last_status != listing.last_status
+        }
+    };
+    if changed {
+        conn.execute(
+            "INSERT INTO listing_history
+                 (board_id, mls_number, recorded_at, list_price, status, last_status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                board_id,
+                listing.mls_number,
+                updated_at.unwrap_or_else(|| Utc::now().to_rfc3339()),
+                listing.list_price,
+                listing.status,
+                listing.last_status,
+            ],
+        )?;
+    }
+
+    Ok(outcome)
+}
+
+fn delete(conn: &Connection, deleted: &DeletedListing, data: &str) -> rusqlite::Result<bool> {
+    let deleted_at = parse_timestamp(&deleted.timestamps.listing_updated);
+
+    // Listings stored without a board ID match a deletion on any board
+    let boards: Vec<Option<u32>> = {
+        let mut stmt = conn.prepare(
+            "DELETE FROM listings
+             WHERE (board_id = ?1 OR board_id IS NULL) AND mls_number = ?2
+             RETURNING board_id",
+        )?;
+        let rows = stmt.query_map(params![deleted.board_id, deleted.mls_number], |row| {
+            row.get(0)
+        })?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+    let removed = !boards.is_empty();
+
+    conn.execute(
+        "INSERT INTO deletions (board_id, mls_number, deleted_at, data)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(board_id, mls_number) DO UPDATE SET
+             deleted_at = excluded.deleted_at,
+             data = excluded.data",
+        params![
+            deleted.board_id,
+            deleted.mls_number,
+            deleted_at.map(|dt| dt.to_rfc3339()),
+            data,
+        ],
+    )?;
+
+    // Only the first deletion is recorded, so replayed sync batches stay idempotent
+    for board_id in boards {
+        conn.execute(
+            "INSERT INTO listing_history (board_id, mls_number, recorded_at, deleted)
+             VALUES (?1, ?2, ?3, 1)",
+            params![
+                board_id,
+                deleted.mls_number,
+                deleted_at.unwrap_or_else(Utc::now).to_rfc3339(),
+            ],
+        )?;
+    }
+
+    Ok(removed)
+}
+
+/// Compares stored JSON structurally, since map fields serialize in no fixed order
+fn same_json(a: &str, b: &str) -> bool {
+    match (
+        serde_json::from_str::<serde_json::Value>(a),
+        serde_json::from_str::<serde_json::Value>(b),
+    ) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|e| RepliersError::Storage(e.to_string()))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(data: &str) -> Result<T> {
+    serde_json::from_str(data).map_err(|e| RepliersError::Storage(e.to_string()))
+}
+
+fn db_error(e: rusqlite::Error) -> RepliersError {
+    RepliersError::Storage(format!("SQLite: {}", e))
+}
@@ -0,0 +1,416 @@
+//! Incremental MLS synchronization
+//!
+//! [`SyncEngine`] keeps a local copy of listings up to date. Each run:
+//!
+//! 1. Loads the watermark (the latest update timestamp already applied) and opens a
+//!    query window starting [`SyncOptions::lookback`] before it
+//! 2. Fetches listings deleted within the window via `get_deleted_listings`
+//! 3. Fetches listings updated within the window via `search_listings`, sorted by
+//!    update time
+//! 4. Streams both feeds into [`SyncEvent`]s (deletions first), skipping listings
+//!    already seen in the run and upserts of listings deleted in the run
+//! 5. Hands the events to a [`SyncSink`] in batches, saving the watermark only after the
+//!    sink acknowledges each batch
+//!
+//! A run that crashes part-way therefore resumes from the last acknowledged batch. Events
+//! inside the window are replayed on the next run, so sinks should treat upserts and
+//! deletes as idempotent.
+//!
+//! Both feeds are paged by offset. A listing updated while a run is paging moves to the
+//! end of the results and shifts every later page by one, so another listing can be
+//! skipped while the watermark moves past it. The lookback window is what recovers it:
+//! the next run queries from [`SyncOptions::lookback`] before the new watermark, so keep
+//! the lookback longer than the time between runs.
+
+use crate::api::RepliersApi;
+use crate::models::{DeletedListing, DeletedListingsQuery, Listing, ListingSearchRequest};
+use crate::pagination::{deleted_stream, search_stream, SearchStreamOptions};
+use crate::storage::{storage_error, write_atomic_async};
+use crate::{RepliersClient, RepliersError};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// A change to apply to a local copy of the listings
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncEvent {
+    /// A listing was created or updated
+    Upsert(Box<Listing>),
+
+    /// A listing was removed from the MLS
+    Delete(Box<DeletedListing>),
+}
+
+impl SyncEvent {
+    /// Returns the MLS number the event refers to
+    pub fn mls_number(&self) -> &str {
+        match self {
+            Self::Upsert(listing) => &listing.mls_number,
+            Self::Delete(deleted) => &deleted.mls_number,
+        }
+    }
+
+    /// Returns when an upserted listing was last updated
+    ///
+    /// Deletions return `None`: the deleted feed only records the listing's last update
+    /// before it was removed, not when the removal happened.
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Upsert(listing) => listing.last_updated(),
+            Self::Delete(_) => None,
+        }
+    }
+}
+
+/// Destination for synchronization events
+///
+/// Implementations should persist each batch before returning `Ok`; the watermark is
+/// advanced as soon as `apply` succeeds.
+#[async_trait]
+pub trait SyncSink: Send {
+    /// Applies a batch of events, in order
+    async fn apply(&mut self, events: &[SyncEvent]) -> Result<(), RepliersError>;
+}
+
+/// Persistent storage for the synchronization watermark
+#[async_trait]
+pub trait WatermarkStore: Send {
+    /// Loads the last saved watermark, or `None` before the first run
+    async fn load(&mut self) -> Result<Option<DateTime<Utc>>, RepliersError>;
+
+    /// Saves a new watermark
+    async fn save(&mut self, watermark: DateTime<Utc>) -> Result<(), RepliersError>;
+}
+
+/// Watermark kept in memory only (useful for tests and one-off runs)
+#[derive(Debug, Clone, Default)]
+pub struct MemoryWatermarkStore {
+    watermark: Option<DateTime<Utc>>,
+}
+
+impl MemoryWatermarkStore {
+    /// Creates a store starting at the given watermark
+    pub fn new(watermark: Option<DateTime<Utc>>) -> Self {
+        Self { watermark }
+    }
+}
+
+#[async_trait]
+impl WatermarkStore for MemoryWatermarkStore {
+    async fn load(&mut self) -> Result<Option<DateTime<Utc>>, RepliersError> {
+        Ok(self.watermark)
+    }
+
+    async fn save(&mut self, watermark: DateTime<Utc>) -> Result<(), RepliersError> {
+        self.watermark = Some(watermark);
+        Ok(())
+    }
+}
+
+/// Watermark persisted to a small JSON file
+///
+/// Writes go to a temporary file that is then renamed over the original, so a crash
+/// never leaves a partially written watermark behind.
+#[derive(Debug, Clone)]
+pub struct FileWatermarkStore {
+    path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WatermarkFile {
+    watermark: DateTime<Utc>,
+}
+
+impl FileWatermarkStore {
+    /// Creates a store backed by the given file (created on first save)
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl WatermarkStore for FileWatermarkStore {
+    async fn load(&mut self) -> Result<Option<DateTime<Utc>>, RepliersError> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(storage_error(self.path.display(), e)),
+        };
+        let file: WatermarkFile =
+            serde_json::from_str(&content).map_err(|e| storage_error(self.path.display(), e))?;
+        Ok(Some(file.watermark))
+    }
+
+    async fn save(&mut self, watermark: DateTime<Utc>) -> Result<(), RepliersError> {
+        let content = serde_json::to_string_pretty(&WatermarkFile { watermark })
+            .map_err(|e| storage_error(self.path.display(), e))?;
+        write_atomic_async(&self.path, content).await
+    }
+}
+
+/// Options for a synchronization run
+#[derive(Debug, Clone)]
+pub struct SyncOptions {
+    /// Filters applied to the updated-listings search (e.g. city or board ID)
+    ///
+    /// Pagination, `sort_by` and `min_updated_on` are managed by the engine.
+    pub base_request: ListingSearchRequest,
+
+    /// Board ID for the deleted-listings feed (multi-MLS accounts)
+    pub board_id: Option<String>,
+
+    /// Watermark to use when none has been saved yet; `None` performs a full sync
+    pub initial_watermark: Option<DateTime<Utc>>,
+
+    /// How far before the watermark to query (default one day)
+    ///
+    /// Absorbs clock skew and date-only filters, and re-fetches listings that offset
+    /// paging skipped in an earlier run. Keep it longer than the time between runs.
+    pub lookback: Duration,
+
+    /// Number of events handed to the sink at a time
+    pub batch_size: usize,
+
+    /// Results requested per page from both feeds
+    pub page_size: u32,
+
+    /// Whether to fetch the deleted-listings feed
+    pub include_deletions: bool,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            base_request: ListingSearchRequest::default(),
+            board_id: None,
+            initial_watermark: None,
+            lookback: Duration::days(1),
+            batch_size: 100,
+            page_size: 100,
+            include_deletions: true,
+        }
+    }
+}
+
+/// Summary of a synchronization run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    /// Watermark at the start of the run
+    pub previous_watermark: Option<DateTime<Utc>>,
+
+    /// Watermark after the run
+    pub watermark: Option<DateTime<Utc>>,
+
+    /// Number of upsert events applied
+    pub upserts: usize,
+
+    /// Number of delete events applied
+    pub deletes: usize,
+
+    /// Number of batches acknowledged by the sink
+    pub batches: usize,
+}
+
+/// Drives incremental synchronization into a [`SyncSink`]
+///
+/// Listings are fetched through any [`RepliersApi`]: a [`RepliersClient`] by default, or
+/// an [`InMemoryApi`](crate::InMemoryApi) in tests.
+///
+/// # Examples
+///
+/// ```no_run
+/// use async_trait::async_trait;
+/// use repliers_beta::sync::{FileWatermarkStore, SyncEngine, SyncEvent, SyncSink};
+/// use repliers_beta::{RepliersClient, RepliersError};
+///
+/// struct PrintSink;
+///
+/// #[async_trait]
+/// impl SyncSink for PrintSink {
+///     async fn apply(&mut self, events: &[SyncEvent]) -> Result<(), RepliersError> {
+///         for event in events {
+///             println!("{:?} {}", event.timestamp(), event.mls_number());
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = RepliersClient::from_env()?;
+/// let mut engine = SyncEngine::new(client, PrintSink, FileWatermarkStore::new("watermark.json"));
+///
+/// let report = engine.run().await?;
+/// println!("{} upserts, {} deletes", report.upserts, report.deletes);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SyncEngine<S, W, A = RepliersClient> {
+    api: A,
+    sink: S,
+    watermarks: W,
+    options: SyncOptions,
+}
+
+impl<S: SyncSink, W: WatermarkStore, A: RepliersApi> SyncEngine<S, W, A> {
+    /// Creates an engine with default options
+    pub fn new(api: A, sink: S, watermarks: W) -> Self {
+        Self {
+            api,
+            sink,
+            watermarks,
+            options: SyncOptions::default(),
+        }
+    }
+
+    /// Replaces the engine's options
+    pub fn with_options(mut self, options: SyncOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns a reference to the sink
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Consumes the engine, returning the sink
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+
+    /// Runs one synchronization pass
+    ///
+    /// Deletions are applied first, then listings updated since the watermark (minus
+    /// [`SyncOptions::lookback`]) in update order. Events are streamed into batches, so
+    /// only one batch is held in memory; the engine does remember the key of every
+    /// event in the run so that pages shifting mid-run never apply a listing twice.
+    ///
+    /// Shifting pages can also skip a listing, and the watermark is saved after every
+    /// batch, so it may move past the skipped listing. A later run picks it up again only
+    /// if it was updated within the lookback of the new watermark.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first API, sink or watermark error. Batches acknowledged before the
+    /// error keep their saved watermark.
+    pub async fn run(&mut self) -> Result<SyncReport, RepliersError> {
+        let Self {
+            api,
+            sink,
+            watermarks,
+            options,
+        } = self;
+
+        let previous = match watermarks.load().await? {
+            Some(watermark) => Some(watermark),
+            None => options.initial_watermark,
+        };
+        let window_start = previous.map(|ts| ts - options.lookback);
+        let query_from = window_start.map(|ts| ts.format("%Y-%m-%d").to_string());
+        let batch_size = options.batch_size.max(1);
+
+        let mut report = SyncReport {
+            previous_watermark: previous,
+            watermark: previous,
+            ..Default::default()
+        };
+        let mut batch = Vec::with_capacity(batch_size);
+
+        // Deleted keys, recorded both with and without the board so that listings
+        // lacking a board ID still match
+        let mut deleted: HashSet<(String, Option<u32>)> = HashSet::new();
+
+        if options.include_deletions {
+            let query = DeletedListingsQuery {
+                min_updated_on: query_from.clone(),
+                board_id: options.board_id.clone(),
+                results_per_page: Some(options.page_size),
+                ..Default::default()
+            };
+            let mut deletions = deleted_stream(&*api, query);
+            while let Some(listing) = deletions.try_next().await? {
+                if !deleted.insert((listing.mls_number.clone(), Some(listing.board_id))) {
+                    continue;
+                }
+                deleted.insert((listing.mls_number.clone(), None));
+                batch.push(SyncEvent::Delete(Box::new(listing)));
+                if batch.len() >= batch_size {
+                    flush(sink, watermarks, &mut batch, &mut report).await?;
+                }
+            }
+        }
+
+        let mut request = options.base_request.clone();
+        request.min_updated_on = query_from;
+        request.sort_by = Some("updatedOnAsc".to_string());
+        request.page = None;
+
+        let stream_options = SearchStreamOptions {
+            page_size: Some(options.page_size),
+            ..Default::default()
+        };
+
+        let mut seen: HashMap<(String, Option<u32>), Option<DateTime<Utc>>> = HashMap::new();
+        let mut listings = search_stream(&*api, request, stream_options);
+        while let Some(listing) = listings.try_next().await? {
+            let ts = listing.last_updated();
+            if let (Some(start), Some(ts)) = (window_start, ts) {
+                if ts < start {
+                    continue;
+                }
+            }
+
+            let key = (listing.mls_number.clone(), listing.board_id);
+            if deleted.contains(&key) {
+                continue;
+            }
+            if seen.get(&key).is_some_and(|applied| *applied >= ts) {
+                continue;
+            }
+            seen.insert(key, ts);
+
+            batch.push(SyncEvent::Upsert(Box::new(listing)));
+            if batch.len() >= batch_size {
+                flush(sink, watermarks, &mut batch, &mut report).await?;
+            }
+        }
+
+        if !batch.is_empty() {
+            flush(sink, watermarks, &mut batch, &mut report).await?;
+        }
+
+        Ok(report)
+    }
+}
+
+/// Hands a batch to the sink, then advances the watermark past its newest upsert
+async fn flush<S: SyncSink, W: WatermarkStore>(
+    sink: &mut S,
+    watermarks: &mut W,
+    batch: &mut Vec<SyncEvent>,
+    report: &mut SyncReport,
+) -> Result<(), RepliersError> {
+    sink.apply(batch).await?;
+    report.batches += 1;
+
+    for event in batch.iter() {
+        match event {
+            SyncEvent::Upsert(_) => report.upserts += 1,
+            SyncEvent::Delete(_) => report.deletes += 1,
+        }
+    }
+
+    let batch_max = batch.iter().filter_map(SyncEvent::timestamp).max();
+    if let Some(ts) = batch_max {
+        if report.watermark.is_none_or(|current| ts > current) {
+            watermarks.save(ts).await?;
+            report.watermark = Some(ts);
+        }
+    }
+
+    batch.clear();
+    Ok(())
+}
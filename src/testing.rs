@@ -0,0 +1,595 @@
+//! In-process fake Repliers API for integration tests
+//!
+//! [`FakeRepliers`] runs a local HTTP server that speaks the same wire format as the
+//! Repliers API. Seed it with listings (your own, or [`synthetic_listings`]) and point a
+//! [`RepliersClient`] at it; the client code under test runs unchanged, over real HTTP.
+//!
+//! All six endpoints answer with the same semantics as
+//! [`InMemoryApi`](crate::InMemoryApi): filtering, sorting and pagination for search,
+//! similar listings by class, property type, price range and radius, address history
+//! built from the seeded listings, and deleted listings for those removed with
+//! [`FakeRepliers::delete_listing`]. Use `InMemoryApi` when the code under test can take
+//! a [`RepliersApi`](crate::RepliersApi); use the fake when the HTTP layer itself
+//! (retries, rate limiting, transports) is part of the test.
+//!
+//! Requests without the fake's API key are rejected with `401`. [`Fault`]s can be queued
+//! to exercise retry and error paths, and every request is logged for assertions.
+//!
+//! Requires the `testing` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use repliers_beta::testing::{synthetic_listings, Fault, FakeRepliers};
+//! use repliers_beta::{ListingSearchRequest, RetryPolicy};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let fake = FakeRepliers::start().await?;
+//! fake.insert_listings(synthetic_listings(200, 42));
+//!
+//! // The first attempt is rate limited, the retry succeeds
+//! fake.inject(Fault::RateLimited { retry_after: Some(Duration::from_millis(10)) });
+//! let client = fake
+//!     .client_builder()
+//!     .retry_policy(RetryPolicy::default().retry_search(true))
+//!     .build()?;
+//!
+//! let request = ListingSearchRequest::builder().city("Toronto").build();
+//! let response = client.search_listings(request).await?;
+//! assert!(response.listings.iter().all(|l| l.city() == Some("Toronto")));
+//! assert_eq!(fake.request_count(), 2);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::ListingData;
+use crate::models::{
+    Address, AddressHistoryQuery, DeletedListing, DeletedListingsQuery, Listing, ListingDetails,
+    ListingSearchRequest, MapCoordinates, NLPSearchRequest, SimilarListingsRequest,
+};
+use crate::{RepliersClient, RepliersClientBuilder, RepliersError};
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// API key accepted by the fake server
+pub const FAKE_API_KEY: &str = "fake-api-key";
+
+/// Header carrying the API key
+const API_KEY_HEADER: &str = "REPLIERS-API-KEY";
+
+/// A failure injected into the next request
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// Delay the response, then answer normally
+    Latency(Duration),
+
+    /// Answer with the given HTTP status and a JSON error body
+    Status(u16),
+
+    /// Answer `429 Too Many Requests`, with a `Retry-After` header when set
+    RateLimited {
+        /// Delay advertised in `Retry-After`, rounded up to whole seconds
+        retry_after: Option<Duration>,
+    },
+
+    /// Answer `200 OK` with a truncated JSON body
+    MalformedJson,
+}
+
+/// A request received by the fake server
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeRequest {
+    /// HTTP method (e.g. "GET")
+    pub method: String,
+
+    /// Request path, without the query string
+    pub path: String,
+
+    /// Decoded query parameters
+    pub query: BTreeMap<String, String>,
+
+    /// JSON request body, if any
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    data: ListingData,
+    faults: VecDeque<Fault>,
+    latency: Duration,
+    requests: Vec<FakeRequest>,
+}
+
+/// A local HTTP server imitating the Repliers API
+///
+/// The server runs on an ephemeral port of `127.0.0.1` and shuts down when the value is
+/// dropped. Seeding and fault injection take effect immediately, including for requests
+/// already in flight.
+#[derive(Debug)]
+pub struct FakeRepliers {
+    addr: SocketAddr,
+    state: Arc<Mutex<FakeState>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl FakeRepliers {
+    /// Starts an empty fake server
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::InvalidConfig`] if no local port can be bound.
+    pub async fn start() -> Result<Self, RepliersError> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| {
+                RepliersError::InvalidConfig(format!("Failed to bind fake server: {}", e))
+            })?;
+        let addr = listener.local_addr().map_err(|e| {
+            RepliersError::InvalidConfig(format!("Failed to bind fake server: {}", e))
+        })?;
+
+        let state = Arc::new(Mutex::new(FakeState::default()));
+        let app = Router::new().fallback(handle).with_state(state.clone());
+        let (shutdown, signal) = oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app)
+                .with_graceful_shutdown(async {
+                    let _ = signal.await;
+                })
+                .await;
+        });
+
+        Ok(Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    /// Returns the base URL of the server (e.g. `http://127.0.0.1:49152`)
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Returns the API key the server accepts
+    pub fn api_key(&self) -> &'static str {
+        FAKE_API_KEY
+    }
+
+    /// Returns a client builder pointed at the server with its API key set
+    pub fn client_builder(&self) -> RepliersClientBuilder {
+        RepliersClient::builder()
+            .api_key(FAKE_API_KEY)
+            .base_url(self.url())
+    }
+
+    /// Returns a client with default settings pointed at the server
+    pub fn client(&self) -> RepliersClient {
+        self.client_builder()
+            .build()
+            .expect("fake server URL is a valid base URL")
+    }
+
+    /// Adds or replaces a listing
+    pub fn insert_listing(&self, listing: Listing) {
+        self.lock().data.insert(listing);
+    }
+
+    /// Adds or replaces several listings
+    pub fn insert_listings(&self, listings: impl IntoIterator<Item = Listing>) {
+        let mut state = self.lock();
+        for listing in listings {
+            state.data.insert(listing);
+        }
+    }
+
+    /// Removes a listing and reports it from the deleted-listings endpoint
+    ///
+    /// Returns `false` if no listing with that MLS number exists.
+    pub fn delete_listing(&self, mls_number: &str, deleted_at: DateTime<Utc>) -> bool {
+        self.lock().data.delete(mls_number, deleted_at)
+    }
+
+    /// Adds a deleted-listing record directly
+    pub fn insert_deleted(&self, deleted: DeletedListing) {
+        self.lock().data.insert_deleted(deleted);
+    }
+
+    /// Returns a listing by MLS number
+    pub fn listing(&self, mls_number: &str) -> Option<Listing> {
+        self.lock().data.get(mls_number).cloned()
+    }
+
+    /// Returns all listings, ordered by MLS number
+    pub fn listings(&self) -> Vec<Listing> {
+        self.lock().data.listings().cloned().collect()
+    }
+
+    /// Queues a fault for the next request
+    ///
+    /// Faults are consumed one per request, in the order they were queued.
+    pub fn inject(&self, fault: Fault) {
+        self.lock().faults.push_back(fault);
+    }
+
+    /// Queues a fault for the next `times` requests
+    pub fn inject_times(&self, fault: Fault, times: usize) {
+        let mut state = self.lock();
+        state.faults.extend(std::iter::repeat_n(fault, times));
+    }
+
+    /// Drops all queued faults
+    pub fn clear_faults(&self) {
+        self.lock().faults.clear();
+    }
+
+    /// Delays every response by `latency` (zero to disable)
+    pub fn set_latency(&self, latency: Duration) {
+        self.lock().latency = latency;
+    }
+
+    /// Returns every request received so far, oldest first
+    pub fn requests(&self) -> Vec<FakeRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// Returns the number of requests received so far
+    pub fn request_count(&self) -> usize {
+        self.lock().requests.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for FakeRepliers {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+async fn handle(
+    State(state): State<Arc<Mutex<FakeState>>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let query: BTreeMap<String, String> = reqwest::Url::parse(&format!("http://fake{}", uri))
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_default();
+    let request = FakeRequest {
+        method: method.to_string(),
+        path: uri.path().to_string(),
+        query,
+        body: serde_json::from_slice(&body).ok(),
+    };
+
+    let (fault, latency) = {
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        state.requests.push(request.clone());
+        (state.faults.pop_front(), state.latency)
+    };
+
+    let mut delay = latency;
+    if let Some(Fault::Latency(extra)) = &fault {
+        delay += *extra;
+    }
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+
+    match fault {
+        Some(Fault::Status(status)) => {
+            let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            return error(status, "Injected failure");
+        }
+        Some(Fault::RateLimited { retry_after }) => {
+            let mut response = error(StatusCode::TOO_MANY_REQUESTS, "Too many requests");
+            if let Some(retry_after) = retry_after {
+                let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, seconds.into());
+            }
+            return response;
+        }
+        Some(Fault::MalformedJson) => {
+            return (
+                StatusCode::OK,
+                [(header::CONTENT_TYPE, "application/json")],
+                r#"{"listings": [{"mlsNumber": "#,
+            )
+                .into_response();
+        }
+        Some(Fault::Latency(_)) | None => {}
+    }
+
+    let authorized = headers
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|key| key == FAKE_API_KEY);
+    if !authorized {
+        return error(StatusCode::UNAUTHORIZED, "Invalid API key");
+    }
+
+    let state = state.lock().unwrap_or_else(|e| e.into_inner());
+    let data = &state.data;
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    match (&method, segments.as_slice()) {
+        (&Method::POST, ["listings"]) => search(data, &body),
+        (&Method::POST, ["nlp"]) => nlp(&body),
+        (&Method::GET, ["listings", "deleted"]) => deleted(data, &request.query),
+        (&Method::GET, ["listings", "history"]) => address_history(data, &request.query),
+        (&Method::GET, ["listings", mls_number, "similar"]) => {
+            similar(data, mls_number, &request.query)
+        }
+        (&Method::GET, ["listings", mls_number]) => detail(data, mls_number, &request.query),
+        _ => error(StatusCode::NOT_FOUND, "Unknown endpoint"),
+    }
+}
+
+fn ok(body: impl serde::Serialize) -> Response {
+    json_response(StatusCode::OK, &body)
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    json_response(status, &json!({ "message": message }))
+}
+
+fn json_response(status: StatusCode, body: &impl serde::Serialize) -> Response {
+    match serde_json::to_string(body) {
+        Ok(body) => (status, [(header::CONTENT_TYPE, "application/json")], body).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+fn search(data: &ListingData, body: &[u8]) -> Response {
+    match serde_json::from_slice::<ListingSearchRequest>(body) {
+        Ok(request) => ok(data.search(&request)),
+        Err(e) => error(StatusCode::BAD_REQUEST, &format!("Invalid search: {}", e)),
+    }
+}
+
+fn nlp(body: &[u8]) -> Response {
+    match serde_json::from_slice::<NLPSearchRequest>(body) {
+        Ok(request) => ok(ListingData::nlp(
+            &request.prompt,
+            request.board_id.as_deref(),
+        )),
+        Err(e) => error(StatusCode::BAD_REQUEST, &format!("Invalid prompt: {}", e)),
+    }
+}
+
+fn detail(data: &ListingData, mls_number: &str, query: &BTreeMap<String, String>) -> Response {
+    match data.detail(mls_number, query.get("boardId").map(String::as_str)) {
+        Some(detail) => ok(detail),
+        None => error(StatusCode::NOT_FOUND, "Listing not found"),
+    }
+}
+
+fn similar(data: &ListingData, mls_number: &str, query: &BTreeMap<String, String>) -> Response {
+    let request = SimilarListingsRequest {
+        mls_number: mls_number.to_string(),
+        board_id: query.get("boardId").cloned(),
+        radius: query.get("radius").and_then(|v| v.parse().ok()),
+        list_price_range: query.get("listPriceRange").and_then(|v| v.parse().ok()),
+        fields: query.get("fields").cloned(),
+        sort_by: query.get("sortBy").cloned(),
+    };
+    match data.similar(&request) {
+        Some(similar) => ok(similar),
+        None => error(StatusCode::NOT_FOUND, "Listing not found"),
+    }
+}
+
+fn address_history(data: &ListingData, query: &BTreeMap<String, String>) -> Response {
+    let (Some(street_number), Some(street_name)) =
+        (query.get("streetNumber"), query.get("streetName"))
+    else {
+        return error(
+            StatusCode::BAD_REQUEST,
+            "streetNumber and streetName are required",
+        );
+    };
+    let query = AddressHistoryQuery {
+        street_number: street_number.clone(),
+        street_name: street_name.clone(),
+        city: query.get("city").cloned(),
+        zip: query.get("zip").cloned(),
+        state: query.get("state").cloned(),
+        board_id: query.get("boardId").cloned(),
+    };
+    match data.address_history(&query) {
+        Some(history) => ok(history),
+        None => error(StatusCode::NOT_FOUND, "No history for this address"),
+    }
+}
+
+fn deleted(data: &ListingData, query: &BTreeMap<String, String>) -> Response {
+    let query = DeletedListingsQuery {
+        updated_on: query.get("updatedOn").cloned(),
+        min_updated_on: query.get("minUpdatedOn").cloned(),
+        max_updated_on: query.get("maxUpdatedOn").cloned(),
+        board_id: query.get("boardId").cloned(),
+        page: query.get("page").and_then(|v| v.parse().ok()),
+        results_per_page: query.get("resultsPerPage").and_then(|v| v.parse().ok()),
+    };
+    ok(data.deleted(&query))
+}
+
+const CITIES: [(&str, &str, f64, f64); 5] = [
+    ("Toronto", "C", 43.6532, -79.3832),
+    ("Mississauga", "W", 43.5890, -79.6441),
+    ("Ottawa", "X", 45.4215, -75.6972),
+    ("Hamilton", "X", 43.2557, -79.8711),
+    ("Oakville", "W", 43.4675, -79.6877),
+];
+
+const STREETS: [(&str, &str); 8] = [
+    ("Queen", "St"),
+    ("King", "St"),
+    ("Yonge", "St"),
+    ("Bloor", "St"),
+    ("Dundas", "St"),
+    ("Lakeshore", "Blvd"),
+    ("Bathurst", "St"),
+    ("Maple", "Ave"),
+];
+
+/// Property type, class, style and price range of each synthetic kind of home
+const KINDS: [(&str, &str, &str, f64, f64); 4] = [
+    (
+        "Detached",
+        "ResidentialProperty",
+        "2-Storey",
+        900_000.0,
+        2_500_000.0,
+    ),
+    (
+        "Semi-Detached",
+        "ResidentialProperty",
+        "2-Storey",
+        750_000.0,
+        1_400_000.0,
+    ),
+    (
+        "Att/Row/Townhouse",
+        "ResidentialProperty",
+        "3-Storey",
+        650_000.0,
+        1_200_000.0,
+    ),
+    (
+        "Condo Apartment",
+        "CondoProperty",
+        "Apartment",
+        400_000.0,
+        1_000_000.0,
+    ),
+];
+
+/// Generates `count` realistic, deterministic listings
+///
+/// The same `seed` always produces the same listings. Listings are spread over five
+/// Ontario cities with coordinates, addresses, details and dates in 2024; roughly one in
+/// five is sold, and one in ten shares its address with an earlier listing so address
+/// history has something to return.
+pub fn synthetic_listings(count: usize, seed: u64) -> Vec<Listing> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let start = NaiveDate::from_ymd_opt(2024, 1, 1).expect("valid date");
+    let mut listings: Vec<Listing> = Vec::with_capacity(count);
+
+    for i in 0..count {
+        // Some homes are listed again: same city, kind and address as an earlier listing
+        let earlier = (i > 0 && rng.gen_bool(0.1)).then(|| listings[rng.gen_range(0..i)].clone());
+        let city_index = earlier
+            .as_ref()
+            .and_then(|l| CITIES.iter().position(|c| l.city() == Some(c.0)))
+            .unwrap_or_else(|| rng.gen_range(0..CITIES.len()));
+        let kind_index = earlier
+            .as_ref()
+            .and_then(|l| l.details.as_ref()?.property_type.as_deref())
+            .and_then(|t| KINDS.iter().position(|k| k.0 == t))
+            .unwrap_or_else(|| rng.gen_range(0..KINDS.len()));
+        let (city, prefix, lat, long) = CITIES[city_index];
+        let (property_type, class, style, min_price, max_price) = KINDS[kind_index];
+        let list_price = (rng.gen_range(min_price..max_price) / 1_000.0).round() * 1_000.0;
+        let bedrooms = if class == "CondoProperty" {
+            rng.gen_range(1..=3)
+        } else {
+            rng.gen_range(2..=5)
+        };
+        let sqft_floor = 500 + bedrooms * 300 + rng.gen_range(0..4) * 100;
+
+        let listed = start + ChronoDuration::days(rng.gen_range(0..330));
+        let days_on_market: u32 = rng.gen_range(1..60);
+        let updated = listed + ChronoDuration::days(i64::from(days_on_market));
+        let sold = rng.gen_bool(0.2);
+
+        let address = match &earlier {
+            Some(earlier) => earlier.address.clone().unwrap_or_default(),
+            None => {
+                let (street, suffix) = STREETS[rng.gen_range(0..STREETS.len())];
+                Address {
+                    area: Some(format!("{} Area", city)),
+                    city: Some(city.to_string()),
+                    country: Some("Canada".to_string()),
+                    street_name: Some(street.to_string()),
+                    street_number: Some(rng.gen_range(1..1000).to_string()),
+                    street_suffix: Some(suffix.to_string()),
+                    unit_number: (class == "CondoProperty")
+                        .then(|| rng.gen_range(100..3000).to_string()),
+                    zip: Some(format!(
+                        "M{}A {}B{}",
+                        rng.gen_range(1..10),
+                        rng.gen_range(1..10),
+                        rng.gen_range(1..10)
+                    )),
+                    state: Some("ON".to_string()),
+                    ..Default::default()
+                }
+            }
+        };
+
+        listings.push(Listing {
+            mls_number: format!("{}{:08}", prefix, 10_000_000 + i),
+            board_id: Some(1),
+            resource: Some("Property:1".to_string()),
+            status: Some(if sold { "U" } else { "A" }.to_string()),
+            last_status: Some(if sold { "Sld" } else { "New" }.to_string()),
+            class: Some(class.to_string()),
+            listing_type: Some("Sale".to_string()),
+            list_price: Some(list_price),
+            original_price: Some(list_price),
+            list_date: Some(format!("{}T00:00:00.000Z", listed)),
+            sold_price: sold
+                .then(|| (list_price * rng.gen_range(0.95..1.1) / 1_000.0).round() * 1_000.0),
+            sold_date: sold.then(|| format!("{}T00:00:00.000Z", updated)),
+            days_on_market: Some(days_on_market),
+            updated_on: Some(format!("{}T12:00:00.000Z", updated)),
+            map: earlier.as_ref().and_then(|l| l.map.clone()).or_else(|| {
+                Some(MapCoordinates {
+                    latitude: Some(lat + rng.gen_range(-0.08..0.08)),
+                    longitude: Some(long + rng.gen_range(-0.08..0.08)),
+                    point: None,
+                })
+            }),
+            details: Some(ListingDetails {
+                num_bedrooms: Some(bedrooms),
+                num_bathrooms: Some(rng.gen_range(1..=bedrooms)),
+                sqft: Some(format!("{}-{}", sqft_floor, sqft_floor + 199)),
+                style: Some(style.to_string()),
+                property_type: Some(property_type.to_string()),
+                description: Some(format!(
+                    "Bright {}-bedroom {} in {}.",
+                    bedrooms,
+                    property_type.to_lowercase(),
+                    city
+                )),
+                num_garage_spaces: Some(f64::from(rng.gen_range(0..3))),
+                num_parking_spaces: Some(f64::from(rng.gen_range(0..4))),
+                ..Default::default()
+            }),
+            address: Some(address),
+            ..Default::default()
+        });
+    }
+    listings
+}
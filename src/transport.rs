@@ -0,0 +1,463 @@
+//! Pluggable HTTP transport and record/replay cassettes
+//!
+//! Every request made by a [`RepliersClient`](crate::RepliersClient) goes through a
+//! [`Transport`]. The default, [`HttpTransport`], sends it over the network with `reqwest`.
+//! Two more transports make code built on the client testable offline:
+//!
+//! - [`RecordingTransport`] forwards requests to another transport and appends each
+//!   request/response pair to a [`Cassette`], written to a file when the recording ends
+//! - [`ReplayTransport`] answers requests from a cassette without touching the network
+//!
+//! Cassettes never contain request headers, and the API key is scrubbed from every
+//! recorded URL and body, so they are safe to commit.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use repliers_beta::transport::ReplayTransport;
+//! use repliers_beta::{ListingSearchRequest, RepliersClient};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! // Record once against the live API; the cassette is written when the client is dropped
+//! let client = RepliersClient::builder()
+//!     .api_key(std::env::var("REPLIERS_API_KEY")?)
+//!     .record("tests/cassettes/toronto.json")
+//!     .build()?;
+//! let request = ListingSearchRequest::builder().city("Toronto").build();
+//! client.search_listings(request.clone()).await?;
+//! drop(client);
+//!
+//! // ...then replay deterministically, without network access or a real key
+//! let client = RepliersClient::builder()
+//!     .api_key("test")
+//!     .transport(ReplayTransport::from_file("tests/cassettes/toronto.json")?)
+//!     .build()?;
+//! let response = client.search_listings(request).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::storage::{storage_error, write_atomic};
+use crate::RepliersError;
+use async_trait::async_trait;
+use reqwest::{Client, Request, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Header carrying the API key, whose value is scrubbed from recordings
+const API_KEY_HEADER: &str = "REPLIERS-API-KEY";
+
+/// Response headers that are not recorded: cookies, and framing or per-connection
+/// headers that would be wrong once the body is re-encoded
+const SKIPPED_HEADERS: &[&str] = &[
+    "set-cookie",
+    "content-length",
+    "transfer-encoding",
+    "connection",
+    "keep-alive",
+    "date",
+];
+
+/// Placeholder written in place of the API key
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Executes HTTP requests on behalf of a client
+///
+/// Rate limiting and retries are applied by the client around the transport, so
+/// implementations only need to turn one request into one response.
+#[async_trait]
+pub trait Transport: Send + Sync + std::fmt::Debug {
+    /// Sends a request and returns the response, whatever its status
+    async fn execute(&self, request: Request) -> Result<Response, RepliersError>;
+}
+
+/// Sends requests over the network with a `reqwest::Client`
+#[derive(Debug, Clone, Default)]
+pub struct HttpTransport {
+    client: Client,
+}
+
+impl HttpTransport {
+    /// Creates a transport using the given HTTP client
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn execute(&self, request: Request) -> Result<Response, RepliersError> {
+        Ok(self.client.execute(request).await?)
+    }
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    async fn execute(&self, request: Request) -> Result<Response, RepliersError> {
+        (**self).execute(request).await
+    }
+}
+
+/// A sequence of recorded request/response pairs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    /// Recorded interactions, in the order they happened
+    pub interactions: Vec<Interaction>,
+}
+
+/// One recorded request and the response it received
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// The request as sent (without headers)
+    pub request: RecordedRequest,
+
+    /// The response as received
+    pub response: RecordedResponse,
+}
+
+/// A recorded request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    /// HTTP method (e.g. `GET`)
+    pub method: String,
+
+    /// Path and query string, e.g. `/listings/N123?boardId=1`
+    pub path: String,
+
+    /// Request body; JSON bodies are stored as JSON, anything else as a string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// A recorded response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    /// HTTP status code
+    pub status: u16,
+
+    /// Response headers (cookies and framing headers are dropped)
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+
+    /// Response body; JSON bodies are stored as JSON, anything else as a string
+    #[serde(default)]
+    pub body: Value,
+}
+
+impl Cassette {
+    /// Reads a cassette from a JSON file
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file cannot be read or parsed.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RepliersError> {
+        let path = path.as_ref();
+        let content =
+            std::fs::read_to_string(path).map_err(|e| storage_error(path.display(), e))?;
+        serde_json::from_str(&content).map_err(|e| storage_error(path.display(), e))
+    }
+
+    /// Writes the cassette to a JSON file, creating parent directories as needed
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RepliersError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| storage_error(parent.display(), e))?;
+        }
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| storage_error(path.display(), e))?;
+        write_atomic(path, content)
+    }
+}
+
+impl RecordedRequest {
+    /// Captures a request, replacing any occurrence of `secret` with [`REDACTED`]
+    fn capture(request: &Request, secret: Option<&str>) -> Self {
+        let body = request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .map(|bytes| scrub_value(decode_body(bytes), secret));
+        Self {
+            method: request.method().to_string(),
+            path: scrub(&normalized_path(request.url()), secret),
+            body,
+        }
+    }
+
+    fn matches(&self, other: &RecordedRequest) -> bool {
+        self.method.eq_ignore_ascii_case(&other.method)
+            && self.path == other.path
+            && self.body == other.body
+    }
+}
+
+impl RecordedResponse {
+    /// Builds a `reqwest::Response` serving this recording
+    fn to_response(&self) -> Result<Response, RepliersError> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let body = match &self.body {
+            Value::String(text) => text.clone(),
+            Value::Null => String::new(),
+            json => json.to_string(),
+        };
+        let response = builder.body(body).map_err(|e| {
+            RepliersError::InvalidRequest(format!("Invalid recorded response: {}", e))
+        })?;
+        Ok(Response::from(response))
+    }
+}
+
+/// Forwards requests to another transport and records every interaction
+///
+/// Interactions are kept in memory and written to the cassette file by
+/// [`save`](Self::save), or when the last clone of the transport is dropped. Recording
+/// appends to an existing cassette.
+///
+/// To record a client's own network traffic (with its timeouts, proxies and headers),
+/// use [`RepliersClientBuilder::record`](crate::RepliersClientBuilder::record). Keep a
+/// clone of a transport passed to [`transport`](crate::RepliersClientBuilder::transport)
+/// to save it explicitly and handle write errors; errors while saving on drop are
+/// ignored.
+#[derive(Debug, Clone)]
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    recorder: Arc<Recorder>,
+}
+
+#[derive(Debug)]
+struct Recorder {
+    path: PathBuf,
+    state: Mutex<RecorderState>,
+}
+
+#[derive(Debug)]
+struct RecorderState {
+    cassette: Cassette,
+    unsaved: bool,
+}
+
+impl RecordingTransport {
+    /// Records the traffic of another transport into the given cassette file
+    pub fn wrap(inner: impl Transport + 'static, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let cassette = Cassette::load(&path).unwrap_or_default();
+        Self {
+            inner: Arc::new(inner),
+            recorder: Arc::new(Recorder {
+                path,
+                state: Mutex::new(RecorderState {
+                    cassette,
+                    unsaved: false,
+                }),
+            }),
+        }
+    }
+
+    /// Returns a copy of everything recorded so far
+    pub fn cassette(&self) -> Cassette {
+        self.recorder.lock().cassette.clone()
+    }
+
+    /// Writes the interactions recorded so far to the cassette file
+    ///
+    /// Does nothing if nothing new was recorded since the last save.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file cannot be written.
+    pub fn save(&self) -> Result<(), RepliersError> {
+        self.recorder.save()
+    }
+}
+
+impl Recorder {
+    fn lock(&self) -> std::sync::MutexGuard<'_, RecorderState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn save(&self) -> Result<(), RepliersError> {
+        let mut state = self.lock();
+        if state.unsaved {
+            state.cassette.save(&self.path)?;
+            state.unsaved = false;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let _ = self.save();
+    }
+}
+
+#[async_trait]
+impl Transport for RecordingTransport {
+    async fn execute(&self, request: Request) -> Result<Response, RepliersError> {
+        let secret = request
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|key| !key.is_empty())
+            .map(str::to_string);
+        let recorded_request = RecordedRequest::capture(&request, secret.as_deref());
+
+        let response = self.inner.execute(request).await?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| {
+                let value = scrub(value.to_str().ok()?, secret.as_deref());
+                Some((name.to_string(), value))
+            })
+            .collect();
+        let bytes = response.bytes().await?;
+
+        let recorded_response = RecordedResponse {
+            status,
+            headers,
+            body: scrub_value(decode_body(&bytes), secret.as_deref()),
+        };
+
+        let mut state = self.recorder.lock();
+        state.cassette.interactions.push(Interaction {
+            request: recorded_request,
+            response: recorded_response.clone(),
+        });
+        state.unsaved = true;
+        drop(state);
+
+        recorded_response.to_response()
+    }
+}
+
+/// Serves responses from a cassette instead of the network
+///
+/// Each request is answered by the first unused interaction with the same method, path
+/// (query parameters in any order) and body. Once every matching interaction has been
+/// used, the last one is served again. Unmatched requests fail with
+/// [`RepliersError::InvalidRequest`].
+#[derive(Debug)]
+pub struct ReplayTransport {
+    cassette: Cassette,
+    used: Mutex<Vec<bool>>,
+}
+
+impl ReplayTransport {
+    /// Replays the given cassette
+    pub fn new(cassette: Cassette) -> Self {
+        let used = vec![false; cassette.interactions.len()];
+        Self {
+            cassette,
+            used: Mutex::new(used),
+        }
+    }
+
+    /// Replays a cassette file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, RepliersError> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    /// Returns `true` once every recorded interaction has been served at least once
+    pub fn is_exhausted(&self) -> bool {
+        self.used
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .all(|used| *used)
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn execute(&self, request: Request) -> Result<Response, RepliersError> {
+        let wanted = RecordedRequest::capture(&request, None);
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+
+        let candidates: Vec<usize> = self
+            .cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| interaction.request.matches(&wanted))
+            .map(|(index, _)| index)
+            .collect();
+        let index = candidates
+            .iter()
+            .copied()
+            .find(|&index| !used[index])
+            .or_else(|| candidates.last().copied())
+            .ok_or_else(|| {
+                RepliersError::InvalidRequest(format!(
+                    "No recorded interaction for {} {}",
+                    wanted.method, wanted.path
+                ))
+            })?;
+        used[index] = true;
+        drop(used);
+
+        self.cassette.interactions[index].response.to_response()
+    }
+}
+
+/// Returns the path plus the query string with its parameters sorted
+fn normalized_path(url: &reqwest::Url) -> String {
+    let mut pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if pairs.is_empty() {
+        return url.path().to_string();
+    }
+    pairs.sort();
+    let query = pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{}?{}", url.path(), query)
+}
+
+fn decode_body(bytes: &[u8]) -> Value {
+    if bytes.is_empty() {
+        return Value::Null;
+    }
+    serde_json::from_slice(bytes)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned()))
+}
+
+fn scrub(text: &str, secret: Option<&str>) -> String {
+    match secret {
+        Some(secret) => text.replace(secret, REDACTED),
+        None => text.to_string(),
+    }
+}
+
+fn scrub_value(value: Value, secret: Option<&str>) -> Value {
+    match (value, secret) {
+        (Value::String(text), Some(_)) => Value::String(scrub(&text, secret)),
+        (Value::Array(items), Some(_)) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| scrub_value(item, secret))
+                .collect(),
+        ),
+        (Value::Object(map), Some(_)) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, scrub_value(v, secret)))
+                .collect(),
+        ),
+        (value, _) => value,
+    }
+}
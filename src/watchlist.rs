@@ -0,0 +1,441 @@
+//! Polling watchlist for specific listings
+//!
+//! A [`Watchlist`] tracks a fixed set of MLS numbers. Each poll fetches every watched
+//! listing through `get_listing` (a bounded number at a time), compares it with the last
+//! known version using [`diff_listing`] and reports the differences as [`WatchEvent`]s.
+//!
+//! Events can be consumed from [`Watchlist::poll_once`], through a callback with
+//! [`Watchlist::run`], or over a channel with [`Watchlist::spawn`]. When a state file is
+//! configured, the last known version of every listing is saved after each poll so that
+//! changes made between runs are still detected.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use repliers_beta::watchlist::{WatchEvent, Watchlist};
+//! use repliers_beta::RepliersClient;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = RepliersClient::from_env()?;
+//! let mut watchlist = Watchlist::load(client, "watchlist.json").await?;
+//! watchlist.add("N12345678");
+//!
+//! watchlist
+//!     .run(|event| match event {
+//!         WatchEvent::Changed { target, changes, .. } => {
+//!             println!("{}: {} change(s)", target.mls_number, changes.len())
+//!         }
+//!         WatchEvent::NotFound { target, .. } => println!("{} is gone", target.mls_number),
+//!     })
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::RepliersApi;
+use crate::diff::{diff_listing, DiffOptions, ListingChange};
+use crate::models::Listing;
+use crate::storage::{storage_error, write_atomic_async};
+use crate::{RepliersClient, RepliersError};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// A listing on the watchlist
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedListing {
+    /// MLS number of the listing
+    pub mls_number: String,
+
+    /// Board ID (required only for multi-MLS accounts)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub board_id: Option<String>,
+}
+
+impl WatchedListing {
+    /// Watches a listing by MLS number
+    pub fn new(mls_number: impl Into<String>) -> Self {
+        Self {
+            mls_number: mls_number.into(),
+            board_id: None,
+        }
+    }
+
+    /// Watches a listing on a specific board
+    pub fn with_board(mls_number: impl Into<String>, board_id: impl Into<String>) -> Self {
+        Self {
+            mls_number: mls_number.into(),
+            board_id: Some(board_id.into()),
+        }
+    }
+}
+
+impl From<&str> for WatchedListing {
+    fn from(mls_number: &str) -> Self {
+        Self::new(mls_number)
+    }
+}
+
+impl From<String> for WatchedListing {
+    fn from(mls_number: String) -> Self {
+        Self::new(mls_number)
+    }
+}
+
+/// A change detected on a watched listing
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchEvent {
+    /// The listing differs from its last known version
+    Changed {
+        /// The watched listing
+        target: WatchedListing,
+
+        /// The listing as just fetched
+        listing: Box<Listing>,
+
+        /// The differences from the last known version
+        changes: Vec<ListingChange>,
+    },
+
+    /// The listing could no longer be found (reported once until it reappears)
+    NotFound {
+        /// The watched listing
+        target: WatchedListing,
+
+        /// The last version seen before it disappeared
+        last_known: Option<Box<Listing>>,
+    },
+}
+
+impl WatchEvent {
+    /// Returns the watched listing the event refers to
+    pub fn target(&self) -> &WatchedListing {
+        match self {
+            Self::Changed { target, .. } | Self::NotFound { target, .. } => target,
+        }
+    }
+}
+
+/// Options for polling a watchlist
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// Time between polls in [`Watchlist::run`] and [`Watchlist::spawn`]; must not be
+    /// zero
+    pub interval: Duration,
+
+    /// Maximum number of `get_listing` requests in flight at once
+    pub concurrency: usize,
+
+    /// Which differences count as changes
+    pub diff: DiffOptions,
+
+    /// Whether the first successful fetch of a listing emits a
+    /// [`ListingChange::New`] event, rather than silently recording a baseline
+    pub notify_initial: bool,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(300),
+            concurrency: 4,
+            diff: DiffOptions::default(),
+            notify_initial: false,
+        }
+    }
+}
+
+/// Outcome of a single poll
+#[derive(Debug, Default)]
+pub struct PollReport {
+    /// Number of listings fetched successfully
+    pub checked: usize,
+
+    /// Events detected during the poll, in watchlist order
+    pub events: Vec<WatchEvent>,
+
+    /// Listings that could not be fetched, with the error (their state is unchanged)
+    pub failures: Vec<(WatchedListing, RepliersError)>,
+}
+
+/// Last known state of a watched listing
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WatchState {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_known: Option<Listing>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_checked: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    missing: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StateFile {
+    entries: Vec<StateEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StateEntry {
+    #[serde(flatten)]
+    target: WatchedListing,
+
+    #[serde(flatten)]
+    state: WatchState,
+}
+
+/// Polls a fixed set of listings for changes
+///
+/// Listings are fetched through any [`RepliersApi`]: a [`RepliersClient`] by default, or
+/// an [`InMemoryApi`](crate::InMemoryApi) in tests.
+pub struct Watchlist<A = RepliersClient> {
+    api: A,
+    entries: BTreeMap<WatchedListing, WatchState>,
+    options: WatchOptions,
+    state_path: Option<PathBuf>,
+}
+
+impl<A: RepliersApi> Watchlist<A> {
+    /// Creates an empty watchlist whose state is kept in memory only
+    pub fn new(api: A) -> Self {
+        Self {
+            api,
+            entries: BTreeMap::new(),
+            options: WatchOptions::default(),
+            state_path: None,
+        }
+    }
+
+    /// Loads a watchlist from a state file, starting empty if the file does not exist
+    ///
+    /// State is saved back to the same file after every poll.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::Storage`] if the file exists but cannot be read or parsed.
+    pub async fn load(api: A, path: impl Into<PathBuf>) -> Result<Self, RepliersError> {
+        let path = path.into();
+        let mut watchlist = Self::new(api);
+
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => {
+                let file: StateFile =
+                    serde_json::from_str(&content).map_err(|e| storage_error(path.display(), e))?;
+                watchlist.entries = file
+                    .entries
+                    .into_iter()
+                    .map(|entry| (entry.target, entry.state))
+                    .collect();
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(storage_error(path.display(), e)),
+        }
+
+        watchlist.state_path = Some(path);
+        Ok(watchlist)
+    }
+
+    /// Replaces the polling options
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::InvalidConfig`] if the interval is zero.
+    pub fn with_options(mut self, options: WatchOptions) -> Result<Self, RepliersError> {
+        if options.interval.is_zero() {
+            return Err(RepliersError::InvalidConfig(
+                "Watchlist interval must be greater than zero".to_string(),
+            ));
+        }
+        self.options = options;
+        Ok(self)
+    }
+
+    /// Saves state to the given file after every poll
+    pub fn persist_to(mut self, path: impl Into<PathBuf>) -> Self {
+        self.state_path = Some(path.into());
+        self
+    }
+
+    /// Adds a listing to the watchlist; returns `false` if it was already watched
+    pub fn add(&mut self, target: impl Into<WatchedListing>) -> bool {
+        let target = target.into();
+        if self.entries.contains_key(&target) {
+            return false;
+        }
+        self.entries.insert(target, WatchState::default());
+        true
+    }
+
+    /// Removes a listing from the watchlist; returns `false` if it was not watched
+    pub fn remove(&mut self, target: &WatchedListing) -> bool {
+        self.entries.remove(target).is_some()
+    }
+
+    /// Iterates over the watched listings
+    pub fn targets(&self) -> impl Iterator<Item = &WatchedListing> {
+        self.entries.keys()
+    }
+
+    /// Returns the number of watched listings
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if nothing is being watched
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the last version seen of a watched listing
+    pub fn last_known(&self, target: &WatchedListing) -> Option<&Listing> {
+        self.entries.get(target)?.last_known.as_ref()
+    }
+
+    /// Fetches every watched listing once and records the changes
+    ///
+    /// Failures to fetch individual listings are collected in the report rather than
+    /// aborting the poll.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error only if the state file cannot be written.
+    pub async fn poll_once(&mut self) -> Result<PollReport, RepliersError> {
+        let api = &self.api;
+        let mut results: Vec<_> = futures::stream::iter(self.entries.keys().cloned())
+            .map(|target| async move {
+                let result = api
+                    .get_listing(&target.mls_number, target.board_id.as_deref())
+                    .await;
+                (target, result)
+            })
+            .buffer_unordered(self.options.concurrency.max(1))
+            .collect()
+            .await;
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let now = Utc::now();
+        let mut report = PollReport::default();
+
+        for (target, result) in results {
+            let Some(state) = self.entries.get_mut(&target) else {
+                continue;
+            };
+            match result {
+                Ok(detail) => {
+                    report.checked += 1;
+                    let listing = detail.listing;
+                    let changes = match &state.last_known {
+                        Some(previous) => diff_listing(previous, &listing, &self.options.diff),
+                        None if self.options.notify_initial => vec![ListingChange::New {
+                            listing: Box::new(listing.clone()),
+                        }],
+                        None => Vec::new(),
+                    };
+                    if !changes.is_empty() {
+                        report.events.push(WatchEvent::Changed {
+                            target: target.clone(),
+                            listing: Box::new(listing.clone()),
+                            changes,
+                        });
+                    }
+                    state.last_known = Some(listing);
+                    state.last_checked = Some(now);
+                    state.missing = false;
+                }
+                Err(e) if e.is_not_found() => {
+                    report.checked += 1;
+                    if !state.missing {
+                        report.events.push(WatchEvent::NotFound {
+                            target: target.clone(),
+                            last_known: state.last_known.clone().map(Box::new),
+                        });
+                    }
+                    state.last_checked = Some(now);
+                    state.missing = true;
+                }
+                Err(e) => report.failures.push((target, e)),
+            }
+        }
+
+        self.save().await?;
+        Ok(report)
+    }
+
+    /// Polls forever at the configured interval, passing every event to `on_event`
+    ///
+    /// # Errors
+    ///
+    /// Returns only if the state file cannot be written.
+    pub async fn run<F>(&mut self, mut on_event: F) -> Result<(), RepliersError>
+    where
+        F: FnMut(WatchEvent),
+    {
+        let mut interval = tokio::time::interval(self.options.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let report = self.poll_once().await?;
+            report.events.into_iter().for_each(&mut on_event);
+        }
+    }
+
+    /// Polls in a background task, delivering events over a channel
+    ///
+    /// The task stops when the receiver is dropped or the state file cannot be written.
+    pub fn spawn(
+        mut self,
+        buffer: usize,
+    ) -> (
+        mpsc::Receiver<WatchEvent>,
+        JoinHandle<Result<(), RepliersError>>,
+    )
+    where
+        A: 'static,
+    {
+        let (tx, rx) = mpsc::channel(buffer.max(1));
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.options.interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let report = self.poll_once().await?;
+                for event in report.events {
+                    if tx.send(event).await.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+        });
+        (rx, handle)
+    }
+
+    /// Writes the current state to the state file, if one is configured
+    ///
+    /// The file is written to a temporary path and renamed over the original.
+    pub async fn save(&self) -> Result<(), RepliersError> {
+        let Some(path) = &self.state_path else {
+            return Ok(());
+        };
+        let file = StateFile {
+            entries: self
+                .entries
+                .iter()
+                .map(|(target, state)| StateEntry {
+                    target: target.clone(),
+                    state: state.clone(),
+                })
+                .collect(),
+        };
+        let content =
+            serde_json::to_string_pretty(&file).map_err(|e| storage_error(path.display(), e))?;
+        write_atomic_async(path, content).await
+    }
+}
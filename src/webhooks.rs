@@ -0,0 +1,291 @@
+//! Webhook receiver for listing events
+//!
+//! Repliers can push listing changes to an HTTP endpoint instead of being polled. This
+//! module provides:
+//!
+//! - [`WebhookEvent`], the typed payload, decoded into the same [`Listing`] and
+//!   [`DeletedListing`] types the client returns
+//! - [`verify_signature`] / [`sign`], HMAC-SHA256 helpers for the shared secret
+//! - [`router`], an embeddable axum router that verifies, decodes and forwards events to
+//!   a [`WebhookHandler`] (your own implementation or a channel sender)
+//!
+//! Payloads are JSON envelopes of the form `{"event": "listing.updated", "data": {...}}`.
+//! The signature is the hex-encoded HMAC-SHA256 of the raw request body, optionally
+//! prefixed with `sha256=`.
+//!
+//! Requires the `webhooks` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use repliers_beta::webhooks::{router, WebhookConfig, WebhookEvent};
+//! use tokio::sync::mpsc;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let (tx, mut rx) = mpsc::channel::<WebhookEvent>(100);
+//! let app = router("/webhooks/repliers", WebhookConfig::with_secret("s3cret"), tx);
+//!
+//! let listener = tokio::net::TcpListener::bind("127.0.0.1:8080").await?;
+//! tokio::spawn(async move { axum::serve(listener, app).await });
+//!
+//! while let Some(event) = rx.recv().await {
+//!     println!("{} {}", event.event_name(), event.mls_number().unwrap_or("?"));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::models::{DeletedListing, Listing};
+use crate::RepliersError;
+use async_trait::async_trait;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use sha2::Sha256;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Default header carrying the payload signature
+pub const DEFAULT_SIGNATURE_HEADER: &str = "x-repliers-signature";
+
+/// A listing event delivered by webhook
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookEvent {
+    /// `listing.created`
+    ListingCreated(Box<Listing>),
+
+    /// `listing.updated`
+    ListingUpdated(Box<Listing>),
+
+    /// `listing.deleted`
+    ListingDeleted(Box<DeletedListing>),
+
+    /// Any other event, left undecoded
+    Other {
+        /// Event name as sent
+        event: String,
+
+        /// Raw event data
+        data: Value,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    event: String,
+    #[serde(default)]
+    data: T,
+}
+
+impl WebhookEvent {
+    /// Decodes an event from a raw request body
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::ParseError`] if the body is not a valid event envelope or
+    /// the data does not match the event type.
+    pub fn from_slice(body: &[u8]) -> Result<Self, RepliersError> {
+        let envelope: Envelope<Value> = serde_json::from_slice(body)
+            .map_err(|e| RepliersError::ParseError(format!("Invalid webhook payload: {}", e)))?;
+        let data = envelope.data;
+        let event = match envelope.event.as_str() {
+            "listing.created" => Self::ListingCreated(decode(data)?),
+            "listing.updated" => Self::ListingUpdated(decode(data)?),
+            "listing.deleted" => Self::ListingDeleted(decode(data)?),
+            _ => Self::Other {
+                event: envelope.event,
+                data,
+            },
+        };
+        Ok(event)
+    }
+
+    /// Returns the event name (e.g. `listing.updated`)
+    pub fn event_name(&self) -> &str {
+        match self {
+            Self::ListingCreated(_) => "listing.created",
+            Self::ListingUpdated(_) => "listing.updated",
+            Self::ListingDeleted(_) => "listing.deleted",
+            Self::Other { event, .. } => event,
+        }
+    }
+
+    /// Returns the MLS number of the listing the event refers to, if any
+    pub fn mls_number(&self) -> Option<&str> {
+        match self {
+            Self::ListingCreated(listing) | Self::ListingUpdated(listing) => {
+                Some(&listing.mls_number)
+            }
+            Self::ListingDeleted(deleted) => Some(&deleted.mls_number),
+            Self::Other { data, .. } => data.get("mlsNumber")?.as_str(),
+        }
+    }
+}
+
+/// Serializes back into the webhook envelope, e.g. for building test fixtures
+impl Serialize for WebhookEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let data = match self {
+            Self::ListingCreated(listing) | Self::ListingUpdated(listing) => {
+                serde_json::to_value(listing).map_err(serde::ser::Error::custom)?
+            }
+            Self::ListingDeleted(deleted) => {
+                serde_json::to_value(deleted).map_err(serde::ser::Error::custom)?
+            }
+            Self::Other { data, .. } => data.clone(),
+        };
+        Envelope {
+            event: self.event_name().to_string(),
+            data,
+        }
+        .serialize(serializer)
+    }
+}
+
+fn decode<T: serde::de::DeserializeOwned>(data: Value) -> Result<Box<T>, RepliersError> {
+    serde_json::from_value(data)
+        .map(Box::new)
+        .map_err(|e| RepliersError::ParseError(format!("Invalid webhook data: {}", e)))
+}
+
+/// Computes the hex-encoded HMAC-SHA256 signature of a body
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Checks a signature against the body in constant time
+///
+/// Accepts the bare hex digest or one prefixed with `sha256=`.
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let signature = signature.trim();
+    let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let Ok(expected) = hex::decode(signature) else {
+        return false;
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Receiver settings
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Shared secret; when `None`, signatures are not checked
+    pub secret: Option<String>,
+
+    /// Header carrying the signature
+    pub signature_header: String,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            secret: None,
+            signature_header: DEFAULT_SIGNATURE_HEADER.to_string(),
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Requires requests to be signed with the given secret
+    pub fn with_secret(secret: impl Into<String>) -> Self {
+        Self {
+            secret: Some(secret.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Reads the signature from a different header
+    pub fn signature_header(mut self, header: impl Into<String>) -> Self {
+        self.signature_header = header.into();
+        self
+    }
+
+    /// Verifies a request's signature against the configured secret
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RepliersError::InvalidRequest`] if the signature is missing or wrong.
+    pub fn verify(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), RepliersError> {
+        let Some(secret) = &self.secret else {
+            return Ok(());
+        };
+        let signature = headers
+            .get(self.signature_header.as_str())
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| {
+                RepliersError::InvalidRequest(format!(
+                    "Missing webhook signature header {}",
+                    self.signature_header
+                ))
+            })?;
+        if verify_signature(secret, body, signature) {
+            Ok(())
+        } else {
+            Err(RepliersError::InvalidRequest(
+                "Webhook signature mismatch".to_string(),
+            ))
+        }
+    }
+}
+
+/// Receives decoded webhook events
+#[async_trait]
+pub trait WebhookHandler: Send + Sync + 'static {
+    /// Handles one event; an error makes the receiver answer with HTTP 500 so the
+    /// sender retries the delivery
+    async fn handle(&self, event: WebhookEvent) -> Result<(), RepliersError>;
+}
+
+/// Forwards events into a channel
+#[async_trait]
+impl WebhookHandler for mpsc::Sender<WebhookEvent> {
+    async fn handle(&self, event: WebhookEvent) -> Result<(), RepliersError> {
+        self.send(event)
+            .await
+            .map_err(|_| RepliersError::Storage("Webhook event receiver closed".to_string()))
+    }
+}
+
+struct ReceiverState<H> {
+    config: WebhookConfig,
+    handler: H,
+}
+
+/// Builds a router accepting `POST` requests on `path`
+///
+/// Responds with `204` once the handler accepts the event, `401` for missing or invalid
+/// signatures, `400` for undecodable payloads and `500` when the handler fails.
+pub fn router<H: WebhookHandler>(path: &str, config: WebhookConfig, handler: H) -> Router {
+    let state = Arc::new(ReceiverState { config, handler });
+    Router::new()
+        .route(path, post(receive::<H>))
+        .with_state(state)
+}
+
+async fn receive<H: WebhookHandler>(
+    State(state): State<Arc<ReceiverState<H>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, String) {
+    if let Err(e) = state.config.verify(&headers, &body) {
+        return (StatusCode::UNAUTHORIZED, e.to_string());
+    }
+    let event = match WebhookEvent::from_slice(&body) {
+        Ok(event) => event,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()),
+    };
+    match state.handler.handle(event).await {
+        Ok(()) => (StatusCode::NO_CONTENT, String::new()),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
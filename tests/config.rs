@@ -0,0 +1,207 @@
+//! Layered configuration: precedence, profiles, validation and rate limit defaults
+
+use repliers_beta::config::{ConfigLoader, Layer};
+use repliers_beta::{ConfigError, RateLimit};
+use std::path::PathBuf;
+
+/// Writes a configuration file under the system temp directory
+fn config_file(name: &str, toml: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("repliers-config-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, toml).unwrap();
+    path
+}
+
+const FILE: &str = r#"
+default_profile = "test"
+api_key = "shared-key"
+base_url = "https://shared.example.com"
+board_id = "1"
+timeout_secs = 30
+
+[profiles.test]
+api_key = "test-key"
+base_url = "https://test.example.com"
+
+[profiles.prod]
+api_key = "prod-key"
+
+[profiles.prod.rate_limit]
+requests_per_minute = 120
+burst = 10
+"#;
+
+#[test]
+fn later_layers_override_earlier_ones() {
+    let path = config_file("precedence.toml", FILE);
+
+    let config = ConfigLoader::new()
+        .file(&path)
+        .env_vars([
+            ("REPLIERS_BASE_URL", "https://env.example.com"),
+            ("REPLIERS_BOARD_ID", "2"),
+        ])
+        .board_id("3")
+        .load()
+        .unwrap();
+
+    assert_eq!(config.profile(), Some("test"));
+    let settings = config.settings();
+    assert_eq!(settings.api_key.as_deref(), Some("test-key"));
+    assert_eq!(
+        settings.base_url.as_deref(),
+        Some("https://env.example.com")
+    );
+    assert_eq!(settings.board_id.as_deref(), Some("3"));
+    assert_eq!(settings.timeout_secs, Some(30));
+}
+
+#[test]
+fn profile_selection_prefers_the_loader_then_the_environment() {
+    let path = config_file("profiles.toml", FILE);
+
+    let from_env = ConfigLoader::new()
+        .file(&path)
+        .env_vars([("REPLIERS_PROFILE", "prod")])
+        .load()
+        .unwrap();
+    assert_eq!(from_env.api_key(), Some("prod-key"));
+
+    let explicit = ConfigLoader::new()
+        .file(&path)
+        .env_vars([("REPLIERS_PROFILE", "prod")])
+        .profile("test")
+        .load()
+        .unwrap();
+    assert_eq!(explicit.api_key(), Some("test-key"));
+
+    let error = ConfigLoader::new()
+        .file(&path)
+        .without_env()
+        .profile("staging")
+        .load()
+        .unwrap_err();
+    assert!(
+        matches!(&error, ConfigError::UnknownProfile { name, .. } if name == "staging"),
+        "{:?}",
+        error
+    );
+}
+
+#[test]
+fn burst_defaults_to_the_per_second_rate() {
+    let load = |vars: &[(&str, &str)]| {
+        ConfigLoader::new()
+            .env_vars(vars.iter().copied())
+            .load()
+            .unwrap()
+            .rate_limit()
+    };
+
+    assert_eq!(load(&[]), None);
+    assert_eq!(
+        load(&[("REPLIERS_RATE_LIMIT_PER_MINUTE", "120")]),
+        Some(RateLimit {
+            refill_per_second: 2.0,
+            burst: 2
+        })
+    );
+    assert_eq!(
+        load(&[("REPLIERS_RATE_LIMIT_PER_SECOND", "2.5")]),
+        Some(RateLimit {
+            refill_per_second: 2.5,
+            burst: 3
+        })
+    );
+    assert_eq!(
+        load(&[("REPLIERS_RATE_LIMIT_PER_MINUTE", "6")]).map(|l| l.burst),
+        Some(1)
+    );
+    assert_eq!(
+        load(&[
+            ("REPLIERS_RATE_LIMIT_PER_SECOND", "2"),
+            ("REPLIERS_RATE_LIMIT_BURST", "8"),
+        ])
+        .map(|l| l.burst),
+        Some(8)
+    );
+}
+
+#[test]
+fn a_rate_from_a_later_layer_keeps_the_earlier_burst() {
+    let path = config_file("rates.toml", FILE);
+
+    let config = ConfigLoader::new()
+        .file(&path)
+        .profile("prod")
+        .env_vars([("REPLIERS_RATE_LIMIT_PER_SECOND", "5")])
+        .load()
+        .unwrap();
+
+    assert_eq!(
+        config.rate_limit(),
+        Some(RateLimit {
+            refill_per_second: 5.0,
+            burst: 10
+        })
+    );
+}
+
+#[test]
+fn invalid_values_name_the_setting_and_layer() {
+    let error = ConfigLoader::new()
+        .env_vars([("REPLIERS_RETRY_MAX_ATTEMPTS", "0")])
+        .load()
+        .unwrap_err();
+    assert!(
+        matches!(
+            &error,
+            ConfigError::InvalidValue { key, layer: Layer::Environment, .. }
+                if key == "REPLIERS_RETRY_MAX_ATTEMPTS"
+        ),
+        "{:?}",
+        error
+    );
+
+    let path = config_file(
+        "invalid.toml",
+        "[profiles.test]\napi_key = \"key\"\n\n[profiles.test.rate_limit]\nburst = 0\n",
+    );
+    let error = ConfigLoader::new()
+        .file(&path)
+        .without_env()
+        .profile("test")
+        .load()
+        .unwrap_err();
+    assert!(
+        matches!(
+            &error,
+            ConfigError::InvalidValue { key, layer: Layer::File, .. }
+                if key == "profiles.test.rate_limit.burst"
+        ),
+        "{:?}",
+        error
+    );
+}
+
+#[test]
+fn settings_from_different_layers_are_checked_together() {
+    let path = config_file("delays.toml", "[retry]\nbase_delay_ms = 5000\n");
+
+    let error = ConfigLoader::new()
+        .file(&path)
+        .env_vars([("REPLIERS_RETRY_MAX_DELAY_MS", "1000")])
+        .load()
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            &error,
+            ConfigError::InvalidValue { key, layer: Layer::Merged, .. }
+                if key == "retry.base_delay_ms"
+        ),
+        "{:?}",
+        error
+    );
+}
@@ -0,0 +1,145 @@
+//! Snapshot diffing: new, removed, repriced, re-statused and edited listings
+
+use repliers_beta::diff::{diff_listing, DiffOptions, ListingChange, Snapshot};
+use repliers_beta::{Listing, ListingDetails, ListingTimestamps};
+use serde_json::json;
+
+fn listing(mls_number: &str, price: f64) -> Listing {
+    Listing {
+        mls_number: mls_number.to_string(),
+        status: Some("A".to_string()),
+        last_status: Some("New".to_string()),
+        list_price: Some(price),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn reports_new_and_removed_listings_with_removals_last() {
+    let before = Snapshot::new([listing("C1", 1.0), listing("C3", 1.0)]);
+    let after = Snapshot::new([listing("C3", 1.0), listing("C2", 1.0)]);
+
+    let changes = before.diff(&after, &DiffOptions::default());
+
+    assert_eq!(
+        changes,
+        [
+            ListingChange::New {
+                listing: Box::new(listing("C2", 1.0))
+            },
+            ListingChange::Removed {
+                listing: Box::new(listing("C1", 1.0))
+            },
+        ]
+    );
+}
+
+#[test]
+fn reports_price_changes_with_a_percentage() {
+    let changes = diff_listing(
+        &listing("C1", 800_000.0),
+        &listing("C1", 760_000.0),
+        &DiffOptions::default(),
+    );
+
+    assert_eq!(
+        changes,
+        [ListingChange::PriceChanged {
+            mls_number: "C1".to_string(),
+            old: 800_000.0,
+            new: 760_000.0,
+            pct: Some(-5.0),
+        }]
+    );
+}
+
+#[test]
+fn price_changes_from_zero_have_no_percentage() {
+    let changes = diff_listing(
+        &listing("C1", 0.0),
+        &listing("C1", 760_000.0),
+        &DiffOptions::default(),
+    );
+
+    assert_eq!(
+        changes,
+        [ListingChange::PriceChanged {
+            mls_number: "C1".to_string(),
+            old: 0.0,
+            new: 760_000.0,
+            pct: None,
+        }]
+    );
+}
+
+#[test]
+fn a_missing_price_is_a_field_change() {
+    let mut unpriced = listing("C1", 0.0);
+    unpriced.list_price = None;
+
+    let changes = diff_listing(
+        &unpriced,
+        &listing("C1", 760_000.0),
+        &DiffOptions::default(),
+    );
+
+    assert_eq!(
+        changes,
+        [ListingChange::FieldChanged {
+            mls_number: "C1".to_string(),
+            field: "listPrice".to_string(),
+            old: json!(null),
+            new: json!(760_000.0),
+        }]
+    );
+}
+
+#[test]
+fn reports_status_and_last_status_moves() {
+    let mut sold = listing("C1", 800_000.0);
+    sold.status = Some("U".to_string());
+    sold.last_status = Some("Sld".to_string());
+
+    let changes = diff_listing(&listing("C1", 800_000.0), &sold, &DiffOptions::default());
+
+    assert_eq!(
+        changes,
+        [ListingChange::StatusChanged {
+            mls_number: "C1".to_string(),
+            old_status: Some("A".to_string()),
+            new_status: Some("U".to_string()),
+            old_last_status: Some("New".to_string()),
+            new_last_status: Some("Sld".to_string()),
+        }]
+    );
+}
+
+#[test]
+fn field_changes_honour_the_ignore_list() {
+    let mut restyled = listing("C1", 800_000.0);
+    restyled.details = Some(ListingDetails {
+        style: Some("Bungalow".to_string()),
+        ..Default::default()
+    });
+    restyled.timestamps = Some(ListingTimestamps {
+        listing_updated: Some("2025-03-01T12:00:00Z".to_string()),
+        ..Default::default()
+    });
+    let original = listing("C1", 800_000.0);
+
+    let changes = diff_listing(&original, &restyled, &DiffOptions::default());
+    assert_eq!(
+        changes,
+        [ListingChange::FieldChanged {
+            mls_number: "C1".to_string(),
+            field: "details.style".to_string(),
+            old: json!(null),
+            new: json!("Bungalow"),
+        }]
+    );
+
+    let options = DiffOptions::default().ignore("details");
+    assert!(diff_listing(&original, &restyled, &options).is_empty());
+    let options = DiffOptions::default().field_changes(false);
+    assert!(diff_listing(&original, &restyled, &options).is_empty());
+}
@@ -0,0 +1,292 @@
+//! Client retry, rate-limit and error handling driven through the fake server
+
+use repliers_beta::testing::{synthetic_listings, FakeRepliers, Fault};
+use repliers_beta::{ListingSearchRequest, RateLimit, RepliersClient, RepliersError, RetryPolicy};
+use std::num::NonZeroU32;
+use std::time::{Duration, Instant};
+
+/// Starts a fake server holding a few listings, returning it with one of their MLS numbers
+async fn server() -> (FakeRepliers, String) {
+    let fake = FakeRepliers::start().await.unwrap();
+    let listings = synthetic_listings(5, 7);
+    let mls_number = listings[0].mls_number.clone();
+    fake.insert_listings(listings);
+    (fake, mls_number)
+}
+
+/// A retry policy with short, deterministic delays
+fn fast_retries(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy::default()
+        .max_attempts(max_attempts)
+        .base_delay(Duration::from_millis(1))
+        .jitter(false)
+}
+
+fn client(fake: &FakeRepliers, policy: RetryPolicy) -> RepliersClient {
+    fake.client_builder().retry_policy(policy).build().unwrap()
+}
+
+#[tokio::test]
+async fn retries_retryable_statuses_until_success() {
+    let (fake, mls_number) = server().await;
+    fake.inject_times(Fault::Status(503), 2);
+
+    let listing = client(&fake, fast_retries(3))
+        .get_listing(&mls_number, None)
+        .await
+        .unwrap();
+
+    assert_eq!(listing.mls_number, mls_number);
+    assert_eq!(fake.request_count(), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let (fake, mls_number) = server().await;
+    fake.inject_times(Fault::Status(500), 5);
+
+    let error = client(&fake, fast_retries(2))
+        .get_listing(&mls_number, None)
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, RepliersError::Server(_)), "{:?}", error);
+    assert_eq!(
+        error.status(),
+        Some(reqwest::StatusCode::INTERNAL_SERVER_ERROR)
+    );
+    assert_eq!(fake.request_count(), 2);
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let (fake, mls_number) = server().await;
+    fake.inject(Fault::Status(400));
+
+    let error = client(&fake, fast_retries(3))
+        .get_listing(&mls_number, None)
+        .await
+        .err()
+        .unwrap();
+
+    assert!(!error.is_retryable());
+    assert_eq!(fake.request_count(), 1);
+}
+
+#[tokio::test]
+async fn search_is_only_retried_when_enabled() {
+    let (fake, _) = server().await;
+    let request = ListingSearchRequest::default();
+
+    fake.inject(Fault::Status(503));
+    let error = client(&fake, fast_retries(3))
+        .search_listings(request.clone())
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(error, RepliersError::Server(_)), "{:?}", error);
+    assert_eq!(fake.request_count(), 1);
+
+    fake.inject(Fault::Status(503));
+    let response = client(&fake, fast_retries(3).retry_search(true))
+        .search_listings(request)
+        .await
+        .unwrap();
+    assert_eq!(response.count, 5);
+    assert_eq!(fake.request_count(), 3);
+}
+
+#[tokio::test]
+async fn rate_limited_response_reports_retry_after() {
+    let (fake, mls_number) = server().await;
+    fake.inject(Fault::RateLimited {
+        retry_after: Some(Duration::from_millis(1_500)),
+    });
+
+    let error = client(&fake, RetryPolicy::none())
+        .get_listing(&mls_number, None)
+        .await
+        .err()
+        .unwrap();
+
+    assert!(error.is_rate_limited());
+    // Rounded up to whole seconds by the server
+    assert_eq!(error.retry_after(), Some(Duration::from_secs(2)));
+}
+
+#[tokio::test]
+async fn rate_limited_retry_waits_for_retry_after() {
+    let (fake, mls_number) = server().await;
+    fake.inject(Fault::RateLimited {
+        retry_after: Some(Duration::from_secs(1)),
+    });
+
+    let started = Instant::now();
+    client(&fake, fast_retries(2))
+        .get_listing(&mls_number, None)
+        .await
+        .unwrap();
+
+    assert!(started.elapsed() >= Duration::from_secs(1));
+    assert_eq!(fake.request_count(), 2);
+}
+
+#[tokio::test]
+async fn rate_limited_retry_without_header_uses_backoff() {
+    let (fake, mls_number) = server().await;
+    fake.inject(Fault::RateLimited { retry_after: None });
+
+    let started = Instant::now();
+    client(&fake, fast_retries(2))
+        .get_listing(&mls_number, None)
+        .await
+        .unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert_eq!(fake.request_count(), 2);
+}
+
+#[tokio::test]
+async fn client_rate_limit_spaces_requests() {
+    let (fake, mls_number) = server().await;
+    let client = fake
+        .client_builder()
+        .rate_limit(RateLimit::per_second(NonZeroU32::new(20).unwrap()).burst(1))
+        .build()
+        .unwrap();
+
+    let started = Instant::now();
+    for _ in 0..5 {
+        client.get_listing(&mls_number, None).await.unwrap();
+    }
+
+    // The first request uses the burst; the other four wait 50ms each
+    assert!(started.elapsed() >= Duration::from_millis(190));
+    assert_eq!(fake.request_count(), 5);
+}
+
+#[tokio::test]
+async fn latency_beyond_the_timeout_is_retried() {
+    let (fake, mls_number) = server().await;
+    fake.inject(Fault::Latency(Duration::from_millis(500)));
+    let client = fake
+        .client_builder()
+        .timeout(Duration::from_millis(100))
+        .retry_policy(fast_retries(2))
+        .build()
+        .unwrap();
+
+    let listing = client.get_listing(&mls_number, None).await.unwrap();
+
+    assert_eq!(listing.mls_number, mls_number);
+    assert_eq!(fake.request_count(), 2);
+}
+
+#[tokio::test]
+async fn latency_beyond_the_timeout_fails_without_retries() {
+    let (fake, mls_number) = server().await;
+    fake.inject(Fault::Latency(Duration::from_millis(500)));
+    let client = fake
+        .client_builder()
+        .timeout(Duration::from_millis(100))
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap();
+
+    let error = client.get_listing(&mls_number, None).await.err().unwrap();
+
+    match error {
+        RepliersError::RequestFailed(e) => assert!(e.is_timeout(), "{:?}", e),
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn latency_within_the_timeout_succeeds() {
+    let (fake, mls_number) = server().await;
+    fake.inject(Fault::Latency(Duration::from_millis(50)));
+
+    let started = Instant::now();
+    client(&fake, RetryPolicy::none())
+        .get_listing(&mls_number, None)
+        .await
+        .unwrap();
+
+    assert!(started.elapsed() >= Duration::from_millis(50));
+    assert_eq!(fake.request_count(), 1);
+}
+
+#[tokio::test]
+async fn malformed_json_is_a_decode_error() {
+    let (fake, mls_number) = server().await;
+    fake.inject(Fault::MalformedJson);
+
+    let error = client(&fake, fast_retries(3))
+        .get_listing(&mls_number, None)
+        .await
+        .err()
+        .unwrap();
+
+    match error {
+        RepliersError::RequestFailed(e) => assert!(e.is_decode(), "{:?}", e),
+        other => panic!("expected a decode error, got {:?}", other),
+    }
+    // A 200 response is never retried, even if its body is broken
+    assert_eq!(fake.request_count(), 1);
+}
+
+#[tokio::test]
+async fn request_log_records_method_path_query_and_body() {
+    let (fake, mls_number) = server().await;
+    let client = fake.client();
+    let board_id = fake
+        .listing(&mls_number)
+        .unwrap()
+        .board_id
+        .unwrap()
+        .to_string();
+
+    client
+        .get_listing(&mls_number, Some(&board_id))
+        .await
+        .unwrap();
+    let request = ListingSearchRequest::builder().city("Toronto").build();
+    client.search_listings(request).await.unwrap();
+
+    let requests = fake.requests();
+    assert_eq!(requests.len(), 2);
+
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].path, format!("/listings/{}", mls_number));
+    assert_eq!(requests[0].query.get("boardId"), Some(&board_id));
+    assert_eq!(requests[0].body, None);
+
+    assert_eq!(requests[1].method, "POST");
+    assert_eq!(requests[1].path, "/listings");
+    let body = requests[1].body.as_ref().unwrap();
+    assert_eq!(body["city"], "Toronto");
+}
+
+#[tokio::test]
+async fn faults_are_consumed_in_order() {
+    let (fake, mls_number) = server().await;
+    fake.inject(Fault::Status(503));
+    fake.inject(Fault::MalformedJson);
+    let client = client(&fake, RetryPolicy::none());
+
+    let first = client.get_listing(&mls_number, None).await.err().unwrap();
+    let second = client.get_listing(&mls_number, None).await.err().unwrap();
+    client.get_listing(&mls_number, None).await.unwrap();
+
+    assert!(matches!(first, RepliersError::Server(_)), "{:?}", first);
+    assert!(
+        matches!(second, RepliersError::RequestFailed(_)),
+        "{:?}",
+        second
+    );
+
+    fake.inject_times(Fault::Status(503), 3);
+    fake.clear_faults();
+    client.get_listing(&mls_number, None).await.unwrap();
+}
@@ -0,0 +1,82 @@
+//! NDJSON export resumption after interrupted writes
+
+use repliers_beta::export::ndjson::{NdjsonReader, NdjsonWriter};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Returns a fresh export path under the system temp directory
+fn export_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("repliers-ndjson-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn read_back(path: &PathBuf) -> Vec<u32> {
+    NdjsonReader::open(path)
+        .unwrap()
+        .records::<u32>()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn gzip_append_drops_a_truncated_last_member() {
+    let path = export_path("records.ndjson.gz");
+    let mut writer = NdjsonWriter::create(&path).unwrap().checkpoint_every(2);
+    writer.write_all(0..5u32).unwrap();
+    writer.finish().unwrap();
+
+    // Cut the last member (record 4) short, as a crash mid-write would
+    let len = std::fs::metadata(&path).unwrap().len();
+    OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_len(len - 4)
+        .unwrap();
+
+    let mut writer = NdjsonWriter::append(&path).unwrap();
+    assert_eq!(writer.existing_records(), 4);
+    writer.write_all(4..6u32).unwrap();
+    writer.finish().unwrap();
+
+    assert_eq!(read_back(&path), [0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn gzip_append_refuses_a_corrupt_file() {
+    let path = export_path("corrupt.ndjson.gz");
+    std::fs::write(
+        &path,
+        [0x1f, 0x8b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+    )
+    .unwrap();
+
+    let error = NdjsonWriter::append(&path).unwrap_err();
+
+    assert!(error.to_string().contains("cannot resume"), "{}", error);
+}
+
+#[test]
+fn plain_append_drops_a_partial_last_line() {
+    let path = export_path("records.ndjson");
+    let mut writer = NdjsonWriter::create(&path).unwrap();
+    writer.write_all(0..3u32).unwrap();
+    writer.finish().unwrap();
+    OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .write_all(b"12")
+        .unwrap();
+
+    let mut writer = NdjsonWriter::append(&path).unwrap();
+    assert_eq!(writer.existing_records(), 3);
+    writer.write(&3u32).unwrap();
+    writer.finish().unwrap();
+
+    assert_eq!(read_back(&path), [0, 1, 2, 3]);
+}
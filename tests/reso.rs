@@ -0,0 +1,84 @@
+//! RESO mapping reports for fields that cannot be carried over
+
+use repliers_beta::reso::{self, ResoProperty, StandardStatus, UnmappedReason};
+use repliers_beta::Listing;
+use serde_json::json;
+
+fn unmapped(report: &reso::MappingReport) -> Vec<(&str, UnmappedReason)> {
+    report
+        .unmapped
+        .iter()
+        .map(|field| (field.field.as_str(), field.reason))
+        .collect()
+}
+
+#[test]
+fn fully_mapped_listings_have_a_complete_report() {
+    let listing: Listing = serde_json::from_value(json!({
+        "mlsNumber": "C1234567",
+        "status": "A",
+        "lastStatus": "New",
+        "class": "ResidentialProperty",
+        "type": "Sale",
+        "listPrice": 899000
+    }))
+    .unwrap();
+
+    let mapped = reso::to_reso(&listing);
+
+    assert!(mapped.report.is_complete(), "{:?}", mapped.report);
+    assert_eq!(mapped.record.standard_status, Some(StandardStatus::Active));
+}
+
+#[test]
+fn reports_board_fields_unknown_lookups_and_invalid_values() {
+    let listing: Listing = serde_json::from_value(json!({
+        "mlsNumber": "C1234567",
+        "status": "A",
+        "lastStatus": "Zzz",
+        "class": "HouseboatProperty",
+        "listDate": "someday",
+        "boardRegion": "GTA",
+        "details": {"waterfront": "Y"}
+    }))
+    .unwrap();
+
+    let mapped = reso::to_reso(&listing);
+
+    assert_eq!(
+        unmapped(&mapped.report),
+        [
+            ("lastStatus", UnmappedReason::UnknownLookup),
+            ("class", UnmappedReason::UnknownLookup),
+            ("listDate", UnmappedReason::InvalidValue),
+            ("details.waterfront", UnmappedReason::NoEquivalent),
+            ("boardRegion", UnmappedReason::NoEquivalent),
+        ]
+    );
+    assert_eq!(mapped.report.unmapped[4].value, json!("GTA"));
+    assert_eq!(mapped.record.standard_status, None);
+}
+
+#[test]
+fn reports_reso_fields_without_a_repliers_equivalent() {
+    let property: ResoProperty = serde_json::from_value(json!({
+        "ListingId": "C1234567",
+        "OriginatingSystemID": "TRREB",
+        "PropertySubType": "Houseboat",
+        "ElementarySchool": "Maple Leaf PS"
+    }))
+    .unwrap();
+
+    let mapped = reso::from_reso(&property);
+
+    assert_eq!(
+        unmapped(&mapped.report),
+        [
+            ("OriginatingSystemID", UnmappedReason::InvalidValue),
+            ("PropertySubType", UnmappedReason::UnknownLookup),
+            ("ElementarySchool", UnmappedReason::NoEquivalent),
+        ]
+    );
+    assert_eq!(mapped.record.mls_number, "C1234567");
+    assert_eq!(mapped.record.board_id, None);
+}
@@ -0,0 +1,210 @@
+//! SQLite listing store: schema migration, upserts and change history
+
+use repliers_beta::store::{ListingStore, UpsertOutcome};
+use repliers_beta::{
+    Address, DeletedListing, DeletedListingTimestamps, Listing, ListingSearchRequest,
+    ListingTimestamps, RepliersError,
+};
+use std::path::PathBuf;
+
+fn listing(board_id: Option<u32>, price: f64, updated: &str) -> Listing {
+    Listing {
+        mls_number: "C1234567".to_string(),
+        board_id,
+        status: Some("A".to_string()),
+        last_status: Some("New".to_string()),
+        list_price: Some(price),
+        timestamps: Some(ListingTimestamps {
+            listing_updated: Some(updated.to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn deletion(board_id: u32, deleted_at: &str) -> DeletedListing {
+    DeletedListing {
+        board_id,
+        mls_number: "C1234567".to_string(),
+        resource: "Property".to_string(),
+        address: Address::default(),
+        timestamps: DeletedListingTimestamps {
+            listing_updated: deleted_at.to_string(),
+        },
+    }
+}
+
+/// Returns a fresh database path under the system temp directory
+fn store_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("repliers-store-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{}.sqlite", name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn migrates_new_stores_and_keeps_data_across_reopens() {
+    assert_eq!(
+        ListingStore::open_in_memory()
+            .unwrap()
+            .schema_version()
+            .unwrap(),
+        1
+    );
+
+    let path = store_path("reopen");
+    let store = ListingStore::open(&path).unwrap();
+    store
+        .upsert_listing(&listing(None, 899_000.0, "2025-03-01T12:00:00Z"))
+        .unwrap();
+    drop(store);
+
+    let store = ListingStore::open(&path).unwrap();
+    assert_eq!(store.schema_version().unwrap(), 1);
+    assert_eq!(store.count().unwrap(), 1);
+    assert_eq!(
+        store
+            .get_listing(None, "C1234567")
+            .unwrap()
+            .unwrap()
+            .board_id,
+        None
+    );
+}
+
+#[test]
+fn refuses_a_schema_newer_than_it_supports() {
+    let path = store_path("newer");
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.pragma_update(None, "user_version", 99).unwrap();
+    drop(conn);
+
+    let error = ListingStore::open(&path).err().unwrap();
+
+    assert!(matches!(error, RepliersError::Storage(_)), "{:?}", error);
+    assert!(
+        error.to_string().contains("newer than supported"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn upserts_report_inserted_updated_and_unchanged() {
+    let store = ListingStore::open_in_memory().unwrap();
+    let original = listing(Some(1), 899_000.0, "2025-03-01T12:00:00Z");
+
+    assert_eq!(
+        store.upsert_listing(&original).unwrap(),
+        UpsertOutcome::Inserted
+    );
+    assert_eq!(
+        store.upsert_listing(&original).unwrap(),
+        UpsertOutcome::Unchanged
+    );
+
+    let mut described = original.clone();
+    described
+        .extra
+        .insert("virtualTour".to_string(), "https://example.com".into());
+    assert_eq!(
+        store.upsert_listing(&described).unwrap(),
+        UpsertOutcome::Updated
+    );
+    assert_eq!(store.count().unwrap(), 1);
+
+    // The same MLS number on another board is a separate listing
+    assert_eq!(
+        store
+            .upsert_listing(&listing(Some(2), 1.0, "2025-03-01T12:00:00Z"))
+            .unwrap(),
+        UpsertOutcome::Inserted
+    );
+    assert_eq!(store.count().unwrap(), 2);
+}
+
+#[test]
+fn history_records_inserts_price_and_status_changes_and_deletions() {
+    let store = ListingStore::open_in_memory().unwrap();
+    store
+        .upsert_listing(&listing(Some(1), 899_000.0, "2025-03-01T12:00:00Z"))
+        .unwrap();
+
+    // A change to another field adds no history
+    let mut described = listing(Some(1), 899_000.0, "2025-03-02T12:00:00Z");
+    described
+        .extra
+        .insert("virtualTour".to_string(), "https://example.com".into());
+    store.upsert_listing(&described).unwrap();
+
+    store
+        .upsert_listing(&listing(Some(1), 849_000.0, "2025-03-03T12:00:00Z"))
+        .unwrap();
+    let mut sold = listing(Some(1), 849_000.0, "2025-03-04T12:00:00Z");
+    sold.status = Some("U".to_string());
+    sold.last_status = Some("Sld".to_string());
+    store.upsert_listing(&sold).unwrap();
+    assert!(store
+        .record_deletion(&deletion(1, "2025-03-05T12:00:00Z"))
+        .unwrap());
+
+    let history = store.history(Some(1), "C1234567").unwrap();
+    let rows: Vec<_> = history
+        .iter()
+        .map(|r| (r.list_price, r.last_status.as_deref(), r.deleted))
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            (Some(899_000.0), Some("New"), false),
+            (Some(849_000.0), Some("New"), false),
+            (Some(849_000.0), Some("Sld"), false),
+            (None, None, true),
+        ]
+    );
+    assert_eq!(
+        history[1].recorded_at.to_rfc3339(),
+        "2025-03-03T12:00:00+00:00"
+    );
+    assert_eq!(store.count().unwrap(), 0);
+    assert_eq!(store.deleted_listings().unwrap().len(), 1);
+}
+
+#[test]
+fn deletions_on_any_board_remove_listings_without_a_board() {
+    let store = ListingStore::open_in_memory().unwrap();
+    store
+        .upsert_listing(&listing(None, 899_000.0, "2025-03-01T12:00:00Z"))
+        .unwrap();
+
+    assert!(store
+        .record_deletion(&deletion(7, "2025-03-02T12:00:00Z"))
+        .unwrap());
+
+    assert_eq!(store.count().unwrap(), 0);
+    let history = store.history(None, "C1234567").unwrap();
+    assert!(history.last().unwrap().deleted);
+
+    // Reappearing clears the deletion record
+    store
+        .upsert_listing(&listing(None, 899_000.0, "2025-03-03T12:00:00Z"))
+        .unwrap();
+    assert!(store.deleted_listings().unwrap().is_empty());
+}
+
+#[test]
+fn search_matches_cities_regardless_of_surrounding_whitespace() {
+    let store = ListingStore::open_in_memory().unwrap();
+    let mut stored = listing(Some(1), 899_000.0, "2025-03-01T12:00:00Z");
+    stored.address = Some(Address {
+        city: Some("Toronto ".to_string()),
+        ..Default::default()
+    });
+    store.upsert_listing(&stored).unwrap();
+
+    let request = ListingSearchRequest::builder().city(" toronto").build();
+
+    assert_eq!(store.search(&request).unwrap().count, 1);
+    assert!(request.matches(&stored));
+}
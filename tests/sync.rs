@@ -0,0 +1,171 @@
+//! Incremental sync runs against the in-memory API
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use repliers_beta::sync::{MemoryWatermarkStore, SyncEngine, SyncEvent, SyncOptions, SyncSink};
+use repliers_beta::{InMemoryApi, Listing, RepliersError};
+
+/// Records every batch it is handed, optionally changing the API after the first one
+#[derive(Default)]
+struct RecordingSink {
+    batches: Vec<Vec<SyncEvent>>,
+    after_first_batch: Option<(InMemoryApi, Listing)>,
+}
+
+#[async_trait]
+impl SyncSink for RecordingSink {
+    async fn apply(&mut self, events: &[SyncEvent]) -> Result<(), RepliersError> {
+        self.batches.push(events.to_vec());
+        if let Some((api, listing)) = self.after_first_batch.take() {
+            api.insert_listing(listing);
+        }
+        Ok(())
+    }
+}
+
+impl RecordingSink {
+    /// Events applied so far, as `+MLS` for upserts and `-MLS` for deletions
+    fn applied(&self) -> Vec<String> {
+        self.batches
+            .iter()
+            .flatten()
+            .map(|event| match event {
+                SyncEvent::Upsert(listing) => format!("+{}", listing.mls_number),
+                SyncEvent::Delete(deleted) => format!("-{}", deleted.mls_number),
+            })
+            .collect()
+    }
+}
+
+/// `minutes` after noon on a fixed day
+fn at(minutes: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap() + Duration::minutes(minutes)
+}
+
+fn listing(mls_number: &str, updated_minutes: i64) -> Listing {
+    Listing {
+        mls_number: mls_number.to_string(),
+        board_id: Some(1),
+        updated_on: Some(at(updated_minutes).to_rfc3339()),
+        ..Default::default()
+    }
+}
+
+fn engine(
+    api: &InMemoryApi,
+    lookback: Duration,
+) -> SyncEngine<RecordingSink, MemoryWatermarkStore, InMemoryApi> {
+    engine_with_sink(api, RecordingSink::default(), lookback)
+}
+
+fn engine_with_sink(
+    api: &InMemoryApi,
+    sink: RecordingSink,
+    lookback: Duration,
+) -> SyncEngine<RecordingSink, MemoryWatermarkStore, InMemoryApi> {
+    SyncEngine::new(api.clone(), sink, MemoryWatermarkStore::default()).with_options(SyncOptions {
+        lookback,
+        batch_size: 2,
+        page_size: 2,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn first_run_applies_everything_in_update_order() {
+    let api = InMemoryApi::new();
+    api.insert_listings([listing("C3", 30), listing("C1", 10), listing("C2", 20)]);
+    let mut engine = engine(&api, Duration::zero());
+
+    let report = engine.run().await.unwrap();
+
+    assert_eq!(engine.sink().applied(), ["+C1", "+C2", "+C3"]);
+    assert_eq!(report.previous_watermark, None);
+    assert_eq!(report.watermark, Some(at(30)));
+    assert_eq!(report.upserts, 3);
+    assert_eq!(report.batches, 2);
+}
+
+#[tokio::test]
+async fn watermark_advances_between_runs() {
+    let api = InMemoryApi::new();
+    api.insert_listings([listing("C1", 10), listing("C2", 20)]);
+    let mut engine = engine(&api, Duration::zero());
+    engine.run().await.unwrap();
+
+    api.insert_listing(listing("C3", 40));
+    let report = engine.run().await.unwrap();
+
+    // The listing at the watermark itself is replayed; older ones are not
+    assert_eq!(engine.sink().applied()[2..], ["+C2", "+C3"]);
+    assert_eq!(report.previous_watermark, Some(at(20)));
+    assert_eq!(report.watermark, Some(at(40)));
+}
+
+#[tokio::test]
+async fn lookback_replays_the_window_before_the_watermark() {
+    let api = InMemoryApi::new();
+    api.insert_listings([listing("C1", 10), listing("C2", 20), listing("C3", 30)]);
+    let mut engine = engine(&api, Duration::minutes(15));
+    engine.run().await.unwrap();
+
+    engine.run().await.unwrap();
+
+    assert_eq!(engine.sink().applied()[3..], ["+C2", "+C3"]);
+}
+
+#[tokio::test]
+async fn deletions_come_first_and_suppress_upserts_of_the_same_listing() {
+    let api = InMemoryApi::new();
+    api.insert_listings([listing("C1", 10), listing("C2", 20)]);
+    // Deleted twice, and still returned by the search
+    api.delete_listing("C2", at(25));
+    api.insert_listing(listing("C2", 20));
+    api.delete_listing("C2", at(26));
+    api.insert_listing(listing("C2", 20));
+    let mut engine = engine(&api, Duration::zero());
+
+    let report = engine.run().await.unwrap();
+
+    assert_eq!(engine.sink().applied(), ["-C2", "+C1"]);
+    assert_eq!(report.deletes, 1);
+    assert_eq!(report.upserts, 1);
+    // Deletions carry no timestamp of their own, so only the upsert moves the watermark
+    assert_eq!(report.watermark, Some(at(10)));
+}
+
+#[tokio::test]
+async fn pages_shifting_mid_run_never_apply_a_listing_twice() {
+    let api = InMemoryApi::new();
+    api.insert_listings((1..=5).map(|i| listing(&format!("C{}", i), i * 10)));
+    // A listing that sorts first appears after the first page was read, shifting every
+    // later page forward by one
+    let sink = RecordingSink {
+        after_first_batch: Some((api.clone(), listing("C0", 5))),
+        ..Default::default()
+    };
+    let mut engine = engine_with_sink(&api, sink, Duration::zero());
+
+    let report = engine.run().await.unwrap();
+
+    assert_eq!(engine.sink().applied(), ["+C1", "+C2", "+C3", "+C4", "+C5"]);
+    assert_eq!(report.upserts, 5);
+    assert_eq!(report.watermark, Some(at(50)));
+}
+
+#[tokio::test]
+async fn lookback_recovers_a_listing_skipped_by_an_earlier_run() {
+    let api = InMemoryApi::new();
+    api.insert_listings((1..=3).map(|i| listing(&format!("C{}", i), i * 10)));
+    let sink = RecordingSink {
+        after_first_batch: Some((api.clone(), listing("C0", 5))),
+        ..Default::default()
+    };
+    let mut engine = engine_with_sink(&api, sink, Duration::hours(1));
+
+    engine.run().await.unwrap();
+    assert!(!engine.sink().applied().contains(&"+C0".to_string()));
+
+    engine.run().await.unwrap();
+    assert!(engine.sink().applied().contains(&"+C0".to_string()));
+}
@@ -0,0 +1,149 @@
+//! Record/replay transport tests against a local mock server
+
+use repliers_beta::transport::{
+    Cassette, HttpTransport, RecordingTransport, ReplayTransport, REDACTED,
+};
+use repliers_beta::{ListingSearchRequest, RepliersClient, RepliersError};
+use reqwest::header::{HeaderName, HeaderValue};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+const API_KEY: &str = "test-secret-key";
+
+fn search_body() -> Value {
+    json!({
+        "listings": [{ "mlsNumber": "C1234567", "listPrice": 899000.0 }],
+        "page": 1,
+        "numPages": 1,
+        "pageSize": 1,
+        "count": 1,
+        "echo": API_KEY,
+    })
+}
+
+/// Returns a fresh cassette path under the system temp directory
+fn cassette_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("repliers-transport-{}", std::process::id()));
+    let path = dir.join(format!("{}.json", name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn request() -> ListingSearchRequest {
+    ListingSearchRequest::builder().city("Toronto").build()
+}
+
+#[tokio::test]
+async fn records_with_the_client_settings_and_replays_offline() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/listings")
+        .match_header("x-test", "recorded")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(search_body().to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    let path = cassette_path("round_trip");
+
+    let client = RepliersClient::builder()
+        .api_key(API_KEY)
+        .base_url(server.url())
+        .default_header(
+            HeaderName::from_static("x-test"),
+            HeaderValue::from_static("recorded"),
+        )
+        .record(&path)
+        .build()
+        .unwrap();
+    let live = client.search_listings(request()).await.unwrap();
+    assert!(
+        !path.exists(),
+        "cassette is written when the client is dropped"
+    );
+    drop(client);
+    assert!(path.exists());
+
+    let client = RepliersClient::builder()
+        .api_key("replay")
+        .base_url("http://replay.invalid")
+        .transport(ReplayTransport::from_file(&path).unwrap())
+        .build()
+        .unwrap();
+    let replayed = client.search_listings(request()).await.unwrap();
+
+    assert_eq!(replayed.count, live.count);
+    assert_eq!(replayed.listings, live.listings);
+    mock.assert_async().await;
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn unmatched_request_is_an_error() {
+    let client = RepliersClient::builder()
+        .api_key("replay")
+        .transport(ReplayTransport::new(Cassette::default()))
+        .build()
+        .unwrap();
+
+    let error = client.get_listing("N999", None).await.err().unwrap();
+
+    match error {
+        RepliersError::InvalidRequest(message) => {
+            assert_eq!(message, "No recorded interaction for GET /listings/N999");
+        }
+        other => panic!("expected InvalidRequest, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn cassette_file_format_is_stable_and_scrubbed() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/listings")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("set-cookie", "session=1")
+        .with_body(search_body().to_string())
+        .create_async()
+        .await;
+    let path = cassette_path("format");
+
+    let recorder = RecordingTransport::wrap(HttpTransport::default(), &path);
+    let client = RepliersClient::builder()
+        .api_key(API_KEY)
+        .base_url(server.url())
+        .transport(recorder.clone())
+        .build()
+        .unwrap();
+    client.search_listings(request()).await.unwrap();
+    recorder.save().unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(!text.contains(API_KEY), "API key leaked into the cassette");
+
+    let file: Value = serde_json::from_str(&text).unwrap();
+    let interactions = file["interactions"].as_array().unwrap();
+    assert_eq!(interactions.len(), 1);
+
+    let interaction = &interactions[0];
+    assert_eq!(interaction["request"]["method"], "POST");
+    assert_eq!(interaction["request"]["path"], "/listings");
+    assert_eq!(interaction["request"]["body"]["city"], "Toronto");
+    assert_eq!(interaction["response"]["status"], 200);
+    assert_eq!(
+        interaction["response"]["headers"]["content-type"],
+        "application/json"
+    );
+    assert!(interaction["response"]["headers"]
+        .get("set-cookie")
+        .is_none());
+    assert_eq!(interaction["response"]["body"]["count"], 1);
+    assert_eq!(interaction["response"]["body"]["echo"], REDACTED);
+
+    // The same format written by hand loads and replays
+    let cassette: Cassette = serde_json::from_value(file).unwrap();
+    assert_eq!(cassette, recorder.cassette());
+    let _ = std::fs::remove_file(&path);
+}